@@ -8,6 +8,8 @@ usage: render_dropin.sh <service-name> [options]
 Notes:
   - <service-name> may be given with or without the .service suffix.
   - Map file defaults to services/<name>.conf (suffix stripped).
+  - `@include <path>` lines pull in other maps (relative to the including
+    file, globs allowed), as in goamet-vault.
 
 Options:
   --map-file PATH   Map file to read (default: services/<service>.conf)
@@ -75,11 +77,31 @@ if [ ! -f "$MAP_FILE" ]; then
   exit 1
 fi
 
-mkdir -p "$OUT_DIR"
-OUT_FILE="$OUT_DIR/credentials.conf"
+# Same limit as service_map::MAX_INCLUDE_DEPTH.
+MAX_INCLUDE_DEPTH=8
 
-{
-  echo "[Service]"
+# render_map <file> <stack>: print the drop-in lines for <file>, expanding
+# `@include <path>` (relative to the including file, globs in sorted order)
+# like goamet-vault does. <stack> holds the resolved files being rendered,
+# one per line, for cycle and depth checks.
+render_map() {
+  local file="$1" stack="$2"
+  local real depth
+  real="$(realpath -e "$file")" || { echo "map file not found: $file" >&2; return 1; }
+  if printf '%s\n' "$stack" | grep -Fxq -- "$real"; then
+    echo "include cycle: $(printf '%s' "$stack" | tr '\n' ' ')-> $real" >&2
+    return 1
+  fi
+  depth="$(printf '%s' "$stack" | grep -c '^' || true)"
+  if [ "$depth" -gt "$MAX_INCLUDE_DEPTH" ]; then
+    echo "include depth exceeds $MAX_INCLUDE_DEPTH in $file" >&2
+    return 1
+  fi
+  stack="$stack$real"$'\n'
+
+  local line raw env_var name cred_path target pattern included
+  local base_dir
+  base_dir="$(dirname "$file")"
   while IFS= read -r line || [ -n "$line" ]; do
     line="${line%%#*}"
     if [ -z "${line//[[:space:]]/}" ]; then
@@ -89,6 +111,36 @@ OUT_FILE="$OUT_DIR/credentials.conf"
     raw="$1"
     env_var="${2:-}"
 
+    if [ "$raw" = "@include" ]; then
+      target="${line#*@include}"
+      target="${target#"${target%%[![:space:]]*}"}"
+      target="${target%"${target##*[![:space:]]}"}"
+      if [ -z "$target" ]; then
+        echo "$file: @include requires a path" >&2
+        return 1
+      fi
+      if [[ "$target" != /* ]]; then
+        target="$base_dir/$target"
+      fi
+      if [[ "$target" == *[*?[]* ]]; then
+        local -a matches=()
+        pattern="$target"
+        while IFS= read -r included; do
+          [ -f "$included" ] && matches+=("$included")
+        done < <(shopt -s nullglob; for m in $pattern; do printf '%s\n' "$m"; done | LC_ALL=C sort)
+        for included in ${matches[@]+"${matches[@]}"}; do
+          render_map "$included" "$stack" || return 1
+        done
+      else
+        render_map "$target" "$stack" || return 1
+      fi
+      continue
+    fi
+    if [[ "$raw" == @* ]]; then
+      echo "$file: unknown directive $raw" >&2
+      return 1
+    fi
+
     if [[ "$raw" == *:* ]]; then
       name="${raw%%:*}"
       cred_path="${raw#*:}"
@@ -101,8 +153,15 @@ OUT_FILE="$OUT_DIR/credentials.conf"
     if [ $NO_ENV -eq 0 ] && [ -n "$env_var" ]; then
       echo "Environment=$env_var=%d/$name"
     fi
-  done < "$MAP_FILE"
-} > "$OUT_FILE"
+  done < "$file"
+}
+
+# Render fully before writing, so a bad include leaves no partial drop-in.
+body="$(render_map "$MAP_FILE" "")"
+
+mkdir -p "$OUT_DIR"
+OUT_FILE="$OUT_DIR/credentials.conf"
+printf '[Service]\n%s\n' "$body" > "$OUT_FILE"
 
 echo "Wrote $OUT_FILE"
 
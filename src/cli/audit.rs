@@ -1,9 +1,17 @@
 use crate::cli::CliContext;
-use crate::core::audit_log;
-use anyhow::Result;
+use crate::core::audit_log::{self, AuditEntry};
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use chrono::{DateTime, Local};
 use clap::{Args, Subcommand};
-use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Table};
+use comfy_table::{Attribute, Cell};
+use rand::RngCore;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Subcommand, Debug)]
 pub enum AuditCommand {
@@ -11,6 +19,12 @@ pub enum AuditCommand {
     Log(AuditLogArgs),
     /// Verify audit chain integrity
     Verify(AuditVerifyArgs),
+    /// Summarize activity per action, actor, and credential
+    Stats(AuditStatsArgs),
+    /// Rotate audit.log when it exceeds a size, keeping the chain intact
+    Rotate(AuditRotateArgs),
+    /// Generate an Ed25519 key pair for signing the audit log
+    Keygen(AuditKeygenArgs),
 }
 
 #[derive(Args, Debug)]
@@ -18,28 +32,202 @@ pub struct AuditLogArgs {
     /// Maximum number of entries to display
     #[arg(long, default_value_t = 50)]
     pub limit: usize,
+
+    /// Keep running and print new entries as they are appended (Ctrl-C to stop)
+    #[arg(long, short = 'f')]
+    pub follow: bool,
+
+    /// Only show entries appended since the last `--new` run (bookmark in
+    /// audit.seen); --limit does not apply
+    #[arg(long, alias = "since-last-verify", conflicts_with = "follow")]
+    pub new: bool,
+
+    /// With --new, leave the bookmark where it is
+    #[arg(long, requires = "new")]
+    pub peek: bool,
+
+    /// Output format (table|json|ndjson); --follow prints ndjson as lines arrive
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Args, Debug)]
+pub struct AuditVerifyArgs {
+    /// Only verify entries appended since the last checkpoint
+    #[arg(long, conflicts_with = "full")]
+    pub incremental: bool,
+
+    /// Re-verify the whole log, ignoring any checkpoint (default)
+    #[arg(long)]
+    pub full: bool,
+
+    /// Also verify rotated logs (audit.log.N ... audit.log.1) as one chain
+    #[arg(long, alias = "all", conflicts_with = "incremental")]
+    pub all_files: bool,
+
+    /// Also check each entry's Ed25519 signature against this public key
+    #[arg(long, value_name = "PATH")]
+    pub pubkey: Option<PathBuf>,
+
+    /// Output format (text|json)
+    #[arg(long, default_value = "text")]
+    pub format: String,
 }
 
 #[derive(Args, Debug)]
-pub struct AuditVerifyArgs {}
+pub struct AuditRotateArgs {
+    /// Rotate only when audit.log is larger than this many bytes
+    #[arg(long, value_name = "BYTES", default_value_t = 10 * 1024 * 1024)]
+    pub max_size: u64,
+
+    /// Number of rotated files to keep
+    #[arg(long, value_name = "N", default_value_t = 5, value_parser = clap::value_parser!(u16).range(1..))]
+    pub keep: u16,
+}
+
+#[derive(Args, Debug)]
+pub struct AuditKeygenArgs {
+    /// Private key file to create; the public key goes to <PATH>.pub
+    #[arg(long, value_name = "PATH")]
+    pub out: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct AuditStatsArgs {
+    /// Output format (text|json)
+    #[arg(long, default_value = "text")]
+    pub format: String,
+}
 
 pub fn run(ctx: &CliContext, cmd: AuditCommand) -> Result<()> {
     match cmd {
         AuditCommand::Log(args) => run_log(ctx, args),
-        AuditCommand::Verify(_) => run_verify(ctx),
+        AuditCommand::Verify(args) => run_verify(ctx, args),
+        AuditCommand::Stats(args) => run_stats(ctx, args),
+        AuditCommand::Rotate(args) => run_rotate(ctx, args),
+        AuditCommand::Keygen(args) => run_keygen(args),
     }
 }
 
 fn run_log(ctx: &CliContext, args: AuditLogArgs) -> Result<()> {
+    if !["table", "json", "ndjson"].contains(&args.format.as_str()) {
+        bail!("invalid format: {} (use table|json|ndjson)", args.format);
+    }
+    if args.follow && args.format == "json" {
+        bail!("--follow cannot produce a JSON array; use --format ndjson");
+    }
+    if args.new {
+        return run_log_new(ctx, args.peek, &args.format);
+    }
+    // Position the follower before reading so nothing appended in between is lost.
+    let follower = if args.follow {
+        Some(audit_log::AuditFollower::from_end(&ctx.paths)?)
+    } else {
+        None
+    };
     let entries = audit_log::read_log(&ctx.paths, Some(args.limit))?;
 
-    if entries.is_empty() {
+    if args.format != "table" {
+        print_json(&entries, &args.format)?;
+    } else if entries.is_empty() {
         println!("No audit entries found.");
+    } else if follower.is_some() {
+        // A pager would hold the output until it exits; never page while following.
+        print!("{}", render_table(ctx, &entries));
+    } else {
+        ctx.page(&render_table(ctx, &entries));
+    }
+
+    if let Some(follower) = follower {
+        follow(follower, args.format == "ndjson")?;
+    }
+    Ok(())
+}
+
+/// Print entries as a pretty JSON array, or one compact object per line.
+fn print_json(entries: &[AuditEntry], format: &str) -> Result<()> {
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(entries).context("serialize audit entries")?);
+        return Ok(());
+    }
+    for entry in entries {
+        println!("{}", serde_json::to_string(entry).context("serialize audit entry")?);
+    }
+    Ok(())
+}
+
+fn run_log_new(ctx: &CliContext, peek: bool, format: &str) -> Result<()> {
+    let bookmark = audit_log::read_seen(&ctx.paths)?;
+    let new = audit_log::entries_since(&ctx.paths, bookmark.as_deref())?;
+    if new.bookmark_missing {
+        eprintln!("Bookmark not found in audit.log (rotated?); showing all entries.");
+    }
+
+    if format != "table" {
+        print_json(&new.entries, format)?;
+    }
+    let Some(last) = new.entries.last() else {
+        if format == "table" {
+            println!("No new audit entries.");
+        }
         return Ok(());
+    };
+    if format == "table" {
+        ctx.page(&render_table(ctx, &new.entries));
     }
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL);
+    if !peek {
+        if let Some(ref hash) = last.entry_hash {
+            audit_log::write_seen(&ctx.paths, hash)?;
+        }
+    }
+    Ok(())
+}
+
+fn follow(mut follower: audit_log::AuditFollower, ndjson: bool) -> Result<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler_stop = Arc::clone(&stop);
+    ctrlc::set_handler(move || handler_stop.store(true, Ordering::SeqCst))
+        .context("install Ctrl-C handler")?;
+
+    if !ndjson {
+        println!("\nFollowing audit log (Ctrl-C to stop)...");
+    }
+    while !stop.load(Ordering::SeqCst) {
+        for entry in follower.poll()? {
+            if ndjson {
+                println!("{}", serde_json::to_string(&entry).context("serialize audit entry")?);
+            } else {
+                println!("{}", format_entry_line(&entry));
+            }
+        }
+        std::thread::sleep(FOLLOW_POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+fn result_label(entry: &AuditEntry) -> String {
+    match &entry.result {
+        Some(r) if r.success => "OK".to_string(),
+        Some(r) => format!("FAIL: {}", r.error.as_deref().unwrap_or("?")),
+        None => "-".to_string(),
+    }
+}
+
+fn format_entry_line(entry: &AuditEntry) -> String {
+    let local: DateTime<Local> = entry.timestamp.into();
+    format!(
+        "{}  {}  {}  {}  {}",
+        local.format("%Y-%m-%d %H:%M:%S"),
+        entry.action,
+        entry.credential,
+        entry.actor,
+        result_label(entry)
+    )
+}
+
+fn render_table(ctx: &CliContext, entries: &[AuditEntry]) -> String {
+    let mut table = ctx.table();
     table.set_header(vec![
         Cell::new("Timestamp").add_attribute(Attribute::Bold),
         Cell::new("Action").add_attribute(Attribute::Bold),
@@ -48,49 +236,209 @@ fn run_log(ctx: &CliContext, args: AuditLogArgs) -> Result<()> {
         Cell::new("Result").add_attribute(Attribute::Bold),
     ]);
 
-    for entry in &entries {
+    for entry in entries {
         let local: DateTime<Local> = entry.timestamp.into();
-        let result_str = match &entry.result {
-            Some(r) if r.success => "OK".to_string(),
-            Some(r) => format!("FAIL: {}", r.error.as_deref().unwrap_or("?")),
-            None => "-".to_string(),
-        };
         table.add_row(vec![
             local.format("%Y-%m-%d %H:%M:%S").to_string(),
             entry.action.clone(),
             entry.credential.clone(),
             entry.actor.clone(),
-            result_str,
+            result_label(entry),
         ]);
     }
 
-    println!("{}", table);
-    println!("\n{} entries shown.", entries.len());
-    Ok(())
+    format!("{}\n\n{} entries shown.\n", table, entries.len())
 }
 
-fn run_verify(ctx: &CliContext) -> Result<()> {
-    let (total, errors) = audit_log::verify_chain(&ctx.paths)?;
+fn run_verify(ctx: &CliContext, args: AuditVerifyArgs) -> Result<()> {
+    if args.format != "text" && args.format != "json" {
+        bail!("invalid format: {} (use text|json)", args.format);
+    }
+
+    let checkpoint = if args.incremental {
+        let checkpoint = audit_log::read_checkpoint(&ctx.paths)?;
+        if checkpoint.is_none() && args.format == "text" {
+            println!("No checkpoint found; verifying the full log.");
+        }
+        checkpoint
+    } else {
+        None
+    };
+    let mut report = if args.all_files {
+        audit_log::verify_chain_all(&ctx.paths)?
+    } else {
+        audit_log::verify_chain_from(&ctx.paths, checkpoint.as_ref())?
+    };
+    if let Some(ref pubkey) = args.pubkey {
+        let key = audit_log::load_verifying_key(pubkey)?;
+        audit_log::verify_report_signatures(&ctx.paths, &mut report, &key, args.all_files)?;
+    }
+
+    if let Some(ref next) = report.checkpoint {
+        if checkpoint.as_ref() != Some(next) {
+            if let Err(e) = audit_log::write_checkpoint(&ctx.paths, next) {
+                eprintln!("warning: could not save audit checkpoint: {:#}", e);
+            }
+        }
+    }
 
-    if total == 0 {
+    if args.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.issues.is_empty() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if report.total == 0 {
         println!("No audit entries to verify.");
         return Ok(());
     }
 
-    for err in &errors {
-        println!("  [FAIL] {}", err);
+    for segment in &report.segments {
+        let status = if segment.issues == 0 { "PASS" } else { "FAIL" };
+        println!(
+            "  [{}] {}: {} entries, {} errors",
+            status, segment.file, segment.entries, segment.issues
+        );
+    }
+    for issue in &report.issues {
+        println!("  [FAIL] {}", issue);
+    }
+    if let Some(verified) = report.signatures_verified {
+        let status = if report.issues.is_empty() { "PASS" } else { "INFO" };
+        println!("  [{}] {} entries carry a valid Ed25519 signature", status, verified);
+    }
+    if let Some(predating) = report.signatures_predating.filter(|&n| n > 0) {
+        println!(
+            "  [INFO] {} older entries predate the first signed entry (not signature-checked)",
+            predating
+        );
     }
 
     println!();
-    if errors.is_empty() {
-        println!("Audit chain: {} entries verified, 0 errors", total);
+    if report.issues.is_empty() && report.skipped > 0 {
+        println!(
+            "Audit chain: {} new entries verified after checkpoint at entry {}, 0 errors",
+            report.total - report.skipped,
+            report.skipped
+        );
+    } else if report.issues.is_empty() && report.segments.len() > 1 {
+        println!(
+            "Audit chain: {} entries across {} files verified, 0 errors",
+            report.total,
+            report.segments.len()
+        );
+    } else if report.issues.is_empty() {
+        println!("Audit chain: {} entries verified, 0 errors", report.total);
     } else {
         println!(
             "Audit chain: {} entries, {} errors",
-            total,
-            errors.len()
+            report.total,
+            report.issues.len()
         );
         std::process::exit(1);
     }
     Ok(())
 }
+
+fn run_rotate(ctx: &CliContext, args: AuditRotateArgs) -> Result<()> {
+    let Some(rotated) = audit_log::rotate_log(&ctx.paths, args.max_size, args.keep as usize)? else {
+        println!("audit.log is within {} bytes; nothing to rotate.", args.max_size);
+        return Ok(());
+    };
+
+    // First entry of the fresh log; it chains from the tail of the rotated file.
+    let mut audit = ctx.audit_ctx("audit-rotate", "audit.log");
+    audit.target_path = Some(rotated.display().to_string());
+    ctx.audit_result(audit, true, None);
+
+    println!("Rotated audit.log to {}", rotated.display());
+    println!("Run 'goamet-vault audit verify --all-files' to check the chain across files.");
+    Ok(())
+}
+
+fn run_keygen(args: AuditKeygenArgs) -> Result<()> {
+    let mut seed = zeroize::Zeroizing::new([0u8; 32]);
+    rand::rngs::OsRng.fill_bytes(seed.as_mut());
+    let key = ed25519_dalek::SigningKey::from_bytes(&seed);
+
+    let pub_path = args.out.with_file_name(format!(
+        "{}.pub",
+        args.out.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let private = zeroize::Zeroizing::new(STANDARD.encode(key.to_bytes()));
+    write_new_file(&args.out, private.as_bytes(), 0o600)?;
+    write_new_file(&pub_path, STANDARD.encode(key.verifying_key().to_bytes()).as_bytes(), 0o644)?;
+
+    println!("Private key: {}", args.out.display());
+    println!("Public key:  {}", pub_path.display());
+    println!();
+    println!("Add to vault.toml to sign new audit entries:");
+    println!("  [audit]");
+    println!("  signing_key_path = \"{}\"", args.out.display());
+    println!("Keep the public key off this host and verify with 'goamet-vault audit verify --pubkey'.");
+    Ok(())
+}
+
+fn write_new_file(path: &std::path::Path, data: &[u8], mode: u32) -> Result<()> {
+    use std::io::Write;
+    #[cfg(unix)]
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(mode)
+        .open(path)
+        .with_context(|| format!("create {}", path.display()))?;
+    file.write_all(data)?;
+    file.write_all(b"\n")?;
+    file.sync_all()?;
+    Ok(())
+}
+
+fn run_stats(ctx: &CliContext, args: AuditStatsArgs) -> Result<()> {
+    if args.format != "text" && args.format != "json" {
+        bail!("invalid format: {} (use text|json)", args.format);
+    }
+
+    let mut stats = audit_log::AuditStats::default();
+    audit_log::for_each_entry(&ctx.paths, |entry| {
+        stats.add(&entry);
+        Ok(())
+    })?;
+
+    if args.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    if stats.total == 0 {
+        println!("No audit entries found.");
+        return Ok(());
+    }
+
+    let fmt_time = |t: Option<chrono::DateTime<chrono::Utc>>| {
+        t.map(|t| {
+            let local: DateTime<Local> = t.into();
+            local.format("%Y-%m-%d %H:%M:%S").to_string()
+        })
+        .unwrap_or_else(|| "-".to_string())
+    };
+
+    println!("Entries: {} ({} failed)", stats.total, stats.failed);
+    println!("Span: {} .. {}", fmt_time(stats.first), fmt_time(stats.last));
+    for (title, counts) in [
+        ("By action", &stats.by_action),
+        ("By actor", &stats.by_actor),
+        ("By credential", &stats.by_credential),
+    ] {
+        println!("\n{}:", title);
+        let mut rows: Vec<_> = counts.iter().collect();
+        rows.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (key, count) in rows {
+            println!("  {:>6}  {}", count, key);
+        }
+    }
+    Ok(())
+}
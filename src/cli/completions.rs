@@ -0,0 +1,46 @@
+//! Shell completion script generation.
+
+use crate::cli::Cli;
+use anyhow::{Context, Result};
+use clap::{Args, CommandFactory};
+use clap_complete::Shell;
+use std::io::Write;
+
+#[derive(Args, Debug)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+pub fn run(args: &CompletionsArgs) -> Result<()> {
+    // Render to a buffer first: clap_complete panics on write errors such as
+    // a closed pipe.
+    let mut script = Vec::new();
+    write_completions(args.shell, &mut script);
+    let mut stdout = std::io::stdout();
+    stdout.write_all(&script).context("write completions")?;
+    stdout.flush().context("flush completions")?;
+    Ok(())
+}
+
+fn write_completions(shell: Shell, out: &mut dyn Write) {
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, name, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_completions() {
+        let mut out = Vec::new();
+        write_completions(Shell::Bash, &mut out);
+        let script = String::from_utf8(out).unwrap();
+        assert!(script.contains("_goamet__vault()"));
+        assert!(script.contains("create-batch"));
+        assert!(script.contains("--non-interactive"));
+    }
+}
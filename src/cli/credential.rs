@@ -1,40 +1,99 @@
-use crate::cli::CliContext;
+use crate::cli::trash::{self as trash_cli, TrashRestoreArgs};
+use crate::cli::{dropin, CliContext};
 use crate::constants;
-use crate::core::{credstore, file_lock::FileLock, metadata};
+use crate::core::audit_log::{self, AuditContext};
+use crate::core::error::VaultError;
+use crate::core::hooks::HookEvent;
+use crate::core::trash::{self, TrashedCredential};
+use crate::core::vault::{
+    dedup, install_with_backup, resolve_key_type, temp_cred_output, write_temp_secret,
+    KeyBinding, WriteOptions,
+};
+use crate::core::{cred_name, credstore, dropin_gen, file_lock::FileLock, metadata, service_map};
 use crate::models::credential::CredentialMeta;
 use crate::models::policy::PolicySection;
-use crate::util::{fs as vault_fs, systemd};
+use crate::util::{entropy, fs as vault_fs, systemd};
 use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Local, Utc};
 use clap::{Args, Subcommand};
-use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Table};
+use comfy_table::{Attribute, Cell};
 use dialoguer::Password;
-use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+use rand::{rngs::OsRng, Rng};
 use serde::Serialize;
 use std::collections::HashSet;
 use std::fs;
-use std::io::{Read, Write};
+use std::io::{IsTerminal, Read, Write};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, OpenOptionsExt, PermissionsExt};
 use std::path::{Path, PathBuf};
-use tempfile::{self, NamedTempFile};
 use zeroize::Zeroizing;
 
-fn parse_credential_name(s: &str) -> Result<String, String> {
-    if s.is_empty() {
-        return Err("name cannot be empty".into());
+pub(crate) fn parse_credential_name(s: &str) -> Result<String, String> {
+    cred_name::validate(s)?;
+    Ok(s.to_string())
+}
+
+/// Symbols used by `alnum-symbols`. Quotes, backslash, backtick and
+/// whitespace are left out so secrets survive shell and .env quoting.
+const SECRET_SYMBOLS: &str = "!#$%&()*+,-./:;<=>?@[]^_{|}~";
+
+/// Alphabet for auto-generated secrets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretCharset {
+    Alnum,
+    AlnumSymbols,
+    Hex,
+    Custom(Vec<char>),
+}
+
+impl SecretCharset {
+    fn alphabet(&self) -> Vec<char> {
+        match self {
+            SecretCharset::Alnum => ('0'..='9').chain('A'..='Z').chain('a'..='z').collect(),
+            SecretCharset::AlnumSymbols => {
+                let mut chars = SecretCharset::Alnum.alphabet();
+                chars.extend(SECRET_SYMBOLS.chars());
+                chars
+            }
+            SecretCharset::Hex => ('0'..='9').chain('a'..='f').collect(),
+            SecretCharset::Custom(chars) => chars.clone(),
+        }
     }
-    if s.contains("..") {
-        return Err("path traversal not allowed".into());
+
+    fn has_symbols(&self) -> bool {
+        self.alphabet().iter().any(|c| !c.is_ascii_alphanumeric())
     }
-    if !s
-        .chars()
-        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
-    {
-        return Err("only [a-zA-Z0-9._-] allowed".into());
+}
+
+pub(crate) fn parse_charset(s: &str) -> Result<SecretCharset, String> {
+    match s {
+        "alnum" => Ok(SecretCharset::Alnum),
+        "alnum-symbols" => Ok(SecretCharset::AlnumSymbols),
+        "hex" => Ok(SecretCharset::Hex),
+        _ => {
+            let Some(custom) = s.strip_prefix("custom:") else {
+                return Err(format!(
+                    "invalid charset: {} (use alnum|alnum-symbols|hex|custom:CHARS)",
+                    s
+                ));
+            };
+            let mut chars: Vec<char> = Vec::new();
+            for c in custom.chars() {
+                if c.is_control() || c.is_whitespace() {
+                    return Err("custom charset cannot contain whitespace or control characters".into());
+                }
+                if !chars.contains(&c) {
+                    chars.push(c);
+                }
+            }
+            if chars.len() < 2 {
+                return Err("custom charset needs at least 2 distinct characters".into());
+            }
+            Ok(SecretCharset::Custom(chars))
+        }
     }
-    Ok(s.to_string())
 }
 
-fn parse_with_key(s: &str) -> Result<String, String> {
+pub(crate) fn parse_with_key(s: &str) -> Result<String, String> {
     if constants::VALID_KEY_TYPES.contains(&s) {
         Ok(s.to_string())
     } else {
@@ -46,6 +105,27 @@ fn parse_with_key(s: &str) -> Result<String, String> {
     }
 }
 
+fn parse_tpm2_pcrs(s: &str) -> Result<String, String> {
+    systemd::parse_tpm2_pcrs(s).map(|_| s.to_string())
+}
+
+fn parse_newline(s: &str) -> Result<String, String> {
+    match s {
+        "auto" | "yes" | "no" => Ok(s.to_string()),
+        _ => Err(format!("invalid newline mode '{}', must be one of: auto, yes, no", s)),
+    }
+}
+
+/// Resolve `--newline auto` ourselves: systemd-creds' own `auto` does not
+/// look at where our stdout goes.
+fn resolve_newline(mode: &str, stdout_is_tty: bool) -> &'static str {
+    match mode {
+        "yes" => "yes",
+        "auto" if stdout_is_tty => "yes",
+        _ => "no",
+    }
+}
+
 #[derive(Args, Debug)]
 pub struct CreateArgs {
     /// Credential name
@@ -57,17 +137,41 @@ pub struct CreateArgs {
     pub with_key: Option<String>,
 
     /// TPM2 PCR values to bind to (advanced, e.g. "7" or "7+11")
-    #[arg(long, value_name = "PCRS")]
+    #[arg(long, value_name = "PCRS", value_parser = parse_tpm2_pcrs)]
     pub tpm2_pcrs: Option<String>,
 
     /// Read secret from stdin instead of interactive prompt
     #[arg(long)]
     pub from_stdin: bool,
 
+    /// Read secret from $CREDENTIALS_DIRECTORY/<NAME> (systemd LoadCredential)
+    #[arg(long, value_name = "NAME", value_parser = parse_credential_name, conflicts_with = "from_stdin")]
+    pub from_credential: Option<String>,
+
+    /// Auto-generate a random secret
+    #[arg(long, conflicts_with_all = ["from_stdin", "from_credential"])]
+    pub auto: bool,
+
+    /// Length for auto-generated secret
+    #[arg(long, default_value_t = 32)]
+    pub length: usize,
+
+    /// Charset for auto-generated secret (alnum|alnum-symbols|hex|custom:CHARS)
+    #[arg(long, value_parser = parse_charset, requires = "auto")]
+    pub charset: Option<SecretCharset>,
+
+    /// Include symbols in auto-generated secret (same as --charset alnum-symbols)
+    #[arg(long, conflicts_with = "charset", requires = "auto")]
+    pub symbols: bool,
+
     /// Description stored in metadata
     #[arg(long)]
     pub description: Option<String>,
 
+    /// Group stored in metadata (organizational only)
+    #[arg(long, value_parser = parse_credential_name)]
+    pub group: Option<String>,
+
     /// Tag(s) for metadata
     #[arg(long, value_name = "TAG")]
     pub tag: Vec<String>,
@@ -75,18 +179,77 @@ pub struct CreateArgs {
     /// Service(s) linked to this credential
     #[arg(long, value_name = "SERVICE")]
     pub service: Vec<String>,
+
+    /// Fail if TPM2 is not available instead of falling back to host-only encryption
+    #[arg(long, alias = "force-tpm2")]
+    pub require_tpm2: bool,
+
+    /// Check policy and show what would be written, without reading a secret or writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CreateBatchArgs {
+    /// Read `name=secret` records from stdin
+    #[arg(long)]
+    pub from_stdin: bool,
+
+    /// Records are NUL-delimited instead of newline-delimited
+    #[arg(long)]
+    pub null: bool,
+
+    /// Key to use for encryption (host|tpm2|host+tpm2|auto; default: host+tpm2 if TPM2 available)
+    #[arg(long, value_parser = parse_with_key)]
+    pub with_key: Option<String>,
+
+    /// TPM2 PCR values to bind to (advanced, e.g. "7" or "7+11")
+    #[arg(long, value_name = "PCRS", value_parser = parse_tpm2_pcrs)]
+    pub tpm2_pcrs: Option<String>,
+
+    /// Tag(s) for metadata of every created credential
+    #[arg(long, value_name = "TAG")]
+    pub tag: Vec<String>,
+
+    /// Service(s) linked to every created credential
+    #[arg(long, value_name = "SERVICE")]
+    pub service: Vec<String>,
 }
 
 #[derive(Args, Debug)]
 pub struct GetArgs {
     /// Credential name
-    #[arg(value_parser = parse_credential_name)]
-    pub name: String,
+    #[arg(
+        value_parser = parse_credential_name,
+        required_unless_present = "file",
+        conflicts_with = "file"
+    )]
+    pub name: Option<String>,
+
+    /// Decrypt this .cred file instead of a credstore entry (name from the file stem)
+    #[arg(long, value_name = "PATH")]
+    pub file: Option<PathBuf>,
+
+    /// With --file: allow a path outside the vault root
+    #[arg(long)]
+    pub allow_outside: bool,
+
+    /// Name the credential was encrypted under, if not the file stem
+    #[arg(long, value_name = "NAME", value_parser = parse_credential_name)]
+    pub cred_name: Option<String>,
 
     /// Output file (avoid stdout)
     #[arg(long, value_name = "PATH")]
     pub output: Option<PathBuf>,
 
+    /// Proceed even if policy max_gets_per_hour is exceeded (audited)
+    #[arg(long)]
+    pub override_rate_limit: bool,
+
+    /// Allow --output to be an existing FIFO; the secret is streamed into it
+    #[arg(long)]
+    pub allow_fifo: bool,
+
     /// Allow stdout output (dangerous)
     #[arg(long)]
     pub confirm: bool,
@@ -95,11 +258,56 @@ pub struct GetArgs {
     #[arg(long)]
     pub reason: Option<String>,
 
-    /// Newline behavior for stdout (auto|yes|no)
-    #[arg(long, default_value = "no")]
+    /// Newline behavior for stdout (auto|yes|no; auto = only when stdout is a terminal)
+    #[arg(long, default_value = "no", value_parser = parse_newline)]
     pub newline: String,
 }
 
+#[derive(Args, Debug)]
+pub struct GetManyArgs {
+    /// Credential names
+    #[arg(required = true, value_parser = parse_credential_name)]
+    pub names: Vec<String>,
+
+    /// Directory to write `<DIR>/<name>` files into (created with mode 0700)
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: PathBuf,
+
+    /// Skip names without a .cred file instead of failing
+    #[arg(long)]
+    pub ignore_missing: bool,
+
+    /// Overwrite files that already exist in the output directory
+    #[arg(long)]
+    pub force: bool,
+
+    /// Proceed even if policy max_gets_per_hour is exceeded (audited)
+    #[arg(long)]
+    pub override_rate_limit: bool,
+
+    /// Reason for the fetch (logged)
+    #[arg(long)]
+    pub reason: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportAllArgs {
+    /// Directory to write plaintext files into (created with mode 0700)
+    pub dir: PathBuf,
+
+    /// Allow writing into an existing non-empty directory (overwrites files)
+    #[arg(long)]
+    pub force: bool,
+
+    /// Required confirmation because this writes every secret in plaintext
+    #[arg(long)]
+    pub confirm: bool,
+
+    /// Reason for the export (required, logged for every credential)
+    #[arg(long)]
+    pub reason: Option<String>,
+}
+
 #[derive(Args, Debug)]
 pub struct ListArgs {
     /// Filter by service name
@@ -110,7 +318,19 @@ pub struct ListArgs {
     #[arg(long)]
     pub tag: Option<String>,
 
-    /// Output format: table|json
+    /// Filter by group
+    #[arg(long)]
+    pub group: Option<String>,
+
+    /// Only credentials whose rotated_at is more than DAYS days ago
+    #[arg(long, value_name = "DAYS")]
+    pub stale: Option<u32>,
+
+    /// Only credentials whose expires_at is within DAYS days (or already passed)
+    #[arg(long, value_name = "DAYS")]
+    pub expiring: Option<u32>,
+
+    /// Output format: table|json|ndjson
     #[arg(long, default_value = "table")]
     pub format: String,
 }
@@ -120,6 +340,37 @@ pub struct DeleteArgs {
     /// Credential name
     #[arg(value_parser = parse_credential_name)]
     pub name: String,
+
+    /// Remove the .cred file for good instead of moving it to the trash
+    #[arg(long)]
+    pub purge: bool,
+
+    /// Reason for the deletion (logged)
+    #[arg(long)]
+    pub reason: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CopyArgs {
+    /// Source credential name
+    #[arg(value_parser = parse_credential_name)]
+    pub src: String,
+
+    /// Destination credential name
+    #[arg(value_parser = parse_credential_name)]
+    pub dst: String,
+
+    /// Key to use for the copy (host|tpm2|host+tpm2|auto; default: host+tpm2 if TPM2 available)
+    #[arg(long, value_parser = parse_with_key)]
+    pub with_key: Option<String>,
+
+    /// TPM2 PCR values to bind to (advanced, e.g. "7" or "7+11")
+    #[arg(long, value_name = "PCRS", value_parser = parse_tpm2_pcrs)]
+    pub tpm2_pcrs: Option<String>,
+
+    /// Overwrite an existing destination (kept as .prev)
+    #[arg(long)]
+    pub force: bool,
 }
 
 #[derive(Args, Debug)]
@@ -127,6 +378,21 @@ pub struct DescribeArgs {
     /// Credential name
     #[arg(value_parser = parse_credential_name)]
     pub name: String,
+
+    /// Output format: table|json
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+/// `describe --format json` output: the metadata plus what is on disk.
+#[derive(Serialize)]
+struct DescribeJson<'a> {
+    #[serde(flatten)]
+    meta: &'a CredentialMeta,
+    mapped_by: &'a [String],
+    rollback_available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rollback_backed_up_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Args, Debug)]
@@ -142,29 +408,45 @@ pub struct RotateArgs {
     pub name: String,
 
     /// Key to use for encryption (host|tpm2|host+tpm2|auto; default: host+tpm2 if TPM2 available)
-    #[arg(long, value_parser = parse_with_key)]
+    #[arg(long, value_parser = parse_with_key, conflicts_with = "schedule")]
     pub with_key: Option<String>,
 
     /// TPM2 PCR values to bind to (advanced, e.g. "7" or "7+11")
-    #[arg(long, value_name = "PCRS")]
+    #[arg(long, value_name = "PCRS", value_parser = parse_tpm2_pcrs, conflicts_with = "schedule")]
     pub tpm2_pcrs: Option<String>,
 
     /// Read secret from stdin instead of interactive prompt
     #[arg(long)]
     pub from_stdin: bool,
 
+    /// Read secret from $CREDENTIALS_DIRECTORY/<NAME> (systemd LoadCredential)
+    #[arg(long, value_name = "NAME", value_parser = parse_credential_name, conflicts_with_all = ["from_stdin", "auto"])]
+    pub from_credential: Option<String>,
+
     /// Auto-generate a random secret
-    #[arg(long)]
+    #[arg(long, conflicts_with = "schedule")]
     pub auto: bool,
 
     /// Length for auto-generated secret
     #[arg(long, default_value_t = 32)]
     pub length: usize,
 
+    /// Charset for auto-generated secret (alnum|alnum-symbols|hex|custom:CHARS)
+    #[arg(long, value_parser = parse_charset, requires = "auto")]
+    pub charset: Option<SecretCharset>,
+
+    /// Include symbols in auto-generated secret (same as --charset alnum-symbols)
+    #[arg(long, conflicts_with = "charset", requires = "auto")]
+    pub symbols: bool,
+
     /// Description update in metadata
     #[arg(long)]
     pub description: Option<String>,
 
+    /// Group update in metadata (organizational only)
+    #[arg(long, value_parser = parse_credential_name)]
+    pub group: Option<String>,
+
     /// Tag(s) to replace metadata tags
     #[arg(long, value_name = "TAG")]
     pub tag: Vec<String>,
@@ -172,6 +454,27 @@ pub struct RotateArgs {
     /// Service(s) to replace metadata services
     #[arg(long, value_name = "SERVICE")]
     pub service: Vec<String>,
+
+    /// Reason for the rotation (logged; required by policy require_reason_for_rotate)
+    #[arg(long)]
+    pub reason: Option<String>,
+
+    /// Fail if TPM2 is not available instead of falling back to host-only encryption
+    #[arg(long, alias = "force-tpm2")]
+    pub require_tpm2: bool,
+
+    /// Check policy and show what would be written, without reading a secret or writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Instead of rotating now, write a systemd timer that rotates with --auto on this
+    /// OnCalendar= schedule (e.g. "monthly", "Sun *-*-* 03:00:00")
+    #[arg(long, value_name = "ONCALENDAR", conflicts_with = "dry_run")]
+    pub schedule: Option<String>,
+
+    /// With --schedule: install and enable the timer (requires root)
+    #[arg(long)]
+    pub apply: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -191,179 +494,618 @@ pub struct RollbackRotateArgs {
 struct ListItem {
     name: String,
     description: Option<String>,
+    group: Option<String>,
     tags: Vec<String>,
     services: Vec<String>,
     size_bytes: Option<u64>,
     modified: Option<String>,
 }
 
-/// Check key-type policy: forbid host-only when TPM2 is available.
-fn check_key_policy(policy: &PolicySection, with_key: &str) -> Result<()> {
-    if policy.forbid_host_only_when_tpm2
-        && with_key == "host"
-        && systemd::has_tpm2().unwrap_or(false)
-    {
-        bail!("policy: host-only encryption forbidden when TPM2 is available (use host+tpm2)");
+pub fn run_create(ctx: &CliContext, args: CreateArgs) -> Result<()> {
+    let mut audit = ctx.audit_ctx("create", &args.name);
+    audit.tpm2_pcrs = args.tpm2_pcrs.clone();
+    audit.service_context = service_context(&args.service);
+    if args.dry_run {
+        // Nothing is written, so there is nothing to audit.
+        return create(ctx, args, &mut audit);
+    }
+    let name = args.name.clone();
+    ctx.audited(audit, |audit| create(ctx, args, audit))?;
+    ctx.run_hooks(HookEvent::PostCreate, &name, &recorded_services(ctx, &name), || {
+        undo_create(ctx, &name)
+    })
+}
+
+fn create(ctx: &CliContext, args: CreateArgs, audit: &mut AuditContext) -> Result<()> {
+    let vault = ctx.vault();
+    let with_key = resolve_key_type(args.with_key.as_deref());
+    audit.with_key = Some(with_key.clone());
+    let opts = WriteOptions {
+        with_key: Some(with_key.clone()),
+        tpm2_pcrs: args.tpm2_pcrs,
+        require_tpm2: args.require_tpm2,
+        description: args.description,
+        group: args.group,
+        tags: args.tag,
+        services: args.service,
+    };
+    // Checked before reading the secret so a refusal never prompts.
+    let binding = vault.check_create(&args.name, &opts)?;
+    audit.tpm2_pcrs = binding.tpm2_pcrs;
+    for warning in cred_name::warnings(&args.name) {
+        eprintln!("warning: {}", warning);
+    }
+
+    if args.dry_run {
+        if args.auto {
+            resolve_charset(&ctx.policy, args.charset, args.symbols)?;
+            check_auto_length(&ctx.policy, args.length)?;
+        }
+        print_dry_run(ctx, "create", &args.name, &with_key, args.auto.then_some(args.length));
+        return Ok(());
     }
+
+    let source = SecretSource::from_args(
+        args.from_stdin,
+        args.from_credential.as_deref(),
+        ctx.askpass.as_deref(),
+    );
+
+    // Non-interactive mode requires a secret source that does not prompt on the TTY
+    if ctx.non_interactive && matches!(source, SecretSource::Prompt) && !args.auto {
+        bail!("--non-interactive requires --from-stdin, --from-credential, --askpass or --auto for create");
+    }
+
+    let secret = if args.auto {
+        let charset = resolve_charset(&ctx.policy, args.charset, args.symbols)?;
+        generate_auto_secret(&ctx.policy, args.length, &charset)?
+    } else {
+        let secret = read_secret(&ctx.policy, source, &args.name)?;
+        check_secret_strength(&ctx.policy, &args.name, &secret)?;
+        secret
+    };
+
+    let output = vault.create(&args.name, secret.as_bytes(), &opts)?;
+    println!("Wrote {}", output.display());
     Ok(())
 }
 
-pub fn run_create(ctx: &CliContext, args: CreateArgs) -> Result<()> {
-    let paths = &ctx.paths;
-    vault_fs::ensure_dir(&paths.credstore, constants::CREDSTORE_DIR_MODE)?;
+pub fn run_create_batch(ctx: &CliContext, args: CreateBatchArgs) -> Result<()> {
+    if !args.from_stdin {
+        bail!("create-batch requires --from-stdin");
+    }
 
+    let vault = ctx.vault();
     let with_key = resolve_key_type(args.with_key.as_deref());
-    check_key_policy(&ctx.policy, &with_key)?;
+    let services = dedup(args.service);
+    let opts = WriteOptions {
+        with_key: Some(with_key.clone()),
+        tpm2_pcrs: args.tpm2_pcrs,
+        tags: dedup(args.tag),
+        services: services.clone(),
+        ..Default::default()
+    };
+
+    let mut raw = Zeroizing::new(Vec::new());
+    std::io::stdin()
+        .read_to_end(&mut raw)
+        .context("read batch from stdin")?;
+    let records = parse_batch_records(&raw, args.null, &ctx.policy);
+    if records.is_empty() {
+        println!("No records found on stdin");
+        return Ok(());
+    }
+
+    let mut created_names = Vec::new();
+    let mut created = 0u32;
+    let mut failed = 0u32;
 
-    // Policy: service allowlist (for metadata linkage)
-    if !args.service.is_empty() {
-        for svc in &args.service {
-            if !ctx.policy.is_service_allowed(svc) {
+    // Strength is a CLI check; the rest runs in one locked `create_many`.
+    let mut pending = Vec::new();
+    for record in records {
+        let (name, secret) = match record {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("  Failed: {}", e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        for warning in cred_name::warnings(&name) {
+            eprintln!("  warning: {}", warning);
+        }
+        let mut audit = ctx.audit_ctx("create", &name);
+        audit.with_key = Some(with_key.clone());
+        audit.tpm2_pcrs = opts.tpm2_pcrs.clone();
+        audit.service_context = service_context(&services);
+        if let Err(e) = check_secret_strength(&ctx.policy, &name, &secret) {
+            eprintln!("  Failed: {}: {}", name, e);
+            ctx.audit_result(audit, false, Some(&e));
+            failed += 1;
+            continue;
+        }
+        pending.push((name, secret, audit));
+    }
+
+    let batch: Vec<(&str, &[u8])> = pending
+        .iter()
+        .map(|(name, secret, _)| (name.as_str(), secret.as_bytes()))
+        .collect();
+    let results = vault.create_many(&batch, &opts);
+    drop(batch);
+    let results = match results {
+        Ok(results) => results,
+        Err(e) => {
+            for (name, _, audit) in pending.drain(..) {
+                eprintln!("  Failed: {}: {:#}", name, e);
+                ctx.audit_result(audit, false, Some(&e));
+                failed += 1;
+            }
+            Vec::new()
+        }
+    };
+    for ((name, _, mut audit), result) in pending.into_iter().zip(results) {
+        match result {
+            Ok((output, binding)) => {
+                audit.tpm2_pcrs = binding.tpm2_pcrs;
+                ctx.audit_result(audit, true, None);
+                println!("  Created: {} -> {}", name, output.display());
+                created_names.push(name);
+                created += 1;
+            }
+            Err(e) => {
+                eprintln!("  Failed: {}: {}", name, e);
+                ctx.audit_result(audit, false, Some(&e));
+                failed += 1;
+            }
+        }
+    }
+
+    for name in created_names {
+        let services = recorded_services(ctx, &name);
+        let hooked = ctx.run_hooks(HookEvent::PostCreate, &name, &services, || {
+            undo_create(ctx, &name)
+        });
+        if let Err(e) = hooked {
+            eprintln!("  Failed: {}: {:#}", name, e);
+            created -= 1;
+            failed += 1;
+        }
+    }
+
+    println!("\nBatch create: {} created, {} failed", created, failed);
+    if failed > 0 {
+        bail!("{} record(s) failed", failed);
+    }
+    Ok(())
+}
+
+pub fn run_get(ctx: &CliContext, args: GetArgs) -> Result<()> {
+    // A file outside the credstore is audited by its path.
+    let credential = match (&args.name, &args.file) {
+        (Some(name), _) => name.clone(),
+        (None, Some(file)) => file.display().to_string(),
+        (None, None) => bail!("a credential name or --file is required"),
+    };
+    let mut audit = ctx.audit_ctx("get", &credential);
+    audit.reason = args.reason.clone();
+    audit.output_mode = Some(if args.output.is_some() { "file" } else { "stdout" }.to_string());
+    audit.target_path = args.output.as_ref().map(|p| p.display().to_string());
+    ctx.audited(audit, |audit| get(ctx, args, audit))
+}
+
+fn get(ctx: &CliContext, args: GetArgs, audit: &mut AuditContext) -> Result<()> {
+    let cred_path = match (&args.name, &args.file) {
+        (Some(name), _) => ctx.vault().check_get(name)?,
+        (None, Some(file)) => resolve_cred_file(ctx, file, args.allow_outside)?,
+        (None, None) => bail!("a credential name or --file is required"),
+    };
+
+    let credential = audit.credential.clone();
+    check_get_rate_limit(ctx, &[&credential], args.override_rate_limit, args.reason.as_deref())?;
+
+    if let Some(output) = args.output {
+        let file_type = fs::metadata(&output).ok().map(|m| m.file_type());
+        if file_type.is_some_and(|t| t.is_fifo()) {
+            audit.output_mode = Some("fifo".to_string());
+            if !args.allow_fifo {
                 bail!(
-                    "policy: service '{}' not allowed (service_allowlist enforced)",
-                    svc
+                    "refusing to write secret to FIFO {} without --allow-fifo",
+                    output.display()
                 );
             }
+            write_to_fifo(&cred_path, args.cred_name.as_deref(), &output)?;
+            println!("Wrote {} (FIFO)", output.display());
+            return Ok(());
         }
+        if file_type.is_some_and(|t| !t.is_file()) {
+            bail!(
+                "refusing to write secret to {}: not a regular file",
+                output.display()
+            );
+        }
+
+        systemd::decrypt_to_file(&cred_path, args.cred_name.as_deref(), &output)?;
+        vault_fs::set_permissions(&output, constants::CRED_FILE_MODE)?;
+        println!("Wrote {}", output.display());
+        return Ok(());
     }
 
-    // Non-interactive mode requires --from-stdin
-    if ctx.non_interactive && !args.from_stdin {
-        bail!("--non-interactive requires --from-stdin for create");
+    if !args.confirm {
+        bail!("refusing to print secret to stdout without --confirm");
+    }
+    if ctx.policy.require_reason_for_stdout
+        && args.reason.as_deref().unwrap_or("").trim().is_empty()
+    {
+        bail!("--reason is required when printing to stdout (policy require_reason_for_stdout)");
     }
 
-    let secret = read_secret(args.from_stdin, &args.name)?;
+    let mut stdout = std::io::stdout();
+    let newline = resolve_newline(&args.newline, stdout.is_terminal());
+    let data =
+        systemd::decrypt_to_stdout(&cred_path, args.cred_name.as_deref(), Some(newline))?;
+    stdout.write_all(&data).context("write to stdout")?;
+    stdout.flush().context("flush stdout")?;
+    Ok(())
+}
 
-    let tmp = write_temp_secret(&secret, &paths.credstore)?;
+pub fn run_get_many(ctx: &CliContext, args: GetManyArgs) -> Result<()> {
+    let mut seen = HashSet::new();
+    let names: Vec<String> = args.names.iter().filter(|n| seen.insert(*n)).cloned().collect();
+    let mut audit = ctx.audit_ctx("get-many", &names.join(","));
+    audit.reason = args.reason.clone();
+    audit.output_mode = Some("file".to_string());
+    audit.target_path = Some(args.output_dir.display().to_string());
+    ctx.audited(audit, |audit| get_many(ctx, &args, names, audit))
+}
 
-    let output = paths.credstore.join(format!("{}{}", args.name, constants::CRED_EXTENSION));
-    systemd::encrypt(&with_key, &args.name, tmp.path(), &output, args.tpm2_pcrs.as_deref())?;
-    vault_fs::set_permissions(&output, constants::CRED_FILE_MODE)?;
+/// Decrypt each credential to `<output_dir>/<name>`. Everything is checked
+/// (names, rate limit, target files) before the first secret is written.
+fn get_many(
+    ctx: &CliContext,
+    args: &GetManyArgs,
+    names: Vec<String>,
+    audit: &mut AuditContext,
+) -> Result<()> {
+    let paths = &ctx.paths;
+    let mut wanted = Vec::new();
+    for name in names {
+        match credstore::existing_cred_path(&paths.credstore, &name) {
+            Ok(cred_path) => wanted.push((name, cred_path)),
+            Err(VaultError::CredentialNotFound { .. }) if args.ignore_missing => {
+                eprintln!("warning: skipping '{}': no .cred file", name);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if wanted.is_empty() {
+        bail!("none of the requested credentials exist");
+    }
+    // Only record the credentials actually fetched.
+    audit.credential = wanted.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>().join(",");
 
-    let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
-    let mut vault = metadata::load(&paths.vault_toml)?;
-    metadata::ensure_vault_section(&mut vault, Some(paths.credstore.display().to_string()));
-    let now = Utc::now();
-    let mut meta = vault
-        .credentials
-        .iter()
-        .find(|c| c.name == args.name)
-        .cloned()
-        .unwrap_or_default();
-    if meta.name.is_empty() {
-        meta.name = args.name.clone();
+    let wanted_names: Vec<&str> = wanted.iter().map(|(n, _)| n.as_str()).collect();
+    check_get_rate_limit(ctx, &wanted_names, args.override_rate_limit, args.reason.as_deref())?;
+    prepare_output_dir(&args.output_dir)?;
+    if !args.force {
+        for (name, _) in &wanted {
+            let target = args.output_dir.join(name);
+            if target.symlink_metadata().is_ok() {
+                bail!("{} already exists (use --force to overwrite)", target.display());
+            }
+        }
+    }
+
+    for (name, cred_path) in &wanted {
+        let target = args.output_dir.join(name);
+        export_one(cred_path, &target, args.force)
+            .with_context(|| format!("fetch '{}'", name))?;
+        println!("Wrote {}", target.display());
     }
-    if meta.created_at.is_none() {
-        meta.created_at = Some(now);
+    Ok(())
+}
+
+/// Create the `get-many` output directory 0700, or accept an existing one
+/// that we own and other users can neither read nor write.
+fn prepare_output_dir(dir: &Path) -> Result<()> {
+    match fs::symlink_metadata(dir) {
+        Ok(meta) => {
+            check_secret_dir(dir, &meta)?;
+            if meta.permissions().mode() & 0o004 != 0 {
+                bail!(
+                    "refusing to write secrets into world-readable directory {} (mode {:o})",
+                    dir.display(),
+                    meta.permissions().mode() & 0o777
+                );
+            }
+            Ok(())
+        }
+        Err(_) => {
+            fs::DirBuilder::new()
+                .mode(constants::CREDSTORE_DIR_MODE)
+                .create(dir)
+                .with_context(|| format!("create {}", dir.display()))?;
+            Ok(())
+        }
     }
-    meta.rotated_at = Some(now);
-    meta.encryption_key = Some(with_key);
-    if let Some(desc) = args.description {
-        meta.description = Some(desc);
+}
+
+pub fn run_export_all(ctx: &CliContext, args: ExportAllArgs) -> Result<()> {
+    let paths = &ctx.paths;
+    if !args.confirm {
+        bail!("refusing to export all secrets in plaintext without --confirm");
     }
-    if !args.tag.is_empty() {
-        meta.tags = dedup(args.tag);
+    let reason = match args.reason.as_deref().map(str::trim) {
+        Some(r) if !r.is_empty() => r.to_string(),
+        _ => bail!("--reason is required for export-all"),
+    };
+
+    let vault = metadata::load(&paths.vault_toml)?;
+    if vault.credentials.is_empty() {
+        println!("No credentials in {}", paths.vault_toml.display());
+        return Ok(());
     }
-    if !args.service.is_empty() {
-        meta.services = dedup(args.service);
+
+    prepare_export_dir(&args.dir, args.force)?;
+
+    println!(
+        "WARNING: writing {} plaintext secret(s) to {}",
+        vault.credentials.len(),
+        args.dir.display()
+    );
+    println!("WARNING: these files are NOT encrypted. Keep them off shared storage and");
+    println!("WARNING: shred the directory as soon as the drill is done.\n");
+
+    let mut exported = 0u32;
+    let mut failed = 0u32;
+    for cred in &vault.credentials {
+        let cred_path = paths.credstore.join(format!("{}{}", cred.name, constants::CRED_EXTENSION));
+        let target = args.dir.join(&cred.name);
+        let mut audit = ctx.audit_ctx("export", &cred.name);
+        audit.reason = Some(reason.clone());
+        audit.output_mode = Some("file".to_string());
+        audit.target_path = Some(target.display().to_string());
+
+        let result = ctx.audited(audit, |_| export_one(&cred_path, &target, args.force));
+        match result {
+            Ok(()) => {
+                println!("  Exported: {} -> {}", cred.name, target.display());
+                exported += 1;
+            }
+            Err(e) => {
+                eprintln!("  Failed: {}: {:#}", cred.name, e);
+                failed += 1;
+            }
+        }
     }
-    metadata::upsert_credential(&mut vault, meta);
-    metadata::save(&paths.vault_toml, &vault)?;
-    ctx.audit_simple("create", &args.name);
 
-    println!("Wrote {}", output.display());
+    println!("\nExport: {} exported, {} failed", exported, failed);
+    println!("WARNING: {} now holds plaintext secrets.", args.dir.display());
+    if failed > 0 {
+        bail!("{} credential(s) failed to export", failed);
+    }
     Ok(())
 }
 
-pub fn run_get(ctx: &CliContext, args: GetArgs) -> Result<()> {
-    let paths = &ctx.paths;
-    let cred_path = paths.credstore.join(format!("{}{}", args.name, constants::CRED_EXTENSION));
+/// Create the export directory 0700, or reuse an existing one we own if it
+/// is empty (or `force` is set).
+fn prepare_export_dir(dir: &Path, force: bool) -> Result<()> {
+    if let Ok(meta) = fs::symlink_metadata(dir) {
+        check_secret_dir(dir, &meta)?;
+        let non_empty = fs::read_dir(dir)
+            .with_context(|| format!("read {}", dir.display()))?
+            .next()
+            .is_some();
+        if non_empty && !force {
+            bail!(
+                "refusing to export into non-empty directory {} without --force",
+                dir.display()
+            );
+        }
+        return vault_fs::set_permissions(dir, constants::CREDSTORE_DIR_MODE);
+    }
+    fs::DirBuilder::new()
+        .mode(constants::CREDSTORE_DIR_MODE)
+        .create(dir)
+        .with_context(|| format!("create {}", dir.display()))?;
+    Ok(())
+}
+
+/// Refuse a directory for plaintext secrets unless it is a real directory
+/// owned by the effective user that neither group nor others can write to
+/// (anyone who can would be able to plant symlinks or swap files).
+fn check_secret_dir(dir: &Path, meta: &fs::Metadata) -> Result<()> {
+    if !meta.is_dir() {
+        bail!("{} exists and is not a directory", dir.display());
+    }
+    let euid = nix::unistd::geteuid().as_raw();
+    if meta.uid() != euid {
+        bail!(
+            "refusing to write secrets into {}: owned by uid {}, not {}",
+            dir.display(),
+            meta.uid(),
+            euid
+        );
+    }
+    let mode = meta.permissions().mode() & 0o777;
+    if mode & 0o022 != 0 {
+        bail!(
+            "refusing to write secrets into group/other-writable directory {} (mode {:o})",
+            dir.display(),
+            mode
+        );
+    }
+    Ok(())
+}
+
+/// Decrypt one credential to `target`, created with CRED_FILE_MODE from the start.
+fn export_one(cred_path: &Path, target: &Path, force: bool) -> Result<()> {
     if !cred_path.is_file() {
         bail!("credential not found: {}", cred_path.display());
     }
+    let data = systemd::decrypt_to_stdout(cred_path, None, Some("no"))?;
+    let mut file = create_secret_file(target, force)?;
+    file.write_all(&data)
+        .with_context(|| format!("write {}", target.display()))?;
+    Ok(())
+}
 
-    ctx.audit_simple("get", &args.name);
+/// Create `target` for a plaintext secret. Never follows a symlink or
+/// reuses an existing file: with `force` the old entry is unlinked first.
+fn create_secret_file(target: &Path, force: bool) -> Result<fs::File> {
+    if force {
+        match fs::remove_file(target) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("remove {}", target.display()));
+            }
+        }
+    }
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .custom_flags(nix::libc::O_NOFOLLOW)
+        .mode(constants::CRED_FILE_MODE)
+        .open(target)
+        .with_context(|| format!("create {}", target.display()))
+}
 
-    if let Some(output) = args.output {
-        systemd::decrypt_to_file(&cred_path, &output)?;
-        vault_fs::set_permissions(&output, constants::CRED_FILE_MODE)?;
-        println!("Wrote {}", output.display());
+/// Check policy max_gets_per_hour for each of `names` against recent `get`s
+/// in the audit log, read once for all of them.
+fn check_get_rate_limit(
+    ctx: &CliContext,
+    names: &[&str],
+    override_rate_limit: bool,
+    reason: Option<&str>,
+) -> Result<()> {
+    let Some(max) = ctx.policy.max_gets_per_hour else {
         return Ok(());
+    };
+    let since = Utc::now() - chrono::Duration::hours(1);
+    let counts = audit_log::count_recent_in_log(&ctx.paths, &["get", "get-many"], names, since)?;
+    for name in names {
+        check_get_count(ctx, name, counts[*name], max, override_rate_limit, reason)?;
     }
+    Ok(())
+}
 
-    if !args.confirm {
-        bail!("refusing to print secret to stdout without --confirm");
+fn check_get_count(
+    ctx: &CliContext,
+    name: &str,
+    recent: usize,
+    max: u32,
+    override_rate_limit: bool,
+    reason: Option<&str>,
+) -> Result<()> {
+    if recent < max as usize {
+        return Ok(());
     }
-    if args.reason.as_deref().unwrap_or("").trim().is_empty() {
-        bail!("--reason is required when printing to stdout");
+
+    if !override_rate_limit {
+        bail!(
+            "policy: '{}' was read {} times in the last hour (max_gets_per_hour = {}); use --override-rate-limit to proceed",
+            name,
+            recent,
+            max
+        );
     }
+    eprintln!(
+        "warning: overriding rate limit for '{}' ({} gets in the last hour, max {})",
+        name, recent, max
+    );
+    let mut audit = ctx.audit_ctx("get-rate-limit-override", name);
+    audit.reason = reason.map(str::to_string);
+    ctx.audit_result(audit, true, None);
+    Ok(())
+}
 
-    let data = systemd::decrypt_to_stdout(&cred_path, Some(args.newline.as_str()))?;
-    let mut stdout = std::io::stdout();
-    stdout.write_all(&data).context("write to stdout")?;
-    stdout.flush().context("flush stdout")?;
+/// Resolve a `--file` credential: an existing regular file inside the vault
+/// root, unless `allow_outside`. Symlinks are resolved before the check.
+pub(crate) fn resolve_cred_file(
+    ctx: &CliContext,
+    file: &Path,
+    allow_outside: bool,
+) -> Result<PathBuf> {
+    let resolved = fs::canonicalize(file)
+        .with_context(|| format!("credential file {}", file.display()))?;
+    if !resolved.is_file() {
+        bail!("{} is not a regular file", file.display());
+    }
+    if !allow_outside {
+        let root = fs::canonicalize(&ctx.paths.root).unwrap_or_else(|_| ctx.paths.root.clone());
+        if !crate::util::path::is_within(&resolved, &root) {
+            bail!(
+                "{} is outside the vault root {} (use --allow-outside)",
+                resolved.display(),
+                root.display()
+            );
+        }
+    }
+    Ok(resolved)
+}
+
+/// Stream a decrypted credential into a FIFO. Opening blocks until a reader
+/// attaches; the plaintext never touches disk and permissions are left alone.
+fn write_to_fifo(cred_path: &Path, cred_name: Option<&str>, fifo: &Path) -> Result<()> {
+    let data = systemd::decrypt_to_stdout(cred_path, cred_name, Some("no"))?;
+    let mut pipe = fs::OpenOptions::new()
+        .write(true)
+        .open(fifo)
+        .with_context(|| format!("open FIFO {}", fifo.display()))?;
+    pipe.write_all(&data)
+        .with_context(|| format!("write to FIFO {}", fifo.display()))?;
+    pipe.flush()
+        .with_context(|| format!("flush FIFO {}", fifo.display()))?;
     Ok(())
 }
 
 pub fn run_list(ctx: &CliContext, args: ListArgs) -> Result<()> {
     let paths = &ctx.paths;
-    if args.format != "table" && args.format != "json" {
-        bail!("invalid format: {} (use table|json)", args.format);
+    if !["table", "json", "ndjson"].contains(&args.format.as_str()) {
+        bail!("invalid format: {} (use table|json|ndjson)", args.format);
     }
 
+    let now = Utc::now();
     let mut items = Vec::new();
-
-    if paths.vault_toml.exists() {
-        let vault = metadata::load(&paths.vault_toml)?;
-        for meta in vault.credentials {
-            if let Some(service) = &args.service {
-                if !meta.services.iter().any(|s| s == service) {
-                    continue;
-                }
+    for meta in ctx.vault().list()? {
+        if let Some(service) = &args.service {
+            if !meta.services.iter().any(|s| s == service) {
+                continue;
             }
-            if let Some(tag) = &args.tag {
-                if !meta.tags.iter().any(|t| t == tag) {
-                    continue;
-                }
+        }
+        if let Some(tag) = &args.tag {
+            if !meta.tags.iter().any(|t| t == tag) {
+                continue;
             }
-            let cred_path = paths.credstore.join(format!("{}{}", meta.name, constants::CRED_EXTENSION));
-            let (size_bytes, modified) = if cred_path.is_file() {
-                let meta_fs = fs::metadata(&cred_path).ok();
-                let size = meta_fs.as_ref().map(|m| m.len());
-                let mod_time = meta_fs.and_then(|m| m.modified().ok()).map(|t| {
-                    let dt: DateTime<Local> = t.into();
-                    dt.format("%Y-%m-%d %H:%M:%S").to_string()
-                });
-                (size, mod_time)
-            } else {
-                (None, None)
-            };
-
-            items.push(ListItem {
-                name: meta.name,
-                description: meta.description,
-                tags: meta.tags,
-                services: meta.services,
-                size_bytes,
-                modified,
-            });
-        }
-    } else if paths.credstore.is_dir() {
-        let entries = credstore::list_credentials(&paths.credstore)?;
-        for entry in entries {
-            let modified = entry.modified.map(|t| {
-                let dt: DateTime<Local> = t.into();
-                dt.format("%Y-%m-%d %H:%M:%S").to_string()
-            });
-            items.push(ListItem {
-                name: entry.name,
-                description: None,
-                tags: Vec::new(),
-                services: Vec::new(),
-                size_bytes: Some(entry.size_bytes),
-                modified,
-            });
         }
+        if let Some(group) = &args.group {
+            if meta.group.as_ref() != Some(group) {
+                continue;
+            }
+        }
+        if !matches_dates(&meta, args.stale, args.expiring, now) {
+            continue;
+        }
+        let meta_fs = fs::metadata(credstore::cred_path(&paths.credstore, &meta.name))
+            .ok()
+            .filter(|m| m.is_file());
+        let size_bytes = meta_fs.as_ref().map(|m| m.len());
+        let modified = meta_fs.and_then(|m| m.modified().ok()).map(|t| {
+            let dt: DateTime<Local> = t.into();
+            dt.format("%Y-%m-%d %H:%M:%S").to_string()
+        });
+
+        items.push(ListItem {
+            name: meta.name,
+            description: meta.description,
+            group: meta.group,
+            tags: meta.tags,
+            services: meta.services,
+            size_bytes,
+            modified,
+        });
     }
 
     if args.format == "json" {
@@ -371,17 +1113,23 @@ pub fn run_list(ctx: &CliContext, args: ListArgs) -> Result<()> {
         println!("{}", json);
         return Ok(());
     }
+    if args.format == "ndjson" {
+        for item in &items {
+            println!("{}", serde_json::to_string(item).context("serialize list item")?);
+        }
+        return Ok(());
+    }
 
     if items.is_empty() {
         println!("No credentials found");
         return Ok(());
     }
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL);
+    let mut table = ctx.table();
     table.set_header(vec![
         Cell::new("Name").add_attribute(Attribute::Bold),
         Cell::new("Description").add_attribute(Attribute::Bold),
+        Cell::new("Group").add_attribute(Attribute::Bold),
         Cell::new("Tags").add_attribute(Attribute::Bold),
         Cell::new("Services").add_attribute(Attribute::Bold),
         Cell::new("Size").add_attribute(Attribute::Bold),
@@ -407,6 +1155,7 @@ pub fn run_list(ctx: &CliContext, args: ListArgs) -> Result<()> {
         table.add_row(vec![
             item.name,
             item.description.unwrap_or_else(|| "-".to_string()),
+            item.group.unwrap_or_else(|| "-".to_string()),
             tags,
             services,
             size,
@@ -414,57 +1163,193 @@ pub fn run_list(ctx: &CliContext, args: ListArgs) -> Result<()> {
         ]);
     }
 
-    println!("{}", table);
+    ctx.page(&format!("{}\n", table));
     Ok(())
 }
 
 pub fn run_delete(ctx: &CliContext, args: DeleteArgs) -> Result<()> {
+    // Ask before the audit span: declining changes nothing.
+    let prompt = if args.purge {
+        format!("Delete credential '{}'? This cannot be undone", args.name)
+    } else {
+        format!("Delete credential '{}'? (kept in trash; `trash restore` undoes it)", args.name)
+    };
+    let exists = credstore::cred_path(&ctx.paths.credstore, &args.name).is_file();
+    if exists && !ctx.confirm(&prompt)? {
+        bail!("aborted: '{}' not deleted", args.name);
+    }
+    let mut audit = ctx.audit_ctx("delete", &args.name);
+    audit.reason = args.reason.clone();
+    audit.output_mode = Some(if args.purge { "purge" } else { "trash" }.to_string());
+    let services = recorded_services(ctx, &args.name);
+    let trashed = ctx.audited(audit, |_| delete(ctx, &args))?;
+    ctx.run_hooks(HookEvent::PostDelete, &args.name, &services, || match trashed {
+        Some(entry) => trash_cli::restore(
+            ctx,
+            &TrashRestoreArgs {
+                name: args.name.clone(),
+                stamp: Some(entry.stamp),
+            },
+        )
+        .map(|_| ()),
+        None => bail!("'{}' was purged and cannot be restored", args.name),
+    })
+}
+
+/// Returns the trash entry, or `None` with `--purge`.
+fn delete(ctx: &CliContext, args: &DeleteArgs) -> Result<Option<TrashedCredential>> {
     let paths = &ctx.paths;
-    let cred_path = paths.credstore.join(format!("{}{}", args.name, constants::CRED_EXTENSION));
-    if !cred_path.exists() {
-        bail!("credential not found: {}", cred_path.display());
+    let trashed = ctx.vault().delete(&args.name, args.purge)?;
+    let cred_path = credstore::cred_path(&paths.credstore, &args.name);
+    match &trashed {
+        Some(entry) => println!(
+            "Deleted {} (moved to {}; restore with `trash restore {}`)",
+            cred_path.display(),
+            entry.cred_path(&paths.trash).display(),
+            args.name
+        ),
+        None => println!("Deleted {} (purged)", cred_path.display()),
+    }
+    Ok(trashed)
+}
+
+pub fn run_copy(ctx: &CliContext, args: CopyArgs) -> Result<()> {
+    let mut audit = ctx.audit_ctx("copy", &args.src);
+    audit.tpm2_pcrs = args.tpm2_pcrs.clone();
+    audit.target_path = Some(
+        ctx.paths
+            .credstore
+            .join(format!("{}{}", args.dst, constants::CRED_EXTENSION))
+            .display()
+            .to_string(),
+    );
+    ctx.audited(audit, |audit| copy(ctx, &args, audit))
+}
+
+fn copy(ctx: &CliContext, args: &CopyArgs, audit: &mut AuditContext) -> Result<()> {
+    let paths = &ctx.paths;
+    if args.src == args.dst {
+        bail!("source and destination are the same credential");
     }
 
+    let vault = ctx.vault();
+    let src_path = vault.check_get(&args.src)?;
+    let dst_path = credstore::cred_path(&paths.credstore, &args.dst);
+
+    // Policy: the copy inherits the source's services and tags
+    let src_meta = vault.describe(&args.src).unwrap_or_default();
+    let with_key = resolve_key_type(args.with_key.as_deref());
+    audit.with_key = Some(with_key.clone());
+    audit.service_context = service_context(&src_meta.services);
+    let opts = WriteOptions {
+        with_key: Some(with_key.clone()),
+        tpm2_pcrs: args.tpm2_pcrs.clone(),
+        tags: src_meta.tags,
+        services: src_meta.services,
+        ..Default::default()
+    };
+    let binding = vault.check_create(&args.dst, &opts)?;
+    audit.tpm2_pcrs = binding.tpm2_pcrs.clone();
+
     let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
-    fs::remove_file(&cred_path)
-        .with_context(|| format!("remove {}", cred_path.display()))?;
-    ctx.audit_simple("delete", &args.name);
+    if dst_path.exists() && !args.force {
+        return Err(VaultError::AlreadyExists {
+            name: args.dst.clone(),
+            path: dst_path,
+        })
+        .with_context(|| format!("refusing to overwrite '{}' (use --force)", args.dst));
+    }
+
+    let mut vault = metadata::load(&paths.vault_toml)?;
+    metadata::ensure_vault_section(&mut vault, paths.credstore_override());
+    // Re-read the source record under the lock; the metadata comes from here.
+    let src_meta = vault
+        .credentials
+        .iter()
+        .find(|c| c.name == args.src)
+        .cloned()
+        .unwrap_or_default();
+    ctx.policy.check_required_tags(&src_meta.tags)?;
+
+    let secret = systemd::decrypt_to_stdout(&src_path, None, Some("no"))?;
+    let tmp_secret = write_temp_secret(&secret, &paths.credstore)?;
+    let tmp_output = temp_cred_output(&paths.credstore)?;
+    let KeyBinding { with_key, tpm2_pcrs } = binding;
+    systemd::encrypt(&with_key, &args.dst, tmp_secret.path(), tmp_output.path(), tpm2_pcrs.as_deref())?;
+    drop(tmp_secret);
+    let final_path = install_with_backup(paths, &vault, &args.dst, tmp_output)?;
 
-    if paths.vault_toml.exists() {
-        let mut vault = metadata::load(&paths.vault_toml)?;
-        metadata::remove_credential(&mut vault, &args.name);
-        metadata::save(&paths.vault_toml, &vault)?;
-    }
+    let now = Utc::now();
+    let meta = CredentialMeta {
+        name: args.dst.clone(),
+        description: src_meta.description,
+        created_at: Some(now),
+        rotated_at: Some(now),
+        encryption_key: Some(with_key),
+        tpm2_pcrs,
+        cred_sha256: credstore::cred_sha256(&final_path).ok(),
+        expires_at: None,
+        tags: src_meta.tags,
+        services: src_meta.services,
+        group: src_meta.group,
+    };
+    metadata::upsert_credential(&mut vault, meta);
+    metadata::save(&paths.vault_toml, &vault)?;
 
-    println!("Deleted {}", cred_path.display());
+    println!("Copied {} -> {}", args.src, final_path.display());
     Ok(())
 }
 
 pub fn run_describe(ctx: &CliContext, args: DescribeArgs) -> Result<()> {
     let paths = &ctx.paths;
-    if !paths.vault_toml.exists() {
-        bail!("metadata not found: {}", paths.vault_toml.display());
+    let meta = ctx.vault().describe(&args.name)?;
+    if !["table", "json"].contains(&args.format.as_str()) {
+        bail!("invalid format: {} (use table|json)", args.format);
+    }
+
+    // The .prev backup left by the last rotate is the rollback target.
+    let backed_up_at = fs::metadata(credstore::prev_cred_path(&paths.credstore, &meta.name))
+        .ok()
+        .filter(|m| m.is_file())
+        .map(|m| m.modified().ok().map(DateTime::<Utc>::from));
+    let refs = service_map::services_referencing(&paths.services, &paths.credstore, &meta.name);
+
+    if args.format == "json" {
+        let out = DescribeJson {
+            meta: &meta,
+            mapped_by: &refs.services,
+            rollback_available: backed_up_at.is_some(),
+            rollback_backed_up_at: backed_up_at.flatten(),
+        };
+        let json = serde_json::to_string_pretty(&out).context("serialize describe")?;
+        println!("{}", json);
+        return Ok(());
     }
-    let vault = metadata::load(&paths.vault_toml)?;
-    let meta = vault
-        .credentials
-        .iter()
-        .find(|c| c.name == args.name)
-        .cloned()
-        .ok_or_else(|| anyhow::anyhow!("metadata not found for {}", args.name))?;
 
     println!("name: {}", meta.name);
     if let Some(desc) = meta.description {
         println!("description: {}", desc);
     }
+    if let Some(group) = meta.group {
+        println!("group: {}", group);
+    }
     if let Some(created) = meta.created_at {
         println!("created_at: {}", created.to_rfc3339());
     }
     if let Some(rotated) = meta.rotated_at {
         println!("rotated_at: {}", rotated.to_rfc3339());
     }
-    if let Some(key) = meta.encryption_key {
+    if let Some(key) = &meta.encryption_key {
         println!("encryption_key: {}", key);
+        if key != "host" {
+            println!(
+                "tpm2_pcrs: {}",
+                meta.tpm2_pcrs.as_deref().unwrap_or("(systemd-creds default)")
+            );
+        }
+    }
+    if let Some(sum) = &meta.cred_sha256 {
+        println!("cred_sha256: {}", sum);
     }
     if !meta.tags.is_empty() {
         println!("tags: {}", meta.tags.join(","));
@@ -472,9 +1357,61 @@ pub fn run_describe(ctx: &CliContext, args: DescribeArgs) -> Result<()> {
     if !meta.services.is_empty() {
         println!("services: {}", meta.services.join(","));
     }
+
+    match backed_up_at {
+        Some(Some(at)) => println!(
+            "rollback_available: yes (backed up {})",
+            at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+        ),
+        Some(None) => println!("rollback_available: yes"),
+        None => println!("rollback_available: no"),
+    }
+
+    // Reconcile metadata services with the maps that actually reference it
+    if refs.services.is_empty() {
+        println!("mapped_by: -");
+    } else {
+        println!("mapped_by: {}", refs.services.join(","));
+    }
+    let (only_in_maps, only_in_metadata) = service_drift(&meta.services, &refs.services);
+    for svc in only_in_maps {
+        println!(
+            "warning: services/{}.conf references '{}' but '{}' is not in metadata services",
+            svc, meta.name, svc
+        );
+    }
+    for svc in only_in_metadata {
+        println!(
+            "warning: metadata lists service '{}' but no service map references '{}'",
+            svc, meta.name
+        );
+    }
+    for err in &refs.unreadable {
+        println!("warning: cannot parse service map {}", err);
+    }
     Ok(())
 }
 
+/// Compare metadata services against map services (ignoring a `.service`
+/// suffix). Returns (only in maps, only in metadata).
+pub(crate) fn service_drift<'a>(
+    metadata_services: &'a [String],
+    map_services: &'a [String],
+) -> (Vec<&'a str>, Vec<&'a str>) {
+    let norm = |s: &'a str| s.strip_suffix(".service").unwrap_or(s);
+    let only_in_maps = map_services
+        .iter()
+        .map(|s| s.as_str())
+        .filter(|m| !metadata_services.iter().any(|s| norm(s) == norm(m)))
+        .collect();
+    let only_in_metadata = metadata_services
+        .iter()
+        .map(|s| s.as_str())
+        .filter(|s| !map_services.iter().any(|m| norm(m) == norm(s)))
+        .collect();
+    (only_in_maps, only_in_metadata)
+}
+
 pub fn run_search(ctx: &CliContext, args: SearchArgs) -> Result<()> {
     let paths = &ctx.paths;
     if !paths.vault_toml.exists() {
@@ -493,8 +1430,7 @@ pub fn run_search(ctx: &CliContext, args: SearchArgs) -> Result<()> {
         return Ok(());
     }
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL);
+    let mut table = ctx.table();
     table.set_header(vec![
         Cell::new("Name").add_attribute(Attribute::Bold),
         Cell::new("Description").add_attribute(Attribute::Bold),
@@ -526,221 +1462,434 @@ pub fn run_search(ctx: &CliContext, args: SearchArgs) -> Result<()> {
 }
 
 pub fn run_rotate(ctx: &CliContext, args: RotateArgs) -> Result<()> {
-    let paths = &ctx.paths;
-    vault_fs::ensure_dir(&paths.credstore, constants::CREDSTORE_DIR_MODE)?;
+    if let Some(on_calendar) = &args.schedule {
+        return schedule_rotate(ctx, &args.name, on_calendar, args.apply);
+    }
+    if args.apply {
+        bail!("--apply is only valid with --schedule");
+    }
+    let mut audit = ctx.audit_ctx("rotate", &args.name);
+    audit.reason = args.reason.clone();
+    audit.tpm2_pcrs = args.tpm2_pcrs.clone();
+    audit.service_context = service_context(&args.service);
+    if args.dry_run {
+        // Nothing is written, so there is nothing to audit.
+        return rotate(ctx, args, &mut audit);
+    }
+    let name = args.name.clone();
+    ctx.audited(audit, |audit| rotate(ctx, args, audit))?;
+    ctx.run_hooks(HookEvent::PostRotate, &name, &recorded_services(ctx, &name), || {
+        run_rollback_rotate(ctx, RollbackRotateArgs { name: name.clone() })
+    })
+}
 
+fn rotate(ctx: &CliContext, args: RotateArgs, audit: &mut AuditContext) -> Result<()> {
+    let vault = ctx.vault();
     let with_key = resolve_key_type(args.with_key.as_deref());
-    check_key_policy(&ctx.policy, &with_key)?;
+    audit.with_key = Some(with_key.clone());
+    let opts = WriteOptions {
+        with_key: Some(with_key.clone()),
+        tpm2_pcrs: args.tpm2_pcrs,
+        require_tpm2: args.require_tpm2,
+        description: args.description,
+        group: args.group,
+        tags: args.tag,
+        services: args.service,
+    };
+    // Checked before reading the secret so a refusal never prompts.
+    let binding = vault.check_rotate(&args.name, &opts)?;
+    audit.tpm2_pcrs = binding.tpm2_pcrs;
 
-    // Policy: service allowlist (for metadata linkage)
-    if !args.service.is_empty() {
-        for svc in &args.service {
-            if !ctx.policy.is_service_allowed(svc) {
-                bail!(
-                    "policy: service '{}' not allowed (service_allowlist enforced)",
-                    svc
-                );
-            }
-        }
+    if ctx.policy.require_reason_for_rotate
+        && args.reason.as_deref().unwrap_or("").trim().is_empty()
+    {
+        bail!("policy: --reason is required for rotate (require_reason_for_rotate enforced)");
     }
 
     if args.auto && args.from_stdin {
         bail!("--auto and --from-stdin cannot be used together");
     }
 
-    // Non-interactive mode requires --from-stdin or --auto
-    if ctx.non_interactive && !args.from_stdin && !args.auto {
-        bail!("--non-interactive requires --from-stdin or --auto for rotate");
+    if args.dry_run {
+        if args.auto {
+            resolve_charset(&ctx.policy, args.charset, args.symbols)?;
+            check_auto_length(&ctx.policy, args.length)?;
+        }
+        print_dry_run(ctx, "rotate", &args.name, &with_key, args.auto.then_some(args.length));
+        return Ok(());
     }
 
-    // Policy: minimum auto-secret length
-    if args.auto {
-        if let Some(min_len) = ctx.policy.min_auto_secret_length {
-            if args.length < min_len {
-                bail!(
-                    "policy: auto-generated secret length {} below minimum {} (set in vault.toml [policy])",
-                    args.length,
-                    min_len
-                );
-            }
-        }
+    let source = SecretSource::from_args(
+        args.from_stdin,
+        args.from_credential.as_deref(),
+        ctx.askpass.as_deref(),
+    );
+
+    // Non-interactive mode requires a secret source that does not prompt on the TTY
+    if ctx.non_interactive && matches!(source, SecretSource::Prompt) && !args.auto {
+        bail!("--non-interactive requires --from-stdin, --from-credential, --askpass or --auto for rotate");
     }
 
     let secret: Zeroizing<String> = if args.auto {
-        Zeroizing::new(generate_secret(args.length))
+        let charset = resolve_charset(&ctx.policy, args.charset, args.symbols)?;
+        generate_auto_secret(&ctx.policy, args.length, &charset)?
     } else {
-        read_secret(args.from_stdin, &args.name)?
+        let secret = read_secret(&ctx.policy, source, &args.name)?;
+        check_secret_strength(&ctx.policy, &args.name, &secret)?;
+        secret
     };
 
-    if secret.is_empty() {
-        bail!("secret is empty");
-    }
-
-    let tmp_secret = write_temp_secret(&secret, &paths.credstore)?;
-    let tmp_output = tempfile::Builder::new()
-        .prefix("cred-")
-        .suffix(".cred.tmp")
-        .tempfile_in(&paths.credstore)
-        .context("create temp output")?;
-    systemd::encrypt(&with_key, &args.name, tmp_secret.path(), tmp_output.path(), args.tpm2_pcrs.as_deref())?;
-
-    let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
-    let final_path = paths.credstore.join(format!("{}{}", args.name, constants::CRED_EXTENSION));
+    let final_path = vault.rotate(&args.name, secret.as_bytes(), &opts)?;
+    println!("Rotated {}", final_path.display());
+    Ok(())
+}
 
-    // Create .prev backup before overwriting
-    let prev_path = paths.credstore.join(format!("{}{}.prev", args.name, constants::CRED_EXTENSION));
-    if final_path.is_file() {
-        fs::copy(&final_path, &prev_path)
-            .with_context(|| format!("backup {} to .prev", final_path.display()))?;
+/// Write `goamet-vault-rotate-<name>.{service,timer}` to the units
+/// directory; with `apply`, install and enable the timer.
+fn schedule_rotate(ctx: &CliContext, name: &str, on_calendar: &str, apply: bool) -> Result<()> {
+    let paths = &ctx.paths;
+    credstore::existing_cred_path(&paths.credstore, name)?;
+    systemd::validate_on_calendar(on_calendar)?;
+
+    let exe = std::env::current_exe().context("locate goamet-vault binary")?;
+    // The timer runs from /, so relative --root/--credstore paths must be resolved now.
+    let root = fs::canonicalize(&paths.root)
+        .with_context(|| format!("resolve vault root {}", paths.root.display()))?;
+    let credstore = fs::canonicalize(&paths.credstore)
+        .with_context(|| format!("resolve credstore {}", paths.credstore.display()))?;
+    let (service, timer) =
+        dropin_gen::generate_rotate_units(name, on_calendar, &exe, &root, &credstore);
+    vault_fs::ensure_dir(&paths.units, constants::UNITS_DIR_MODE)?;
+    let stem = dropin_gen::rotate_unit_stem(name);
+    let mut written = Vec::new();
+    for (ext, content) in [("service", service), ("timer", timer)] {
+        let path = paths.units.join(format!("{}.{}", stem, ext));
+        fs::write(&path, content).with_context(|| format!("write {}", path.display()))?;
+        println!("Wrote {}", path.display());
+        written.push(path);
+    }
+    if !apply {
+        return Ok(());
     }
 
-    match tmp_output.persist(&final_path) {
-        Ok(_) => {}
-        Err(e) => {
-            // Restore from backup on failure
-            if prev_path.is_file() {
-                let _ = fs::rename(&prev_path, &final_path);
-            }
-            bail!("persist rotated credential: {}", e);
+    let timer_unit = format!("{}.timer", stem);
+    let mut audit = ctx.audit_ctx("rotate-schedule", name);
+    audit.target_path = Some(format!("/etc/systemd/system/{}", timer_unit));
+    ctx.audited(audit, |_| {
+        dropin::install_units(&written)?;
+        if !dropin::systemctl_available() {
+            bail!("systemctl not available; cannot enable {}", timer_unit);
         }
-    }
-    vault_fs::set_permissions(&final_path, constants::CRED_FILE_MODE)?;
-
-    let mut vault = metadata::load(&paths.vault_toml)?;
-    metadata::ensure_vault_section(&mut vault, Some(paths.credstore.display().to_string()));
-    let now = Utc::now();
-    let mut meta = vault
-        .credentials
-        .iter()
-        .find(|c| c.name == args.name)
-        .cloned()
-        .unwrap_or_default();
-    if meta.name.is_empty() {
-        meta.name = args.name.clone();
-    }
-    if meta.created_at.is_none() {
-        meta.created_at = Some(now);
-    }
-    meta.rotated_at = Some(now);
-    meta.encryption_key = Some(with_key);
-    if let Some(desc) = args.description {
-        meta.description = Some(desc);
-    }
-    if !args.tag.is_empty() {
-        meta.tags = dedup(args.tag);
-    }
-    if !args.service.is_empty() {
-        meta.services = dedup(args.service);
-    }
-    metadata::upsert_credential(&mut vault, meta);
-    metadata::save(&paths.vault_toml, &vault)?;
-    ctx.audit_simple("rotate", &args.name);
-
-    println!("Rotated {}", final_path.display());
-    Ok(())
+        let status = std::process::Command::new("systemctl")
+            .args(["enable", "--now", &timer_unit])
+            .status()
+            .context("run systemctl enable")?;
+        if !status.success() {
+            bail!("systemctl enable --now {} failed ({})", timer_unit, status);
+        }
+        println!("Enabled {} (OnCalendar={})", timer_unit, on_calendar);
+        Ok(())
+    })
 }
 
 pub fn run_rollback(ctx: &CliContext, cmd: RollbackCommand) -> Result<()> {
     match cmd {
-        RollbackCommand::Rotate(args) => run_rollback_rotate(ctx, args),
+        RollbackCommand::Rotate(args) => {
+            let audit = ctx.audit_ctx("rollback-rotate", &args.name);
+            ctx.audited(audit, |_| run_rollback_rotate(ctx, args))
+        }
     }
 }
 
-fn run_rollback_rotate(ctx: &CliContext, args: RollbackRotateArgs) -> Result<()> {
+pub(crate) fn run_rollback_rotate(ctx: &CliContext, args: RollbackRotateArgs) -> Result<()> {
     let paths = &ctx.paths;
     let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
     let cred_path = paths.credstore.join(format!("{}{}", args.name, constants::CRED_EXTENSION));
-    let prev_path = paths.credstore.join(format!("{}{}.prev", args.name, constants::CRED_EXTENSION));
+    let prev_path = credstore::prev_cred_path(&paths.credstore, &args.name);
 
     if !prev_path.is_file() {
         bail!("no .prev backup found for '{}' — cannot rollback", args.name);
     }
 
+    let snapshot_path = metadata::prev_snapshot_path(&paths.credstore, &args.name);
+    let snapshot = metadata::read_prev_snapshot(&snapshot_path)?;
+
     fs::rename(&prev_path, &cred_path)
         .with_context(|| format!("restore {} from .prev", args.name))?;
 
-    ctx.audit_simple("rollback-rotate", &args.name);
+    if paths.vault_toml.exists() {
+        let mut vault = metadata::load(&paths.vault_toml)?;
+        let restored_sha256 = credstore::cred_sha256(&cred_path).ok();
+        if !metadata::rollback_credential(&mut vault, &args.name, snapshot, restored_sha256) {
+            eprintln!(
+                "warning: no metadata snapshot for '{}'; rotated_at and tpm2_pcrs were cleared \
+                 (encryption_key may be stale)",
+                args.name
+            );
+        }
+        metadata::save(&paths.vault_toml, &vault)?;
+    }
+    if snapshot_path.exists() {
+        fs::remove_file(&snapshot_path)
+            .with_context(|| format!("remove {}", snapshot_path.display()))?;
+    }
+
     println!("Rolled back '{}' to previous version", args.name);
     Ok(())
 }
 
 #[cfg(test)]
 fn validate_name(name: &str) -> Result<()> {
-    if name.is_empty() {
-        bail!("name cannot be empty");
-    }
-    if name.contains('/') || name.contains('\\') || name.contains("..") {
-        bail!("invalid name: path separators are not allowed");
-    }
-    if name.chars().any(|c| c.is_whitespace()) {
-        bail!("invalid name: whitespace not allowed");
+    cred_name::validate(name).map_err(|e| anyhow::anyhow!("invalid name: {}", e))
+}
+
+/// Services linked to `name` in vault.toml, for hook `{service}`.
+fn recorded_services(ctx: &CliContext, name: &str) -> Vec<String> {
+    metadata::load(&ctx.paths.vault_toml)
+        .ok()
+        .and_then(|v| v.credentials.into_iter().find(|c| c.name == name))
+        .map(|c| c.services)
+        .unwrap_or_default()
+}
+
+/// Undo a create whose hook failed: the credential goes to the trash.
+fn undo_create(ctx: &CliContext, name: &str) -> Result<()> {
+    let paths = &ctx.paths;
+    let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
+    let mut vault = metadata::load(&paths.vault_toml)?;
+    let meta = vault.credentials.iter().find(|c| c.name == name);
+    trash::move_to_trash(paths, name, meta)?;
+    metadata::remove_credential(&mut vault, name);
+    metadata::save(&paths.vault_toml, &vault)?;
+    println!("Undid create of '{}' (moved to trash)", name);
+    Ok(())
+}
+
+/// Linked services as recorded in the audit trail.
+fn service_context(services: &[String]) -> Option<String> {
+    if services.is_empty() {
+        None
+    } else {
+        Some(services.join(","))
     }
-    if !name
-        .chars()
-        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
-    {
-        bail!("invalid name: only [a-zA-Z0-9._-] allowed");
+}
+
+/// Where `create`/`rotate` read the plaintext from.
+enum SecretSource<'a> {
+    Stdin,
+    /// A credential passed to this process via systemd `LoadCredential`.
+    Credential(&'a str),
+    /// An askpass program (`--askpass`, or `$SSH_ASKPASS` without a TTY) printing
+    /// the secret on stdout.
+    Askpass(&'a Path),
+    Prompt,
+}
+
+impl<'a> SecretSource<'a> {
+    fn from_args(
+        from_stdin: bool,
+        from_credential: Option<&'a str>,
+        askpass: Option<&'a Path>,
+    ) -> Self {
+        if from_stdin {
+            SecretSource::Stdin
+        } else if let Some(name) = from_credential {
+            SecretSource::Credential(name)
+        } else if let Some(prog) = askpass {
+            SecretSource::Askpass(prog)
+        } else {
+            SecretSource::Prompt
+        }
     }
-    Ok(())
 }
 
-/// Resolve the effective key type: use explicit value or auto-detect TPM2.
-fn resolve_key_type(explicit: Option<&str>) -> String {
-    match explicit {
-        Some(k) => k.to_string(),
-        None => {
-            if systemd::has_tpm2().unwrap_or(false) {
-                constants::DEFAULT_KEY_TYPE_WITH_TPM2.to_string()
-            } else {
-                constants::DEFAULT_KEY_TYPE_WITHOUT_TPM2.to_string()
+/// Read the secret from `source`, enforcing the policy's secret size limits.
+fn read_secret(
+    policy: &PolicySection,
+    source: SecretSource,
+    name: &str,
+) -> Result<Zeroizing<String>> {
+    let secret = match source {
+        SecretSource::Stdin => {
+            let mut buf = String::new();
+            std::io::stdin()
+                .read_to_string(&mut buf)
+                .context("read secret from stdin")?;
+            Zeroizing::new(buf.trim_end_matches(['\r', '\n']).to_string())
+        }
+        SecretSource::Credential(cred) => {
+            let dir = std::env::var_os("CREDENTIALS_DIRECTORY")
+                .filter(|d| !d.is_empty())
+                .context("--from-credential requires $CREDENTIALS_DIRECTORY (run under systemd with LoadCredential=)")?;
+            read_loaded_credential(Path::new(&dir), cred, policy.max_secret_size())?
+        }
+        SecretSource::Askpass(prog) => run_askpass(prog, &format!("Secret for {}: ", name))?,
+        SecretSource::Prompt => {
+            if !std::io::stderr().is_terminal() {
+                bail!("no TTY available to prompt for the secret; use --from-stdin, --from-credential or --askpass");
             }
+            Zeroizing::new(
+                Password::new()
+                    .with_prompt(format!("Secret for {}", name))
+                    .allow_empty_password(false)
+                    .interact()
+                    .context("read secret from prompt")?,
+            )
         }
-    }
+    };
+    policy.check_secret_size(secret.len())?;
+    Ok(secret)
 }
 
-fn read_secret(from_stdin: bool, name: &str) -> Result<Zeroizing<String>> {
-    let secret = if from_stdin {
-        let mut buf = String::new();
-        std::io::stdin()
-            .read_to_string(&mut buf)
-            .context("read secret from stdin")?;
-        Zeroizing::new(buf.trim_end_matches(['\r', '\n']).to_string())
-    } else {
-        Zeroizing::new(
-            Password::new()
-                .with_prompt(format!("Secret for {}", name))
-                .allow_empty_password(false)
-                .interact()
-                .context("read secret from prompt")?,
-        )
+/// Compare a supplied secret's entropy estimate against policy
+/// `min_secret_entropy_bits`; warn, or bail with `reject_weak_secrets`.
+fn check_secret_strength(policy: &PolicySection, name: &str, secret: &str) -> Result<()> {
+    let Some(min_bits) = policy.min_secret_entropy_bits else {
+        return Ok(());
     };
-    if secret.len() > constants::MAX_SECRET_SIZE {
+    let bits = entropy::estimate_bits(secret.as_bytes());
+    if bits >= f64::from(min_bits) {
+        return Ok(());
+    }
+    if policy.reject_weak_secrets {
         bail!(
-            "secret exceeds maximum size ({} bytes, max {} bytes)",
-            secret.len(),
-            constants::MAX_SECRET_SIZE
+            "policy: secret for '{}' looks weak (~{:.0} bits estimated, min_secret_entropy_bits = {}, reject_weak_secrets enforced)",
+            name,
+            bits,
+            min_bits
         );
     }
+    eprintln!(
+        "warning: secret for '{}' looks weak (~{:.0} bits estimated, min_secret_entropy_bits = {})",
+        name, bits, min_bits
+    );
+    Ok(())
+}
+
+/// Run an askpass program with `prompt` as its only argument and read the
+/// secret from its stdout (one trailing newline stripped).
+fn run_askpass(prog: &Path, prompt: &str) -> Result<Zeroizing<String>> {
+    let output = std::process::Command::new(prog)
+        .arg(prompt)
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .with_context(|| format!("run askpass program {}", prog.display()))?;
+    let stdout = Zeroizing::new(output.stdout);
+    if !output.status.success() {
+        bail!("askpass program {} failed ({})", prog.display(), output.status);
+    }
+    let text = std::str::from_utf8(&stdout).context("askpass output is not valid UTF-8")?;
+    let secret = Zeroizing::new(text.trim_end_matches(['\r', '\n']).to_string());
+    if secret.is_empty() {
+        bail!("askpass program {} returned an empty secret", prog.display());
+    }
     Ok(secret)
 }
 
-fn write_temp_secret(secret: &str, credstore: &Path) -> Result<NamedTempFile> {
-    let mut tmp = tempfile::Builder::new()
-        .prefix(".secret-")
-        .tempfile_in(credstore)
-        .context("create temp file")?;
-    tmp.write_all(secret.as_bytes())
-        .context("write temp secret")?;
-    tmp.flush().context("flush temp secret")?;
-    Ok(tmp)
+/// Read `<dir>/<name>` as provided by systemd in `$CREDENTIALS_DIRECTORY`,
+/// refusing files larger than `max_size` bytes before reading them.
+fn read_loaded_credential(dir: &Path, name: &str, max_size: usize) -> Result<Zeroizing<String>> {
+    let path = dir.join(name);
+    let meta = fs::metadata(&path)
+        .with_context(|| format!("credential '{}' not found in {}", name, dir.display()))?;
+    if !meta.is_file() {
+        bail!("credential '{}' in {} is not a regular file", name, dir.display());
+    }
+    if meta.len() > max_size as u64 {
+        bail!(
+            "policy: secret exceeds maximum size ({} bytes, max {} bytes)",
+            meta.len(),
+            max_size
+        );
+    }
+    let bytes = Zeroizing::new(fs::read(&path).with_context(|| format!("read {}", path.display()))?);
+    let text = std::str::from_utf8(&bytes)
+        .with_context(|| format!("credential '{}' is not valid UTF-8", name))?;
+    Ok(Zeroizing::new(text.trim_end_matches(['\r', '\n']).to_string()))
 }
 
-fn dedup(values: Vec<String>) -> Vec<String> {
-    let mut seen = HashSet::new();
-    values
-        .into_iter()
-        .filter(|v| seen.insert(v.clone()))
-        .collect()
+type BatchRecord = std::result::Result<(String, Zeroizing<String>), String>;
+
+/// Split a batch stream into `name=secret` records.
+///
+/// Records are separated by `\n` (a trailing `\r` is stripped) or by NUL when
+/// `null` is set. Blank records are ignored. Each record is validated on its
+/// own so one bad line does not abort the whole batch; secret sizes are
+/// checked against `policy`.
+fn parse_batch_records(data: &[u8], null: bool, policy: &PolicySection) -> Vec<BatchRecord> {
+    let delim = if null { b'\0' } else { b'\n' };
+    let mut records = Vec::new();
+
+    for (idx, raw) in data.split(|b| *b == delim).enumerate() {
+        let record_num = idx + 1;
+        let raw = if null {
+            raw
+        } else {
+            raw.strip_suffix(b"\r").unwrap_or(raw)
+        };
+        if raw.iter().all(|b| b.is_ascii_whitespace()) {
+            continue;
+        }
+
+        let text = match std::str::from_utf8(raw) {
+            Ok(t) => t,
+            Err(_) => {
+                records.push(Err(format!("record {}: not valid UTF-8", record_num)));
+                continue;
+            }
+        };
+        let (name, value) = match text.split_once('=') {
+            Some(pair) => pair,
+            None => {
+                records.push(Err(format!("record {}: expected name=secret", record_num)));
+                continue;
+            }
+        };
+        let name = match parse_credential_name(name.trim()) {
+            Ok(n) => n,
+            Err(e) => {
+                records.push(Err(format!("record {}: invalid name: {}", record_num, e)));
+                continue;
+            }
+        };
+        if value.is_empty() {
+            records.push(Err(format!("record {}: {}: secret is empty", record_num, name)));
+            continue;
+        }
+        if let Err(e) = policy.check_secret_size(value.len()) {
+            records.push(Err(format!("record {}: {}: {}", record_num, name, e)));
+            continue;
+        }
+        records.push(Ok((name, Zeroizing::new(value.to_string()))));
+    }
+
+    records
+}
+
+/// `list --stale` / `--expiring`. Whole days from `now`; a credential
+/// without the timestamp is neither stale nor expiring.
+fn matches_dates(
+    meta: &CredentialMeta,
+    stale_days: Option<u32>,
+    expiring_days: Option<u32>,
+    now: DateTime<Utc>,
+) -> bool {
+    if let Some(days) = stale_days {
+        let cutoff = now - chrono::Duration::days(i64::from(days));
+        let stale = meta.rotated_at.is_some_and(|t| t < cutoff);
+        if !stale {
+            return false;
+        }
+    }
+    if let Some(days) = expiring_days {
+        let horizon = now + chrono::Duration::days(i64::from(days));
+        let expiring = meta.expires_at.is_some_and(|t| t <= horizon);
+        if !expiring {
+            return false;
+        }
+    }
+    true
 }
 
 fn match_credential(meta: &CredentialMeta, query: &str) -> bool {
@@ -769,14 +1918,87 @@ fn match_credential(meta: &CredentialMeta, query: &str) -> bool {
     false
 }
 
-fn generate_secret(length: usize) -> String {
-    if length == 0 {
+/// Pick the charset from `--charset`/`--symbols`, honoring policy
+/// `required_secret_charset`.
+pub(crate) fn resolve_charset(
+    policy: &PolicySection,
+    charset: Option<SecretCharset>,
+    symbols: bool,
+) -> Result<SecretCharset> {
+    let requested = if symbols {
+        Some(SecretCharset::AlnumSymbols)
+    } else {
+        charset
+    };
+    let Some(required) = policy.required_secret_charset.as_deref() else {
+        return Ok(requested.unwrap_or(SecretCharset::Alnum));
+    };
+    let required_charset = parse_charset(required)
+        .map_err(|e| anyhow::anyhow!("policy: required_secret_charset: {}", e))?;
+    match requested {
+        Some(c) if c != required_charset => bail!(
+            "policy: auto-generated secrets must use charset '{}' (required_secret_charset enforced)",
+            required
+        ),
+        _ => Ok(required_charset),
+    }
+}
+
+/// Check an auto-secret length against `min_auto_secret_length` and the secret size limits.
+pub(crate) fn check_auto_length(policy: &PolicySection, length: usize) -> Result<()> {
+    if let Some(min_len) = policy.min_auto_secret_length {
+        if length < min_len {
+            bail!(
+                "policy: auto-generated secret length {} below minimum {} (set in vault.toml [policy])",
+                length,
+                min_len
+            );
+        }
+    }
+    // Generated characters are ASCII, so the length is the size in bytes.
+    Ok(policy.check_secret_size(length)?)
+}
+
+/// Preview for `create/rotate --dry-run`, in the same layout as `plan rotate`.
+fn print_dry_run(ctx: &CliContext, action: &str, name: &str, with_key: &str, auto_length: Option<usize>) {
+    let output = ctx.paths.credstore.join(format!("{}{}", name, constants::CRED_EXTENSION));
+    println!("Plan: {} '{}'", action, name);
+    println!("  exists: {}", output.is_file());
+    println!("  output: {}", output.display());
+    println!("  key_type: {}", with_key);
+    match auto_length {
+        Some(length) => println!("  auto: length={}", length),
+        None => println!("  source: stdin/prompt"),
+    }
+    println!("  status: ready");
+    println!("\nNo changes made (dry-run).");
+}
+
+/// Generate an auto secret after the policy checks (minimum length, and at
+/// least one symbol when `required_secret_charset` has symbols).
+fn generate_auto_secret(
+    policy: &PolicySection,
+    length: usize,
+    charset: &SecretCharset,
+) -> Result<Zeroizing<String>> {
+    check_auto_length(policy, length)?;
+    let force_symbol = policy.required_secret_charset.is_some() && charset.has_symbols();
+    loop {
+        let secret = Zeroizing::new(generate_secret(length, charset));
+        if !force_symbol || length == 0 || secret.chars().any(|c| !c.is_ascii_alphanumeric()) {
+            return Ok(secret);
+        }
+    }
+}
+
+/// Sample `length` characters uniformly from `charset` using the OS RNG.
+fn generate_secret(length: usize, charset: &SecretCharset) -> String {
+    let alphabet = charset.alphabet();
+    if length == 0 || alphabet.is_empty() {
         return String::new();
     }
-    OsRng
-        .sample_iter(&Alphanumeric)
-        .take(length)
-        .map(char::from)
+    (0..length)
+        .map(|_| alphabet[OsRng.gen_range(0..alphabet.len())])
         .collect()
 }
 
@@ -784,6 +2006,39 @@ fn generate_secret(length: usize) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_run_askpass() {
+        let dir = tempfile::tempdir().unwrap();
+        let prog = dir.path().join("askpass");
+        fs::write(&prog, "#!/bin/sh\nprintf 'from-askpass\\n'\n").unwrap();
+        vault_fs::set_permissions(&prog, 0o700).unwrap();
+        assert_eq!(run_askpass(&prog, "Secret: ").unwrap().as_str(), "from-askpass");
+
+        let failing = dir.path().join("cancel");
+        fs::write(&failing, "#!/bin/sh\nexit 1\n").unwrap();
+        vault_fs::set_permissions(&failing, 0o700).unwrap();
+        assert!(run_askpass(&failing, "Secret: ").is_err());
+    }
+
+    #[test]
+    fn test_read_loaded_credential() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("db_password"), "s3cret\n").unwrap();
+        fs::create_dir(dir.path().join("subdir")).unwrap();
+        let max = constants::MAX_SECRET_SIZE;
+
+        let secret = read_loaded_credential(dir.path(), "db_password", max).unwrap();
+        assert_eq!(secret.as_str(), "s3cret");
+
+        let err = read_loaded_credential(dir.path(), "missing", max).unwrap_err();
+        assert!(err.to_string().contains("not found"));
+        let err = read_loaded_credential(dir.path(), "subdir", max).unwrap_err();
+        assert!(err.to_string().contains("not a regular file"));
+        assert!(read_loaded_credential(dir.path(), "db_password", 7).is_ok());
+        let err = read_loaded_credential(dir.path(), "db_password", 6).unwrap_err();
+        assert!(err.to_string().contains("exceeds maximum size"));
+    }
+
     #[test]
     fn test_validate_name_valid() {
         assert!(validate_name("db_password").is_ok());
@@ -816,18 +2071,6 @@ mod tests {
         assert!(validate_name("foo!bar").is_err());
     }
 
-    #[test]
-    fn test_dedup_preserves_order() {
-        let input = vec!["b".into(), "a".into(), "b".into(), "c".into()];
-        assert_eq!(dedup(input), vec!["b", "a", "c"]);
-    }
-
-    #[test]
-    fn test_dedup_empty() {
-        let input: Vec<String> = vec![];
-        assert_eq!(dedup(input), Vec::<String>::new());
-    }
-
     #[test]
     fn test_match_credential_by_name() {
         let meta = CredentialMeta {
@@ -847,6 +2090,28 @@ mod tests {
         assert!(match_credential(&meta, "test"));
     }
 
+    #[test]
+    fn test_matches_dates() {
+        let now = Utc::now();
+        let meta = |rotated: Option<i64>, expires: Option<i64>| CredentialMeta {
+            name: "db".into(),
+            rotated_at: rotated.map(|d| now - chrono::Duration::days(d)),
+            expires_at: expires.map(|d| now + chrono::Duration::days(d)),
+            ..Default::default()
+        };
+        assert!(matches_dates(&meta(None, None), None, None, now));
+        assert!(matches_dates(&meta(Some(100), None), Some(90), None, now));
+        assert!(!matches_dates(&meta(Some(10), None), Some(90), None, now));
+        assert!(!matches_dates(&meta(None, None), Some(90), None, now));
+        assert!(matches_dates(&meta(None, Some(5)), None, Some(30), now));
+        assert!(matches_dates(&meta(None, Some(-1)), None, Some(30), now));
+        assert!(!matches_dates(&meta(None, Some(60)), None, Some(30), now));
+        assert!(!matches_dates(&meta(None, None), None, Some(30), now));
+        // Both filters: AND.
+        assert!(matches_dates(&meta(Some(100), Some(5)), Some(90), Some(30), now));
+        assert!(!matches_dates(&meta(Some(100), Some(60)), Some(90), Some(30), now));
+    }
+
     #[test]
     fn test_match_credential_case_insensitive() {
         let meta = CredentialMeta {
@@ -858,14 +2123,206 @@ mod tests {
 
     #[test]
     fn test_generate_secret_length() {
-        assert_eq!(generate_secret(32).len(), 32);
-        assert_eq!(generate_secret(0).len(), 0);
-        assert_eq!(generate_secret(1).len(), 1);
+        assert_eq!(generate_secret(32, &SecretCharset::Alnum).len(), 32);
+        assert_eq!(generate_secret(0, &SecretCharset::Alnum).len(), 0);
+        assert_eq!(generate_secret(1, &SecretCharset::Alnum).len(), 1);
+    }
+
+    #[test]
+    fn test_prepare_export_dir() {
+        let parent = tempfile::TempDir::new().unwrap();
+        let dir = parent.path().join("export");
+        prepare_export_dir(&dir, false).unwrap();
+        let mode = fs::metadata(&dir).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o700);
+
+        // Empty existing directory is fine; non-empty needs --force.
+        prepare_export_dir(&dir, false).unwrap();
+        fs::write(dir.join("db_pass"), "old").unwrap();
+        assert!(prepare_export_dir(&dir, false).is_err());
+        assert!(prepare_export_dir(&dir, true).is_ok());
+
+        assert!(prepare_export_dir(&dir.join("db_pass"), true).is_err());
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o730)).unwrap();
+        let err = prepare_export_dir(&dir, true).unwrap_err().to_string();
+        assert!(err.contains("group/other-writable"), "{}", err);
+    }
+
+    #[test]
+    fn test_prepare_output_dir_refuses_world_readable() {
+        let parent = tempfile::TempDir::new().unwrap();
+        let dir = parent.path().join("out");
+        prepare_output_dir(&dir).unwrap();
+        let mode = fs::metadata(&dir).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o700);
+        prepare_output_dir(&dir).unwrap();
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(prepare_output_dir(&dir).is_err());
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o750)).unwrap();
+        assert!(prepare_output_dir(&dir).is_ok());
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o770)).unwrap();
+        let err = prepare_output_dir(&dir).unwrap_err().to_string();
+        assert!(err.contains("group/other-writable"), "{}", err);
+    }
+
+    #[test]
+    fn test_create_secret_file_refuses_existing_and_symlinks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let victim = dir.path().join("victim");
+        fs::write(&victim, "keep").unwrap();
+        let link = dir.path().join("db_pass");
+        std::os::unix::fs::symlink(&victim, &link).unwrap();
+
+        assert!(create_secret_file(&link, false).is_err());
+        let mut file = create_secret_file(&link, true).unwrap();
+        file.write_all(b"secret").unwrap();
+        assert_eq!(fs::read_to_string(&victim).unwrap(), "keep");
+        assert!(!fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        let mode = fs::metadata(&link).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, constants::CRED_FILE_MODE);
+
+        assert!(create_secret_file(&link, false).is_err());
+    }
+
+    #[test]
+    fn test_newline_mode() {
+        assert!(parse_newline("auto").is_ok());
+        assert!(parse_newline("maybe").is_err());
+        assert_eq!(resolve_newline("auto", true), "yes");
+        assert_eq!(resolve_newline("auto", false), "no");
+        assert_eq!(resolve_newline("yes", false), "yes");
+        assert_eq!(resolve_newline("no", true), "no");
+    }
+
+    #[test]
+    fn test_parse_batch_records_newline() {
+        let data = b"db_pass=s3cr3t\r\n\napi.token=a=b\n";
+        let records = parse_batch_records(data, false, &PolicySection::default());
+        assert_eq!(records.len(), 2);
+        let (name, value) = records[0].as_ref().unwrap();
+        assert_eq!(name, "db_pass");
+        assert_eq!(value.as_str(), "s3cr3t");
+        let (name, value) = records[1].as_ref().unwrap();
+        assert_eq!(name, "api.token");
+        assert_eq!(value.as_str(), "a=b");
+    }
+
+    #[test]
+    fn test_parse_batch_records_null_keeps_newlines() {
+        let data = b"cert=line1\nline2\0key=abc\0";
+        let records = parse_batch_records(data, true, &PolicySection::default());
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].as_ref().unwrap().1.as_str(), "line1\nline2");
+    }
+
+    #[test]
+    fn test_parse_batch_records_invalid() {
+        let data = b"../etc=x\nnoequals\nempty=\nok=1\n";
+        let records = parse_batch_records(data, false, &PolicySection::default());
+        assert_eq!(records.len(), 4);
+        assert!(records[0].as_ref().unwrap_err().contains("invalid name"));
+        assert!(records[1].as_ref().unwrap_err().contains("expected name=secret"));
+        assert!(records[2].as_ref().unwrap_err().contains("secret is empty"));
+        assert!(records[3].is_ok());
+    }
+
+    #[test]
+    fn test_parse_batch_records_size_policy() {
+        let policy = PolicySection {
+            max_secret_size: Some(4),
+            min_secret_size: Some(2),
+            ..Default::default()
+        };
+        let records = parse_batch_records(b"a=1\nb=12\nc=1234\nd=12345\n", false, &policy);
+        assert!(records[0].as_ref().unwrap_err().contains("min_secret_size = 2"));
+        assert!(records[1].is_ok());
+        assert!(records[2].is_ok());
+        assert!(records[3].as_ref().unwrap_err().contains("max 4 bytes"));
     }
 
     #[test]
     fn test_generate_secret_alphanumeric() {
-        let s = generate_secret(100);
+        let s = generate_secret(100, &SecretCharset::Alnum);
         assert!(s.chars().all(|c| c.is_ascii_alphanumeric()));
     }
+
+    #[test]
+    fn test_generate_secret_charsets() {
+        let hex = generate_secret(64, &SecretCharset::Hex);
+        assert!(hex.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+
+        let custom = parse_charset("custom:ab").unwrap();
+        let s = generate_secret(64, &custom);
+        assert!(s.chars().all(|c| c == 'a' || c == 'b'));
+
+        let sym = generate_secret(200, &SecretCharset::AlnumSymbols);
+        assert!(sym
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || SECRET_SYMBOLS.contains(c)));
+    }
+
+    #[test]
+    fn test_parse_charset() {
+        assert_eq!(parse_charset("alnum").unwrap(), SecretCharset::Alnum);
+        assert_eq!(parse_charset("alnum-symbols").unwrap(), SecretCharset::AlnumSymbols);
+        assert_eq!(parse_charset("hex").unwrap(), SecretCharset::Hex);
+        assert_eq!(
+            parse_charset("custom:abca").unwrap(),
+            SecretCharset::Custom(vec!['a', 'b', 'c'])
+        );
+        assert!(parse_charset("custom:aaa").is_err());
+        assert!(parse_charset("custom:a b").is_err());
+        assert!(parse_charset("base64").is_err());
+    }
+
+    #[test]
+    fn test_service_drift() {
+        let metadata = vec!["api.service".to_string(), "old".to_string()];
+        let maps = vec!["api".to_string(), "worker".to_string()];
+        let (only_in_maps, only_in_metadata) = service_drift(&metadata, &maps);
+        assert_eq!(only_in_maps, vec!["worker"]);
+        assert_eq!(only_in_metadata, vec!["old"]);
+    }
+
+    #[test]
+    fn test_check_secret_strength() {
+        let mut policy = PolicySection::default();
+        assert!(check_secret_strength(&policy, "db", "aaaa").is_ok());
+
+        policy.min_secret_entropy_bits = Some(40);
+        assert!(check_secret_strength(&policy, "db", "aaaa").is_ok());
+        policy.reject_weak_secrets = true;
+        assert!(check_secret_strength(&policy, "db", "aaaa").is_err());
+        assert!(check_secret_strength(&policy, "db", "xK9#mQ2$vL7@pR4!").is_ok());
+    }
+
+    #[test]
+    fn test_required_secret_charset_policy() {
+        let mut policy = PolicySection::default();
+        assert_eq!(resolve_charset(&policy, None, false).unwrap(), SecretCharset::Alnum);
+        assert_eq!(resolve_charset(&policy, None, true).unwrap(), SecretCharset::AlnumSymbols);
+
+        policy.required_secret_charset = Some("alnum-symbols".to_string());
+        assert_eq!(resolve_charset(&policy, None, false).unwrap(), SecretCharset::AlnumSymbols);
+        assert!(resolve_charset(&policy, Some(SecretCharset::Hex), false).is_err());
+
+        for _ in 0..20 {
+            let s = generate_auto_secret(&policy, 4, &SecretCharset::AlnumSymbols).unwrap();
+            assert!(s.chars().any(|c| SECRET_SYMBOLS.contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_auto_length_respects_max_secret_size() {
+        let policy = PolicySection {
+            max_secret_size: Some(16),
+            ..Default::default()
+        };
+        assert!(check_auto_length(&policy, 16).is_ok());
+        let err = generate_auto_secret(&policy, 32, &SecretCharset::Alnum).unwrap_err();
+        assert!(err.to_string().starts_with("policy: secret exceeds maximum size"));
+    }
 }
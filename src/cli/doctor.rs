@@ -2,8 +2,12 @@
 
 use crate::cli::CliContext;
 use crate::constants;
-use anyhow::Result;
+use crate::core::audit_log;
+use crate::util::scratch;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
 use clap::Args;
+use serde::Serialize;
 use std::collections::BTreeSet;
 use std::env;
 use std::fs;
@@ -15,54 +19,170 @@ pub struct DoctorArgs {
     /// Also check for multiple goamet-vault binaries on PATH
     #[arg(long)]
     pub path: bool,
+
+    /// Output format (text|json)
+    #[arg(long, default_value = "text")]
+    pub format: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+    /// Informational; not counted in the summary.
+    Info,
+    /// Not applicable on this platform; not counted in the summary.
+    #[cfg_attr(unix, allow(dead_code))]
+    Skip,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+            Status::Info => "INFO",
+            Status::Skip => "SKIP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CheckResult {
+    check: &'static str,
+    status: Status,
+    detail: String,
+}
+
+impl CheckResult {
+    fn new(check: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            check,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+struct Summary {
+    pass: u32,
+    warn: u32,
+    fail: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DoctorReport {
+    vault: String,
+    checks: Vec<CheckResult>,
+    summary: Summary,
 }
 
 pub fn run(ctx: &CliContext, args: DoctorArgs) -> Result<()> {
+    if args.format != "text" && args.format != "json" {
+        bail!("invalid format: {} (use text|json)", args.format);
+    }
+    let checks = run_checks(ctx, &args);
+    let report = DoctorReport {
+        vault: ctx.paths.to_string(),
+        summary: summarize(&checks),
+        checks,
+    };
+
+    if args.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("Doctor: {}", report.vault);
+        for c in &report.checks {
+            println!("  [{}] {}", c.status.label(), c.detail);
+        }
+        println!();
+        println!(
+            "Doctor summary: {} pass, {} warn, {} fail",
+            report.summary.pass, report.summary.warn, report.summary.fail
+        );
+    }
+    if report.summary.fail > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn summarize(checks: &[CheckResult]) -> Summary {
+    let mut summary = Summary::default();
+    for c in checks {
+        match c.status {
+            Status::Pass => summary.pass += 1,
+            Status::Warn => summary.warn += 1,
+            Status::Fail => summary.fail += 1,
+            Status::Info | Status::Skip => {}
+        }
+    }
+    summary
+}
+
+fn run_checks(ctx: &CliContext, args: &DoctorArgs) -> Vec<CheckResult> {
     let paths = &ctx.paths;
-    let mut ok = 0u32;
-    let mut warn = 0u32;
-    let mut fail = 0u32;
+    let mut checks = Vec::new();
 
-    println!("Doctor: {}", paths);
     if let Some(w) = &ctx.policy_load_warning {
-        println!("  [WARN] {}", w);
+        checks.push(CheckResult::new("policy", Status::Warn, w.clone()));
     }
 
     // Vault directory existence checks
-    if paths.root.is_dir() {
-        println!("  [PASS] vault root exists: {}", paths.root.display());
-        ok += 1;
+    checks.push(if paths.root.is_dir() {
+        CheckResult::new(
+            "vault_root",
+            Status::Pass,
+            format!("vault root exists: {}", paths.root.display()),
+        )
     } else {
-        println!("  [FAIL] vault root missing: {}", paths.root.display());
-        fail += 1;
-    }
+        CheckResult::new(
+            "vault_root",
+            Status::Fail,
+            format!("vault root missing: {}", paths.root.display()),
+        )
+    });
 
-    if paths.credstore.is_dir() {
-        println!("  [PASS] credstore exists: {}", paths.credstore.display());
-        ok += 1;
+    checks.push(if paths.credstore.is_dir() {
+        CheckResult::new(
+            "credstore",
+            Status::Pass,
+            format!("credstore exists: {}", paths.credstore.display()),
+        )
     } else {
-        println!("  [WARN] credstore missing: {}", paths.credstore.display());
-        warn += 1;
-    }
+        CheckResult::new(
+            "credstore",
+            Status::Warn,
+            format!("credstore missing: {}", paths.credstore.display()),
+        )
+    });
 
     // systemd-creds existence
-    if Command::new("systemd-creds").arg("--version").output().is_ok() {
-        println!("  [PASS] systemd-creds available");
-        ok += 1;
+    checks.push(if Command::new("systemd-creds").arg("--version").output().is_ok() {
+        CheckResult::new("systemd_creds", Status::Pass, "systemd-creds available")
     } else {
-        println!("  [FAIL] systemd-creds not found on PATH");
-        fail += 1;
-    }
+        CheckResult::new("systemd_creds", Status::Fail, "systemd-creds not found on PATH")
+    });
 
     // Host key presence (best-effort, might require root to inspect perms but exists() is fine)
     let host_key = Path::new(constants::HOST_KEY_PATH);
-    if host_key.exists() {
-        println!("  [PASS] host key exists: {}", host_key.display());
-        ok += 1;
+    checks.push(if host_key.exists() {
+        CheckResult::new(
+            "host_key",
+            Status::Pass,
+            format!("host key exists: {}", host_key.display()),
+        )
     } else {
-        println!("  [WARN] host key missing: {} (run: systemd-creds setup)", host_key.display());
-        warn += 1;
-    }
+        CheckResult::new(
+            "host_key",
+            Status::Warn,
+            format!("host key missing: {} (run: systemd-creds setup)", host_key.display()),
+        )
+    });
 
     // Permission checks (best-effort; if not accessible, just warn)
     if let Ok(meta) = fs::metadata(&paths.credstore) {
@@ -70,46 +190,119 @@ pub fn run(ctx: &CliContext, args: DoctorArgs) -> Result<()> {
         {
             use std::os::unix::fs::PermissionsExt;
             let mode = meta.permissions().mode() & 0o777;
-            if mode == constants::CREDSTORE_DIR_MODE {
-                println!("  [PASS] credstore mode ok: {:04o}", mode);
-                ok += 1;
+            checks.push(if mode == constants::CREDSTORE_DIR_MODE {
+                CheckResult::new(
+                    "credstore_mode",
+                    Status::Pass,
+                    format!("credstore mode ok: {:04o}", mode),
+                )
             } else {
-                println!(
-                    "  [WARN] credstore mode: {:04o} (expected {:04o})",
-                    mode,
-                    constants::CREDSTORE_DIR_MODE
-                );
-                warn += 1;
+                CheckResult::new(
+                    "credstore_mode",
+                    Status::Warn,
+                    format!(
+                        "credstore mode: {:04o} (expected {:04o})",
+                        mode,
+                        constants::CREDSTORE_DIR_MODE
+                    ),
+                )
+            });
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = meta;
+            checks.push(CheckResult::new(
+                "credstore_mode",
+                Status::Skip,
+                "permission checks not supported on this platform",
+            ));
+        }
+    }
+
+    let scratch = scratch::resolve(&paths.credstore);
+    checks.push(if scratch.runtime {
+        CheckResult::new(
+            "scratch_dir",
+            Status::Pass,
+            format!("scratch dir: {} (private runtime dir)", scratch.path.display()),
+        )
+    } else {
+        CheckResult::new(
+            "scratch_dir",
+            Status::Info,
+            format!(
+                "scratch dir: {} (no private runtime dir; plaintext temp files use the credstore)",
+                scratch.path.display()
+            ),
+        )
+    });
+
+    // Clock skew: a clock that moved backward gives new audit entries
+    // timestamps earlier than existing ones.
+    match audit_log::read_log(paths, Some(1)) {
+        Ok(entries) => {
+            if let Some(last) = entries.last() {
+                checks.push(clock_check(last.timestamp, Utc::now()));
             }
         }
+        Err(e) => checks.push(CheckResult::new(
+            "clock",
+            Status::Info,
+            format!("clock check skipped: audit log not readable ({:#})", e),
+        )),
     }
 
     if args.path {
         let bins = find_bins_on_path("goamet-vault");
         if bins.is_empty() {
-            println!("  [WARN] goamet-vault not found on PATH");
-            warn += 1;
+            checks.push(CheckResult::new(
+                "path",
+                Status::Warn,
+                "goamet-vault not found on PATH",
+            ));
         } else {
-            println!("  [INFO] goamet-vault binaries on PATH:");
-            for b in &bins {
-                println!("    - {}", b.display());
-            }
-            if bins.len() > 1 {
-                println!("  [WARN] multiple binaries detected; automation should pin /usr/local/bin/goamet-vault");
-                warn += 1;
+            let listed: Vec<String> = bins.iter().map(|b| b.display().to_string()).collect();
+            checks.push(if bins.len() > 1 {
+                CheckResult::new(
+                    "path",
+                    Status::Warn,
+                    format!(
+                        "multiple goamet-vault binaries on PATH ({}); automation should pin /usr/local/bin/goamet-vault",
+                        listed.join(", ")
+                    ),
+                )
             } else {
-                ok += 1;
-            }
+                CheckResult::new(
+                    "path",
+                    Status::Pass,
+                    format!("goamet-vault on PATH: {}", listed[0]),
+                )
+            });
         }
     }
 
-    // Summary
-    println!();
-    println!("Doctor summary: {} pass, {} warn, {} fail", ok, warn, fail);
-    if fail > 0 {
-        std::process::exit(1);
+    checks
+}
+
+/// Compare `now` to the timestamp of the last audit entry.
+fn clock_check(last_entry: DateTime<Utc>, now: DateTime<Utc>) -> CheckResult {
+    if now < last_entry {
+        CheckResult::new(
+            "clock",
+            Status::Warn,
+            format!(
+                "system clock appears to have moved backward (now {}, last audit entry {})",
+                now.to_rfc3339(),
+                last_entry.to_rfc3339()
+            ),
+        )
+    } else {
+        CheckResult::new(
+            "clock",
+            Status::Pass,
+            format!("clock is not behind the last audit entry ({})", last_entry.to_rfc3339()),
+        )
     }
-    Ok(())
 }
 
 fn find_bins_on_path(name: &str) -> Vec<PathBuf> {
@@ -142,3 +335,35 @@ fn is_executable_file(path: &Path) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_skips_info_and_json_shape() {
+        let checks = vec![
+            CheckResult::new("vault_root", Status::Pass, "vault root exists: /v"),
+            CheckResult::new("host_key", Status::Warn, "host key missing"),
+            CheckResult::new("scratch_dir", Status::Info, "scratch dir: /v/credstore"),
+            CheckResult::new("systemd_creds", Status::Fail, "systemd-creds not found on PATH"),
+        ];
+        assert_eq!(summarize(&checks), Summary { pass: 1, warn: 1, fail: 1 });
+
+        let json = serde_json::to_value(&checks[1]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"check": "host_key", "status": "warn", "detail": "host key missing"})
+        );
+    }
+
+    #[test]
+    fn test_clock_check_warns_when_now_precedes_last_entry() {
+        use chrono::Duration;
+        let last = Utc::now();
+        let check = clock_check(last, last - Duration::minutes(5));
+        assert_eq!(check.status, Status::Warn);
+        assert!(check.detail.starts_with("system clock appears to have moved backward"));
+        assert_eq!(clock_check(last, last).status, Status::Pass);
+    }
+}
@@ -1,11 +1,13 @@
 use crate::cli::CliContext;
 use crate::constants;
-use crate::core::dropin_gen::generate_dropin;
+use crate::core::dropin_gen::generate_dropin_with_inline;
 use crate::core::file_lock::FileLock;
 use crate::core::paths::VaultPaths;
+use crate::core::service_map;
 use crate::util::fs as vault_fs;
 use anyhow::{bail, Context, Result};
 use clap::{Args, Subcommand};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -18,6 +20,8 @@ pub enum DropinCommand {
     Apply(DropinApplyArgs),
     /// Show diff between generated and installed drop-in
     Diff(DropinDiffArgs),
+    /// Remove the installed drop-in from /etc/systemd/system and reload
+    Uninstall(DropinUninstallArgs),
 }
 
 #[derive(Args, Debug)]
@@ -44,6 +48,10 @@ pub struct DropinGenerateArgs {
     #[arg(long)]
     pub no_hardening: bool,
 
+    /// Inline .cred contents via SetCredentialEncrypted= instead of LoadCredentialEncrypted=
+    #[arg(long)]
+    pub inline: bool,
+
     /// Also install the drop-in to /etc/systemd/system and reload
     #[arg(long)]
     pub apply: bool,
@@ -69,6 +77,10 @@ pub struct DropinApplyArgs {
     #[arg(long)]
     pub no_hardening: bool,
 
+    /// Inline .cred contents via SetCredentialEncrypted= instead of LoadCredentialEncrypted=
+    #[arg(long)]
+    pub inline: bool,
+
     /// Required confirmation because this writes to /etc/systemd/system and reloads systemd
     #[arg(long)]
     pub confirm: bool,
@@ -91,6 +103,19 @@ pub struct DropinDiffArgs {
     /// Disable hardening flags in the drop-in
     #[arg(long)]
     pub no_hardening: bool,
+
+    /// Inline .cred contents via SetCredentialEncrypted= instead of LoadCredentialEncrypted=
+    #[arg(long)]
+    pub inline: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DropinUninstallArgs {
+    pub service: String,
+
+    /// Required confirmation because this removes files from /etc/systemd/system and reloads systemd
+    #[arg(long)]
+    pub confirm: bool,
 }
 
 pub fn run(ctx: &CliContext, cmd: DropinCommand) -> Result<()> {
@@ -117,11 +142,18 @@ pub fn run(ctx: &CliContext, cmd: DropinCommand) -> Result<()> {
                 out_dir: args.out_dir,
                 no_env: args.no_env,
                 no_hardening: args.no_hardening,
+                inline: args.inline,
                 apply: true,
             };
             run_generate(paths, gen, true, true)
         }
         DropinCommand::Diff(args) => run_diff(paths, args),
+        DropinCommand::Uninstall(args) => {
+            if !args.confirm {
+                bail!("refusing to remove from /etc/systemd/system without --confirm");
+            }
+            run_uninstall(ctx, args)
+        }
     }
 }
 
@@ -156,7 +188,7 @@ fn run_generate(paths: &VaultPaths, args: DropinGenerateArgs, apply: bool, use_l
         .with_context(|| format!("create output dir {}", out_dir.display()))?;
     let out_file = out_dir.join("credentials.conf");
 
-    let dropin = generate_dropin(&map_file, &cred_dir, args.no_env, !args.no_hardening)?;
+    let dropin = render_dropin(&map_file, &cred_dir, args.no_env, !args.no_hardening, args.inline)?;
     fs::write(&out_file, dropin).with_context(|| format!("write {}", out_file.display()))?;
     println!("Wrote {}", out_file.display());
 
@@ -184,7 +216,7 @@ fn run_diff(paths: &VaultPaths, args: DropinDiffArgs) -> Result<()> {
         bail!("map file not found: {}", map_file.display());
     }
 
-    let generated = generate_dropin(&map_file, &cred_dir, args.no_env, !args.no_hardening)?;
+    let generated = render_dropin(&map_file, &cred_dir, args.no_env, !args.no_hardening, args.inline)?;
     let target_file = PathBuf::from(format!(
         "/etc/systemd/system/{}.d/credentials.conf",
         unit_name
@@ -211,6 +243,46 @@ fn run_diff(paths: &VaultPaths, args: DropinDiffArgs) -> Result<()> {
     Ok(())
 }
 
+/// Parse the map, warn about problems, and render the drop-in text.
+fn render_dropin(
+    map_file: &Path,
+    cred_dir: &Path,
+    no_env: bool,
+    hardening: bool,
+    inline: bool,
+) -> Result<String> {
+    let entries = service_map::parse_service_map(map_file, cred_dir)
+        .with_context(|| format!("parse map file {}", map_file.display()))?;
+    if !no_env {
+        for w in service_map::env_var_collisions(&entries) {
+            eprintln!("warning: {}: {}", map_file.display(), w.message);
+        }
+    }
+
+    let mut blobs = HashMap::new();
+    if inline {
+        for entry in &entries {
+            let size = fs::metadata(&entry.cred_path)
+                .with_context(|| format!("stat {}", entry.cred_path.display()))?
+                .len();
+            if size > constants::MAX_INLINE_CRED_SIZE {
+                eprintln!(
+                    "warning: {} is {} bytes (max {} for --inline); using LoadCredentialEncrypted=",
+                    entry.cred_path.display(),
+                    size,
+                    constants::MAX_INLINE_CRED_SIZE
+                );
+                continue;
+            }
+            let text = fs::read_to_string(&entry.cred_path)
+                .with_context(|| format!("read {}", entry.cred_path.display()))?;
+            blobs.insert(entry.cred_name.clone(), text);
+        }
+    }
+
+    Ok(generate_dropin_with_inline(&entries, &blobs, no_env, hardening))
+}
+
 fn print_diff(current: &str, generated: &str) {
     println!("--- current");
     println!("+++ generated");
@@ -231,6 +303,66 @@ fn apply_dropin(unit_name: &str, source: &Path) -> Result<()> {
         .with_context(|| format!("copy to {}", target_file.display()))?;
     vault_fs::set_permissions(&target_file, constants::CRED_FILE_MODE)?;
 
+    daemon_reload();
+
+    println!("Installed {}", target_file.display());
+    Ok(())
+}
+
+/// Install unit files (e.g. a timer and its service) into
+/// /etc/systemd/system and reload systemd. Returns the installed paths.
+pub(crate) fn install_units(sources: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let target_dir = Path::new("/etc/systemd/system");
+    let mut installed = Vec::new();
+    for source in sources {
+        let file_name = source
+            .file_name()
+            .with_context(|| format!("not a unit file: {}", source.display()))?;
+        let target = target_dir.join(file_name);
+        fs::copy(source, &target).with_context(|| format!("copy to {}", target.display()))?;
+        vault_fs::set_permissions(&target, constants::UNIT_FILE_MODE)?;
+        println!("Installed {}", target.display());
+        installed.push(target);
+    }
+    daemon_reload();
+    Ok(installed)
+}
+
+fn run_uninstall(ctx: &CliContext, args: DropinUninstallArgs) -> Result<()> {
+    let (unit_name, _) = normalize_service_name(&args.service);
+    let audit = ctx.audit_ctx("dropin-uninstall", &unit_name);
+    ctx.audited(audit, |_| uninstall(ctx, &unit_name))
+}
+
+fn uninstall(ctx: &CliContext, unit_name: &str) -> Result<()> {
+    let _vault_lock = FileLock::exclusive(&ctx.paths.vault_lock)?;
+    let target_dir = PathBuf::from(format!("/etc/systemd/system/{}.d", unit_name));
+    let target_file = target_dir.join("credentials.conf");
+
+    if !target_file.is_file() {
+        println!("No installed drop-in at {}; nothing to do", target_file.display());
+        return Ok(());
+    }
+
+    fs::remove_file(&target_file)
+        .with_context(|| format!("remove {}", target_file.display()))?;
+    println!("Removed {}", target_file.display());
+
+    // Prune the drop-in directory only if nothing else lives there.
+    let is_empty = fs::read_dir(&target_dir)
+        .map(|mut d| d.next().is_none())
+        .unwrap_or(false);
+    if is_empty {
+        fs::remove_dir(&target_dir)
+            .with_context(|| format!("remove {}", target_dir.display()))?;
+        println!("Removed empty {}", target_dir.display());
+    }
+
+    daemon_reload();
+    Ok(())
+}
+
+fn daemon_reload() {
     if systemctl_available() {
         let status = Command::new("systemctl").arg("daemon-reload").status();
         if let Ok(status) = status {
@@ -239,9 +371,6 @@ fn apply_dropin(unit_name: &str, source: &Path) -> Result<()> {
             }
         }
     }
-
-    println!("Installed {}", target_file.display());
-    Ok(())
 }
 
 fn resolve_path(root: &Path, path: PathBuf) -> PathBuf {
@@ -252,7 +381,7 @@ fn resolve_path(root: &Path, path: PathBuf) -> PathBuf {
     }
 }
 
-fn normalize_service_name(service: &str) -> (String, String) {
+pub(crate) fn normalize_service_name(service: &str) -> (String, String) {
     if let Some(stripped) = service.strip_suffix(".service") {
         (service.to_string(), stripped.to_string())
     } else {
@@ -260,7 +389,7 @@ fn normalize_service_name(service: &str) -> (String, String) {
     }
 }
 
-fn systemctl_available() -> bool {
+pub(crate) fn systemctl_available() -> bool {
     Command::new("systemctl")
         .arg("--version")
         .output()
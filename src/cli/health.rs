@@ -1,8 +1,11 @@
 use crate::cli::CliContext;
 use crate::constants;
-use crate::core::{credstore, metadata, service_map};
+use crate::core::credstore::{self, Integrity};
+use crate::core::{metadata, service_map};
+use crate::models::credential::CredentialMeta;
 use crate::util::systemd;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use clap::Args;
 use std::fs;
 use std::path::Path;
@@ -15,12 +18,22 @@ pub struct HealthArgs {
     /// Try to decrypt each .cred file (slower but thorough)
     #[arg(long)]
     pub decrypt: bool,
+
+    /// Warn about credentials not rotated in this many days (overrides
+    /// policy max_credential_age_days)
+    #[arg(long, value_name = "DAYS")]
+    pub age_warn: Option<u32>,
+
+    /// Report stale credentials as [FAIL] instead of [WARN]
+    #[arg(long)]
+    pub strict: bool,
 }
 
 pub fn run(ctx: &CliContext, args: HealthArgs) -> Result<()> {
     let paths = &ctx.paths;
     let mut passed = 0u32;
     let mut failed = 0u32;
+    let mut skipped = 0u32;
 
     // 1. Check host key
     let host_key = Path::new(constants::HOST_KEY_PATH);
@@ -52,14 +65,19 @@ pub fn run(ctx: &CliContext, args: HealthArgs) -> Result<()> {
 
     // 2. Check credstore permissions
     if paths.credstore.is_dir() {
-        let ok = check_mode(&paths.credstore, 0o700);
-        if ok {
-            println!("  [PASS] Credstore permissions: 0700");
-            passed += 1;
-        } else {
-            let actual = get_mode(&paths.credstore).unwrap_or(0);
-            println!("  [FAIL] Credstore permissions: {:04o} (expected 0700)", actual);
-            failed += 1;
+        match check_mode(&paths.credstore, &[0o700]) {
+            ModeCheck::Ok => {
+                println!("  [PASS] Credstore permissions: 0700");
+                passed += 1;
+            }
+            ModeCheck::Mismatch(actual) => {
+                println!("  [FAIL] Credstore permissions: {:04o} (expected 0700)", actual);
+                failed += 1;
+            }
+            ModeCheck::Skipped => {
+                println!("  [SKIP] Credstore permissions: checks not supported on this platform");
+                skipped += 1;
+            }
         }
     } else {
         println!("  [FAIL] Credstore directory missing: {}", paths.credstore.display());
@@ -68,18 +86,23 @@ pub fn run(ctx: &CliContext, args: HealthArgs) -> Result<()> {
 
     // 3. Check vault.toml permissions
     if paths.vault_toml.exists() {
-        let ok = check_mode_one_of(&paths.vault_toml, &[0o600, constants::VAULT_TOML_MODE]);
-        if ok {
-            println!("  [PASS] vault.toml permissions: 0600/0640");
-            passed += 1;
-        } else {
-            let actual = get_mode(&paths.vault_toml).unwrap_or(0);
-            println!(
-                "  [FAIL] vault.toml permissions: {:04o} (expected 0600 or {:04o})",
-                actual,
-                constants::VAULT_TOML_MODE
-            );
-            failed += 1;
+        match check_mode(&paths.vault_toml, &[0o600, constants::VAULT_TOML_MODE]) {
+            ModeCheck::Ok => {
+                println!("  [PASS] vault.toml permissions: 0600/0640");
+                passed += 1;
+            }
+            ModeCheck::Mismatch(actual) => {
+                println!(
+                    "  [FAIL] vault.toml permissions: {:04o} (expected 0600 or {:04o})",
+                    actual,
+                    constants::VAULT_TOML_MODE
+                );
+                failed += 1;
+            }
+            ModeCheck::Skipped => {
+                println!("  [SKIP] vault.toml permissions: checks not supported on this platform");
+                skipped += 1;
+            }
         }
     } else {
         println!("  [WARN] vault.toml not found (not initialized?)");
@@ -92,8 +115,7 @@ pub fn run(ctx: &CliContext, args: HealthArgs) -> Result<()> {
             println!("  [WARN] No .cred files in credstore");
         }
         for entry in &creds {
-            let tmp = tempfile::NamedTempFile::new()?;
-            match systemd::decrypt_to_file(&entry.path, tmp.path()) {
+            match systemd::verify_decryptable(&entry.path, None) {
                 Ok(()) => {
                     println!("  [PASS] Decryptable: {}", entry.name);
                     passed += 1;
@@ -109,6 +131,55 @@ pub fn run(ctx: &CliContext, args: HealthArgs) -> Result<()> {
         println!("  [INFO] {} .cred files found (use --decrypt to verify)", creds.len());
     }
 
+    // 4b. Ciphertext checksums recorded when the vault last wrote each .cred
+    if paths.vault_toml.exists() && paths.credstore.is_dir() {
+        let vault = metadata::load(&paths.vault_toml)?;
+        let mut matched = 0u32;
+        let mut mismatched = 0u32;
+        let mut unrecorded = 0u32;
+        for cred in &vault.credentials {
+            let path = credstore::cred_path(&paths.credstore, &cred.name);
+            match credstore::check_integrity(cred, &path) {
+                Integrity::Match => matched += 1,
+                Integrity::Unrecorded => unrecorded += 1,
+                // A missing .cred is reported by the service map checks.
+                Integrity::Unreadable => {}
+                Integrity::Corrupted => {
+                    println!(
+                        "  [FAIL] Checksum mismatch: {} ({} is corrupted)",
+                        cred.name,
+                        path.display()
+                    );
+                    mismatched += 1;
+                }
+                Integrity::ModifiedOutOfBand {
+                    modified,
+                    rotated_at,
+                } => {
+                    println!(
+                        "  [FAIL] TAMPERED? {} was replaced outside the vault: {} modified {}, last vault write {}",
+                        cred.name,
+                        path.display(),
+                        modified.to_rfc3339(),
+                        rotated_at.to_rfc3339()
+                    );
+                    mismatched += 1;
+                }
+            }
+        }
+        failed += mismatched;
+        if matched > 0 && mismatched == 0 {
+            println!("  [PASS] Ciphertext checksums match ({} credential(s))", matched);
+            passed += 1;
+        }
+        if unrecorded > 0 {
+            println!(
+                "  [INFO] {} credential(s) have no recorded checksum (recorded on the next rotate)",
+                unrecorded
+            );
+        }
+    }
+
     // 5. Check service map files consistent with vault.toml
     if paths.vault_toml.exists() && paths.services.is_dir() {
         let vault = metadata::load(&paths.vault_toml)?;
@@ -142,8 +213,11 @@ pub fn run(ctx: &CliContext, args: HealthArgs) -> Result<()> {
                             failed += 1;
                         }
                     }
-                    // Report missing .cred files as warnings
-                    for w in warnings.iter().filter(|w| w.message.contains(".cred file not found")) {
+                    // Report missing .cred files and shadowed env vars as warnings
+                    for w in warnings.iter().filter(|w| {
+                        w.message.contains(".cred file not found")
+                            || w.message.contains("env var collision")
+                    }) {
                         println!("  [WARN] Service map {}: {}", svc, w.message);
                     }
                 }
@@ -175,72 +249,186 @@ pub fn run(ctx: &CliContext, args: HealthArgs) -> Result<()> {
         }
     }
 
+    // 6b. PCR bindings that differ from the host default
+    if paths.vault_toml.exists() {
+        let vault = metadata::load(&paths.vault_toml)?;
+        let default = ctx.policy.default_tpm2_pcrs.as_deref();
+        let drifted: Vec<_> = vault
+            .credentials
+            .iter()
+            .filter(|c| c.encryption_key.as_deref().is_some_and(|k| k != "host"))
+            .filter(|c| !systemd::same_tpm2_pcrs(c.tpm2_pcrs.as_deref(), default))
+            .collect();
+        for cred in &drifted {
+            println!(
+                "  [WARN] {} is bound to PCRs {} but the host default is {} (rotate with --tpm2-pcrs to rebind)",
+                cred.name,
+                cred.tpm2_pcrs.as_deref().unwrap_or("(systemd-creds default)"),
+                default.unwrap_or("(systemd-creds default)")
+            );
+        }
+    }
+
+    // 6c. Rotation age
+    let mut oldest = None;
+    if paths.vault_toml.exists() {
+        let vault = metadata::load(&paths.vault_toml)?;
+        let ages = credential_ages(&vault.credentials, Utc::now());
+        if let Some(max_days) = args.age_warn.or(ctx.policy.max_credential_age_days) {
+            let stale: Vec<_> = ages
+                .iter()
+                .filter(|(_, days)| *days > i64::from(max_days))
+                .collect();
+            if stale.is_empty() {
+                println!("  [PASS] All credentials rotated within {} days", max_days);
+                passed += 1;
+            }
+            for (name, days) in stale {
+                let level = if args.strict { "FAIL" } else { "WARN" };
+                println!("  [{}] {} not rotated for {} days (max {})", level, name, days, max_days);
+                if args.strict {
+                    failed += 1;
+                }
+            }
+        }
+        oldest = ages.into_iter().next();
+    }
+
     // 7. Policy warnings
     if ctx.policy.forbid_host_only_when_tpm2 && !tpm2_available {
         println!("  [WARN] Policy 'forbid_host_only_when_tpm2' set but TPM2 not available");
     }
+    if ctx.policy.require_tpm2 && !tpm2_available {
+        println!("  [WARN] Policy 'require_tpm2' set but TPM2 not available (create/rotate will fail)");
+    }
 
     // 8. Check audit.log permissions (if exists)
     let audit_path = paths.root.join("audit.log");
     if audit_path.exists() {
-        let ok = check_mode_one_of(&audit_path, &[0o600, constants::AUDIT_LOG_MODE]);
-        if ok {
-            println!("  [PASS] audit.log permissions: 0600/0640");
-            passed += 1;
-        } else {
-            let actual = get_mode(&audit_path).unwrap_or(0);
-            println!(
-                "  [FAIL] audit.log permissions: {:04o} (expected 0600 or {:04o})",
-                actual,
-                constants::AUDIT_LOG_MODE
-            );
-            failed += 1;
+        match check_mode(&audit_path, &[0o600, constants::AUDIT_LOG_MODE]) {
+            ModeCheck::Ok => {
+                println!("  [PASS] audit.log permissions: 0600/0640");
+                passed += 1;
+            }
+            ModeCheck::Mismatch(actual) => {
+                println!(
+                    "  [FAIL] audit.log permissions: {:04o} (expected 0600 or {:04o})",
+                    actual,
+                    constants::AUDIT_LOG_MODE
+                );
+                failed += 1;
+            }
+            ModeCheck::Skipped => {
+                println!("  [SKIP] audit.log permissions: checks not supported on this platform");
+                skipped += 1;
+            }
         }
     }
 
     // Summary
     println!();
-    if failed == 0 {
-        println!("Health check: {} passed, 0 failed", passed);
-    } else {
-        println!("Health check: {} passed, {} failed", passed, failed);
+    println!("{}", summary_line(passed, failed, skipped));
+    if let Some((name, days)) = oldest {
+        println!("Oldest credential: {} ({} days since last rotation)", name, days);
     }
 
     Ok(())
 }
 
-#[cfg(unix)]
-fn check_mode(path: &Path, expected: u32) -> bool {
-    fs::metadata(path)
-        .ok()
-        .map(|m| m.permissions().mode() & 0o777 == expected)
-        .unwrap_or(false)
+/// Skipped checks are counted on their own: they are neither a pass nor a fail.
+fn summary_line(passed: u32, failed: u32, skipped: u32) -> String {
+    let mut line = format!("Health check: {} passed, {} failed", passed, failed);
+    if skipped > 0 {
+        line.push_str(&format!(", {} skipped", skipped));
+    }
+    line
 }
 
-#[cfg(unix)]
-fn check_mode_one_of(path: &Path, expected: &[u32]) -> bool {
-    fs::metadata(path)
-        .ok()
-        .map(|m| {
-            let mode = m.permissions().mode() & 0o777;
-            expected.iter().any(|e| *e == mode)
+/// Days since each credential was rotated (or created), oldest first.
+/// Credentials without either timestamp are left out.
+fn credential_ages(creds: &[CredentialMeta], now: DateTime<Utc>) -> Vec<(String, i64)> {
+    let mut ages: Vec<_> = creds
+        .iter()
+        .filter_map(|c| {
+            let at = c.rotated_at.or(c.created_at)?;
+            Some((c.name.clone(), (now - at).num_days()))
         })
-        .unwrap_or(false)
+        .collect();
+    ages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ages
 }
 
-#[cfg(not(unix))]
-fn check_mode(_path: &Path, _expected: u32) -> bool {
-    true
+/// Outcome of a permission check. Platforms without Unix modes cannot check
+/// anything, which must not be reported as a pass.
+#[derive(Debug, PartialEq, Eq)]
+enum ModeCheck {
+    Ok,
+    /// Actual mode; 0 when the file could not be inspected.
+    Mismatch(u32),
+    #[cfg_attr(unix, allow(dead_code))]
+    Skipped,
 }
 
 #[cfg(unix)]
-fn get_mode(path: &Path) -> Option<u32> {
-    fs::metadata(path)
-        .ok()
-        .map(|m| m.permissions().mode() & 0o777)
+fn check_mode(path: &Path, expected: &[u32]) -> ModeCheck {
+    match fs::metadata(path).map(|m| m.permissions().mode() & 0o777) {
+        Ok(mode) if expected.contains(&mode) => ModeCheck::Ok,
+        Ok(mode) => ModeCheck::Mismatch(mode),
+        Err(_) => ModeCheck::Mismatch(0),
+    }
 }
 
 #[cfg(not(unix))]
-fn get_mode(_path: &Path) -> Option<u32> {
-    None
+fn check_mode(_path: &Path, _expected: &[u32]) -> ModeCheck {
+    ModeCheck::Skipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_counts_skipped_separately() {
+        assert_eq!(summary_line(3, 0, 0), "Health check: 3 passed, 0 failed");
+        assert_eq!(summary_line(1, 1, 2), "Health check: 1 passed, 1 failed, 2 skipped");
+    }
+
+    #[test]
+    fn test_credential_ages_fall_back_to_created_at() {
+        let now = Utc::now();
+        let days = |d: i64| Some(now - chrono::Duration::days(d));
+        let creds = vec![
+            CredentialMeta {
+                name: "fresh".into(),
+                created_at: days(400),
+                rotated_at: days(2),
+                ..Default::default()
+            },
+            CredentialMeta {
+                name: "never-rotated".into(),
+                created_at: days(120),
+                ..Default::default()
+            },
+            CredentialMeta {
+                name: "imported".into(),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            credential_ages(&creds, now),
+            vec![("never-rotated".to_string(), 120), ("fresh".to_string(), 2)]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_mode() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("f");
+        fs::write(&path, "").unwrap();
+        crate::util::fs::set_permissions(&path, 0o640).unwrap();
+        assert_eq!(check_mode(&path, &[0o600, 0o640]), ModeCheck::Ok);
+        assert_eq!(check_mode(&path, &[0o600]), ModeCheck::Mismatch(0o640));
+        assert_eq!(check_mode(&dir.path().join("missing"), &[0o600]), ModeCheck::Mismatch(0));
+    }
 }
@@ -2,14 +2,19 @@ use crate::cli::CliContext;
 use crate::constants;
 use crate::core::metadata;
 use crate::util::{fs as vault_fs, systemd};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Args;
+use std::path::Path;
 
 #[derive(Args, Debug)]
 pub struct InitArgs {
     /// Run systemd-creds setup to ensure host key exists
     #[arg(long)]
     pub setup: bool,
+
+    /// Collapse duplicate credential names in vault.toml, keeping the newest
+    #[arg(long)]
+    pub repair: bool,
 }
 
 pub fn run(ctx: &CliContext, args: InitArgs) -> Result<()> {
@@ -18,10 +23,31 @@ pub fn run(ctx: &CliContext, args: InitArgs) -> Result<()> {
     vault_fs::ensure_dir(&paths.services, constants::SERVICES_DIR_MODE)?;
     vault_fs::ensure_dir(&paths.units, constants::UNITS_DIR_MODE)?;
 
-    let mut vault = metadata::load(&paths.vault_toml)?;
-    metadata::ensure_vault_section(&mut vault, Some(paths.credstore.display().to_string()));
+    let mut vault = if args.repair {
+        let (vault, dups) = metadata::load_repair(&paths.vault_toml)?;
+        for name in &dups {
+            println!("Repaired duplicate metadata for '{}' (kept newest)", name);
+        }
+        vault
+    } else {
+        metadata::load(&paths.vault_toml)?
+    };
+    // Older releases recorded the default credstore, which later runs from
+    // another root would take as an override.
+    let default = paths.root.join("credstore");
+    let recorded = vault.vault.credstore_path.as_deref().map(Path::new);
+    if recorded.is_some_and(|p| p == default || paths.root.join(p) == default) {
+        vault.vault.credstore_path = None;
+    }
+    metadata::ensure_vault_section(&mut vault, paths.credstore_override());
     metadata::save(&paths.vault_toml, &vault)?;
 
+    // Marks the root for auto-detection when run from a subdirectory.
+    let marker = paths.root.join(constants::ROOT_MARKER_FILE);
+    if !marker.exists() {
+        std::fs::write(&marker, "").with_context(|| format!("create {}", marker.display()))?;
+    }
+
     if args.setup {
         systemd::setup()?;
     }
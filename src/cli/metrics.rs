@@ -0,0 +1,212 @@
+//! Prometheus text-format metrics for a node_exporter textfile collector.
+//!
+//! Metadata only: reads vault.toml and counts the audit log. `--decrypt`
+//! additionally checks that every `.cred` still decrypts.
+
+use crate::cli::CliContext;
+use crate::core::{audit_log, credstore, metadata};
+use crate::models::vault_config::VaultFile;
+use crate::util::systemd;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+#[derive(Args, Debug)]
+pub struct MetricsArgs {
+    /// Count credentials expiring within this many days as expiring
+    #[arg(long, value_name = "DAYS", default_value_t = 30)]
+    pub expiring_days: u32,
+
+    /// Also report whether each .cred decrypts (slower)
+    #[arg(long)]
+    pub decrypt: bool,
+}
+
+pub fn run(ctx: &CliContext, args: MetricsArgs) -> Result<()> {
+    let paths = &ctx.paths;
+    let vault = if paths.vault_toml.exists() {
+        metadata::load(&paths.vault_toml)?
+    } else {
+        VaultFile::default()
+    };
+    let mut audit_entries = 0u64;
+    audit_log::for_each_entry(paths, |_| {
+        audit_entries += 1;
+        Ok(())
+    })?;
+    let decryptable = if args.decrypt && paths.credstore.is_dir() {
+        let creds = credstore::list_credentials(&paths.credstore)?;
+        Some(
+            creds
+                .into_iter()
+                .map(|c| {
+                    let ok = systemd::verify_decryptable(&c.path, None).is_ok();
+                    (c.name, ok)
+                })
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    print!(
+        "{}",
+        render(&vault, audit_entries, decryptable.as_ref(), Utc::now(), args.expiring_days)
+    );
+    Ok(())
+}
+
+fn render(
+    vault: &VaultFile,
+    audit_entries: u64,
+    decryptable: Option<&BTreeMap<String, bool>>,
+    now: DateTime<Utc>,
+    expiring_days: u32,
+) -> String {
+    let creds = &vault.credentials;
+    let horizon = now + Duration::days(i64::from(expiring_days));
+    let expired = creds
+        .iter()
+        .filter(|c| c.expires_at.is_some_and(|t| t <= now))
+        .count();
+    let expiring = creds
+        .iter()
+        .filter(|c| c.expires_at.is_some_and(|t| t > now && t <= horizon))
+        .count();
+    let mut by_key: BTreeMap<&str, usize> = BTreeMap::new();
+    for cred in creds {
+        *by_key.entry(cred.encryption_key.as_deref().unwrap_or("unknown")).or_default() += 1;
+    }
+
+    let mut out = String::new();
+    header(&mut out, "goamet_vault_credentials", "gauge", "Credentials recorded in vault.toml.");
+    let _ = writeln!(out, "goamet_vault_credentials {}", creds.len());
+
+    header(
+        &mut out,
+        "goamet_vault_credentials_by_key",
+        "gauge",
+        "Credentials by encryption key type.",
+    );
+    for (key, count) in &by_key {
+        let _ = writeln!(out, "goamet_vault_credentials_by_key{{key=\"{}\"}} {}", label(key), count);
+    }
+
+    header(
+        &mut out,
+        "goamet_vault_credentials_expired",
+        "gauge",
+        "Credentials whose expires_at has passed.",
+    );
+    let _ = writeln!(out, "goamet_vault_credentials_expired {}", expired);
+
+    header(
+        &mut out,
+        "goamet_vault_credentials_expiring",
+        "gauge",
+        "Credentials expiring within the given number of days.",
+    );
+    let _ = writeln!(
+        out,
+        "goamet_vault_credentials_expiring{{days=\"{}\"}} {}",
+        expiring_days, expiring
+    );
+
+    header(
+        &mut out,
+        "goamet_vault_audit_entries",
+        "gauge",
+        "Entries in the current audit.log (drops when the log is rotated).",
+    );
+    let _ = writeln!(out, "goamet_vault_audit_entries {}", audit_entries);
+
+    header(
+        &mut out,
+        "goamet_vault_credential_rotation_age_seconds",
+        "gauge",
+        "Seconds since the credential was last rotated (or created).",
+    );
+    for cred in creds {
+        if let Some(at) = cred.rotated_at.or(cred.created_at) {
+            let _ = writeln!(
+                out,
+                "goamet_vault_credential_rotation_age_seconds{{name=\"{}\"}} {}",
+                label(&cred.name),
+                (now - at).num_seconds().max(0)
+            );
+        }
+    }
+
+    if let Some(decryptable) = decryptable {
+        header(
+            &mut out,
+            "goamet_vault_credential_decryptable",
+            "gauge",
+            "1 if the .cred decrypts on this host, else 0.",
+        );
+        for (name, ok) in decryptable {
+            let _ = writeln!(
+                out,
+                "goamet_vault_credential_decryptable{{name=\"{}\"}} {}",
+                label(name),
+                u8::from(*ok)
+            );
+        }
+    }
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Escape a label value for the text exposition format.
+fn label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::credential::CredentialMeta;
+
+    #[test]
+    fn test_render_metrics() {
+        let now = Utc::now();
+        let cred = |name: &str, key: Option<&str>, expires: Option<i64>| CredentialMeta {
+            name: name.into(),
+            encryption_key: key.map(str::to_string),
+            rotated_at: Some(now - Duration::seconds(90)),
+            expires_at: expires.map(|d| now + Duration::days(d)),
+            ..Default::default()
+        };
+        let vault = VaultFile {
+            credentials: vec![
+                cred("db", Some("host+tpm2"), Some(-1)),
+                cred("api", Some("host+tpm2"), Some(10)),
+                cred("legacy", None, Some(90)),
+            ],
+            ..Default::default()
+        };
+        let decryptable = BTreeMap::from([("db".to_string(), false)]);
+
+        let out = render(&vault, 7, Some(&decryptable), now, 30);
+        assert!(out.contains("goamet_vault_credentials 3\n"));
+        assert!(out.contains("goamet_vault_credentials_by_key{key=\"host+tpm2\"} 2\n"));
+        assert!(out.contains("goamet_vault_credentials_by_key{key=\"unknown\"} 1\n"));
+        assert!(out.contains("goamet_vault_credentials_expired 1\n"));
+        assert!(out.contains("goamet_vault_credentials_expiring{days=\"30\"} 1\n"));
+        assert!(out.contains("# TYPE goamet_vault_audit_entries gauge\n"));
+        assert!(out.contains("goamet_vault_audit_entries 7\n"));
+        assert!(out.contains("goamet_vault_credential_rotation_age_seconds{name=\"api\"} 90\n"));
+        assert!(out.contains("goamet_vault_credential_decryptable{name=\"db\"} 0\n"));
+    }
+
+    #[test]
+    fn test_label_escaping() {
+        assert_eq!(label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
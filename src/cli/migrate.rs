@@ -1,15 +1,19 @@
-use crate::cli::CliContext;
-use crate::core::{metadata, file_lock::FileLock, service_map};
+use crate::cli::{credential, CliContext};
+use crate::core::{credstore, metadata, file_lock::FileLock, service_map, vault};
 use crate::core::paths::VaultPaths;
 use crate::models::credential::CredentialMeta;
-use crate::util::{fs as vault_fs, systemd};
+use crate::models::policy::PolicySection;
+use crate::models::vault_config::VaultFile;
+use crate::util::{fs as vault_fs, scratch, systemd};
 use anyhow::{bail, Context, Result};
 use chrono::Utc;
 use clap::{Args, Subcommand};
-use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Color, Table};
+use comfy_table::{Attribute, Cell, Color};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use zeroize::Zeroizing;
 
 #[derive(Subcommand, Debug)]
@@ -20,12 +24,48 @@ pub enum MigrateCommand {
     Import(MigrateImportArgs),
     /// Verify a service runs after migration
     Verify(MigrateVerifyArgs),
+    /// Re-encrypt host-only credentials with host+tpm2 (keeps secret values)
+    Rekey(MigrateRekeyArgs),
 }
 
 #[derive(Args, Debug)]
 pub struct MigrateScanArgs {
     /// Path to .env file
     pub path: PathBuf,
+
+    /// Output format (table|json); previews are masked in both
+    #[arg(long, default_value = "table")]
+    pub format: String,
+
+    #[command(flatten)]
+    pub filter: KeyFilterArgs,
+}
+
+/// Key filters shared by `scan` and `import` so previews match imports.
+#[derive(Args, Debug, Clone)]
+pub struct KeyFilterArgs {
+    /// Only keep keys matching this glob (repeatable; e.g. 'DB_*')
+    #[arg(long, value_name = "KEY", value_parser = parse_key_pattern)]
+    pub include: Vec<glob::Pattern>,
+
+    /// Drop keys matching this glob (repeatable; applied after --include)
+    #[arg(long, value_name = "KEY", value_parser = parse_key_pattern)]
+    pub exclude: Vec<glob::Pattern>,
+}
+
+impl KeyFilterArgs {
+    fn allows(&self, key: &str) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|p| p.matches(key));
+        included && !self.exclude.iter().any(|p| p.matches(key))
+    }
+
+    fn apply(&self, entries: &mut Vec<EnvEntry>) {
+        entries.retain(|e| self.allows(&e.key));
+    }
+}
+
+fn parse_key_pattern(s: &str) -> Result<glob::Pattern, String> {
+    glob::Pattern::new(s).map_err(|e| format!("invalid key pattern '{}': {}", s, e))
 }
 
 #[derive(Args, Debug)]
@@ -40,6 +80,28 @@ pub struct MigrateImportArgs {
     /// Key to use for encryption (host|tpm2|host+tpm2|auto; default: host+tpm2 if TPM2 available)
     #[arg(long)]
     pub with_key: Option<String>,
+
+    /// Replace each successfully imported line in the .env file with a `# MIGRATED:` comment
+    #[arg(long)]
+    pub redact_source: bool,
+
+    /// Credential name for each key; placeholders: {key}, {key_lower}, {service}
+    #[arg(long, value_name = "TEMPLATE", default_value = "{key_lower}")]
+    pub name_template: String,
+
+    /// Import the last value of a key that occurs more than once instead of failing
+    #[arg(long)]
+    pub last_wins: bool,
+
+    #[command(flatten)]
+    pub filter: KeyFilterArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct MigrateRekeyArgs {
+    /// Required confirmation because this rewrites every host-only credential
+    #[arg(long)]
+    pub confirm: bool,
 }
 
 #[derive(Args, Debug)]
@@ -52,6 +114,26 @@ struct EnvEntry {
     key: String,
     value: Zeroizing<String>,
     is_secret: bool,
+    /// 1-based line number in the .env file.
+    line: usize,
+}
+
+impl EnvEntry {
+    /// Masked for secrets, truncated for config values; never the full secret.
+    fn preview(&self) -> String {
+        if self.is_secret {
+            mask_value(&self.value)
+        } else {
+            truncate_value(&self.value, 40)
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ScanItem<'a> {
+    key: &'a str,
+    is_secret: bool,
+    value_preview: String,
 }
 
 const SECRET_PATTERNS: &[&str] = &[
@@ -66,7 +148,7 @@ const SECRET_PATTERNS: &[&str] = &[
 pub fn run(ctx: &CliContext, cmd: MigrateCommand) -> Result<()> {
     let paths = &ctx.paths;
     match cmd {
-        MigrateCommand::Scan(args) => run_scan(paths, args),
+        MigrateCommand::Scan(args) => run_scan(ctx, args),
         MigrateCommand::Import(args) => {
             if !ctx.policy.is_service_allowed(&args.service) {
                 bail!(
@@ -77,15 +159,116 @@ pub fn run(ctx: &CliContext, cmd: MigrateCommand) -> Result<()> {
             run_import(ctx, paths, args)
         }
         MigrateCommand::Verify(args) => run_verify(paths, args),
+        MigrateCommand::Rekey(args) => run_rekey(ctx, args),
+    }
+}
+
+fn run_rekey(ctx: &CliContext, args: MigrateRekeyArgs) -> Result<()> {
+    let paths = &ctx.paths;
+    if !args.confirm {
+        bail!("refusing to re-encrypt credentials without --confirm");
+    }
+    if !systemd::has_tpm2().unwrap_or(false) {
+        bail!("TPM2 not available; nothing to rekey to");
+    }
+    let new_key = crate::constants::DEFAULT_KEY_TYPE_WITH_TPM2;
+    let tpm2_pcrs = vault::resolve_tpm2_pcrs(&ctx.policy, new_key, None, None)?;
+
+    let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
+    let mut vault = metadata::load(&paths.vault_toml)?;
+    let targets: Vec<String> = vault
+        .credentials
+        .iter()
+        .filter(|c| c.encryption_key.as_deref() == Some("host"))
+        .map(|c| c.name.clone())
+        .collect();
+    if targets.is_empty() {
+        println!("No host-only credentials to rekey");
+        return Ok(());
+    }
+
+    let mut rekeyed = 0u32;
+    let mut failed = 0u32;
+    for name in &targets {
+        let mut audit = ctx.audit_ctx("rekey", name);
+        audit.with_key = Some(new_key.to_string());
+        audit.tpm2_pcrs = tpm2_pcrs.clone();
+        let result = ctx.audited(audit, |_| {
+            rekey_one(paths, &mut vault, name, new_key, tpm2_pcrs.as_deref())
+        });
+        match result {
+            Ok(()) => {
+                println!("  [PASS] {}: host -> {}", name, new_key);
+                rekeyed += 1;
+            }
+            Err(e) => {
+                println!("  [FAIL] {}: {:#}", name, e);
+                failed += 1;
+            }
+        }
+    }
+
+    println!("\nRekey: {} rekeyed, {} failed", rekeyed, failed);
+    if failed > 0 {
+        bail!("{} credential(s) failed to rekey", failed);
+    }
+    Ok(())
+}
+
+/// Decrypt and re-encrypt one credential with `new_key` and the policy PCR
+/// binding, keeping a `.prev` backup, then record both in metadata. Caller
+/// holds `vault_lock`.
+fn rekey_one(
+    paths: &VaultPaths,
+    vault: &mut VaultFile,
+    name: &str,
+    new_key: &str,
+    tpm2_pcrs: Option<&str>,
+) -> Result<()> {
+    let cred_path = paths.credstore.join(format!("{}.cred", name));
+    if !cred_path.is_file() {
+        bail!("credential not found: {}", cred_path.display());
+    }
+
+    let secret = systemd::decrypt_to_stdout(&cred_path, None, Some("no"))?;
+    let tmp_secret = vault::write_temp_secret(&secret, &paths.credstore)?;
+    let tmp_output = vault::temp_cred_output(&paths.credstore)?;
+    systemd::encrypt(new_key, name, tmp_secret.path(), tmp_output.path(), tpm2_pcrs)?;
+    let final_path = vault::install_with_backup(paths, vault, name, tmp_output)?;
+
+    if let Some(meta) = vault.credentials.iter_mut().find(|c| c.name == name) {
+        meta.encryption_key = Some(new_key.to_string());
+        meta.tpm2_pcrs = tpm2_pcrs.map(str::to_string);
+        meta.cred_sha256 = credstore::cred_sha256(&final_path).ok();
     }
+    metadata::save(&paths.vault_toml, vault)
 }
 
-fn run_scan(_paths: &VaultPaths, args: MigrateScanArgs) -> Result<()> {
+fn run_scan(ctx: &CliContext, args: MigrateScanArgs) -> Result<()> {
+    if args.format != "table" && args.format != "json" {
+        bail!("invalid format: {} (use table|json)", args.format);
+    }
     if !args.path.is_file() {
         bail!("file not found: {}", args.path.display());
     }
 
-    let entries = parse_env_file(&args.path)?;
+    let mut entries = parse_env_file(&args.path)?;
+    args.filter.apply(&mut entries);
+    for (key, lines) in duplicate_keys(&entries) {
+        eprintln!("warning: duplicate key {} on lines {}", key, join_lines(&lines));
+    }
+    if args.format == "json" {
+        let items: Vec<ScanItem> = entries
+            .iter()
+            .map(|e| ScanItem {
+                key: &e.key,
+                is_secret: e.is_secret,
+                value_preview: e.preview(),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&items).context("serialize scan")?);
+        return Ok(());
+    }
     if entries.is_empty() {
         println!("No entries found in {}", args.path.display());
         return Ok(());
@@ -94,8 +277,7 @@ fn run_scan(_paths: &VaultPaths, args: MigrateScanArgs) -> Result<()> {
     let secret_count = entries.iter().filter(|e| e.is_secret).count();
     let config_count = entries.len() - secret_count;
 
-    let mut table = Table::new();
-    table.load_preset(UTF8_FULL);
+    let mut table = ctx.table();
     table.set_header(vec![
         Cell::new("Key").add_attribute(Attribute::Bold),
         Cell::new("Type").add_attribute(Attribute::Bold),
@@ -108,15 +290,10 @@ fn run_scan(_paths: &VaultPaths, args: MigrateScanArgs) -> Result<()> {
         } else {
             Cell::new("config").fg(Color::Green)
         };
-        let preview = if entry.is_secret {
-            mask_value(&entry.value)
-        } else {
-            truncate_value(&entry.value, 40)
-        };
         table.add_row(vec![
             Cell::new(&entry.key),
             type_label,
-            Cell::new(preview),
+            Cell::new(entry.preview()),
         ]);
     }
 
@@ -151,8 +328,27 @@ fn run_import(ctx: &CliContext, paths: &VaultPaths, args: MigrateImportArgs) ->
             }
         }
     };
-
-    let entries = parse_env_file(&args.path)?;
+    vault::check_key_policy(&ctx.policy, &with_key, false)?;
+    let tpm2_pcrs = vault::resolve_tpm2_pcrs(&ctx.policy, &with_key, None, None)?;
+
+    let mut entries = parse_env_file(&args.path)?;
+    args.filter.apply(&mut entries);
+    let duplicates = duplicate_keys(&entries);
+    if !duplicates.is_empty() {
+        let report: Vec<String> = duplicates
+            .iter()
+            .map(|(key, lines)| format!("{} (lines {})", key, join_lines(lines)))
+            .collect();
+        if !args.last_wins {
+            bail!(
+                "duplicate key(s) in {}: {} (fix the file or pass --last-wins)",
+                args.path.display(),
+                report.join(", ")
+            );
+        }
+        eprintln!("warning: using the last value for duplicate key(s): {}", report.join(", "));
+        keep_last_occurrence(&mut entries);
+    }
     let secrets: Vec<&EnvEntry> = entries.iter().filter(|e| e.is_secret).collect();
 
     if secrets.is_empty() {
@@ -160,30 +356,41 @@ fn run_import(ctx: &CliContext, paths: &VaultPaths, args: MigrateImportArgs) ->
         return Ok(());
     }
 
+    // Resolve every name up front so a bad template or a collision aborts
+    // before anything is encrypted.
+    let names = render_names(&secrets, &args.name_template, &args.service)?;
+    check_secret_sizes(&ctx.policy, &secrets)?;
+    let mut vault = metadata::load(&paths.vault_toml)?;
+    check_foreign_names(&vault, &names, &args.service)?;
+
     vault_fs::ensure_dir(&paths.credstore, 0o700)?;
     vault_fs::ensure_dir(&paths.services, 0o755)?;
+    metadata::ensure_vault_section(&mut vault, paths.credstore_override());
 
-    let mut vault = metadata::load(&paths.vault_toml)?;
-    metadata::ensure_vault_section(&mut vault, Some(paths.credstore.display().to_string()));
-
+    let scratch = scratch::resolve(&paths.credstore);
+    let mut migrated: HashMap<String, String> = HashMap::new();
     let mut map_lines = Vec::new();
     let now = Utc::now();
     let mut imported = 0u32;
 
-    for entry in &secrets {
-        let cred_name = entry.key.to_lowercase();
+    for (entry, cred_name) in secrets.iter().zip(names) {
         let cred_path = paths.credstore.join(format!("{}.cred", cred_name));
 
-        // Write secret to temp file in credstore (0700), not /tmp
-        let mut tmp = tempfile::Builder::new()
-            .prefix(".secret-")
-            .tempfile_in(&paths.credstore)
-            .context("create temp file")?;
-        tmp.write_all(entry.value.as_bytes())
+        // Write secret to a private scratch dir (runtime tmpfs or credstore), not /tmp
+        let mut tmp = vault_fs::ScrubbedTempFile::new_in(&scratch.path, ".secret-")?;
+        let file = tmp.as_file_mut();
+        file.write_all(entry.value.as_bytes())
             .context("write temp secret")?;
-        tmp.flush().context("flush temp secret")?;
+        file.flush().context("flush temp secret")?;
+
+        let mut audit = ctx.audit_ctx("import", &cred_name);
+        audit.with_key = Some(with_key.clone());
+        audit.tpm2_pcrs = tpm2_pcrs.clone();
+        audit.service_context = Some(args.service.clone());
+        audit.target_path = Some(args.path.display().to_string());
 
-        match systemd::encrypt(&with_key, &cred_name, tmp.path(), &cred_path, None) {
+        let pcrs = tpm2_pcrs.as_deref();
+        match systemd::encrypt(&with_key, &cred_name, tmp.path(), &cred_path, pcrs) {
             Ok(()) => {
                 vault_fs::set_permissions(&cred_path, 0o600)?;
 
@@ -193,21 +400,27 @@ fn run_import(ctx: &CliContext, paths: &VaultPaths, args: MigrateImportArgs) ->
                     created_at: Some(now),
                     rotated_at: Some(now),
                     encryption_key: Some(with_key.clone()),
+                    tpm2_pcrs: tpm2_pcrs.clone(),
+                    cred_sha256: credstore::cred_sha256(&cred_path).ok(),
                     tags: vec!["migrated".to_string()],
                     services: vec![args.service.clone()],
+                    ..Default::default()
                 };
                 metadata::upsert_credential(&mut vault, meta);
 
                 // Map line: cred_name ENV_VAR_FILE
                 map_lines.push(format!("{} {}_FILE", cred_name, entry.key));
+                let shown = cred_path.strip_prefix(&paths.root).unwrap_or(&cred_path);
+                migrated.insert(entry.key.clone(), shown.display().to_string());
 
-                ctx.audit_simple("import", &cred_name);
+                ctx.audit_result(audit, true, None);
 
                 println!("  Imported: {} -> {}", entry.key, cred_path.display());
                 imported += 1;
             }
             Err(e) => {
                 eprintln!("  Failed to import {}: {}", entry.key, e);
+                ctx.audit_result(audit, false, Some(&e));
             }
         }
     }
@@ -218,9 +431,7 @@ fn run_import(ctx: &CliContext, paths: &VaultPaths, args: MigrateImportArgs) ->
         // Write service map file atomically
         let map_path = paths.services.join(format!("{}.conf", args.service));
         let map_content = map_lines.join("\n") + "\n";
-        let mut tmp = tempfile::Builder::new()
-            .prefix(".map-")
-            .tempfile_in(&paths.services)
+        let mut tmp = vault_fs::secure_tempfile(&paths.services, ".map-", "")
             .context("create temp map file")?;
         tmp.write_all(map_content.as_bytes())
             .context("write temp map")?;
@@ -234,11 +445,131 @@ fn run_import(ctx: &CliContext, paths: &VaultPaths, args: MigrateImportArgs) ->
         );
         println!("Service map: {}", map_path.display());
         println!("Run 'goamet-vault dropin generate {}' to create the systemd drop-in.", args.service);
+
+        if args.redact_source {
+            redact_source_file(&args.path, &migrated)?;
+            println!("Redacted {} imported key(s) in {}", migrated.len(), args.path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Render `template` for one env key and validate it as a credential name.
+fn render_name(template: &str, key: &str, service: &str) -> Result<String> {
+    let name = template
+        .replace("{key_lower}", &key.to_lowercase())
+        .replace("{key}", key)
+        .replace("{service}", service);
+    credential::parse_credential_name(&name)
+        .map_err(|e| anyhow::anyhow!("--name-template gives '{}' for {}: {}", name, key, e))
+}
+
+/// Credential names for `secrets`, in order. Fails if two different keys
+/// would share a name (e.g. `DB_URL` and `db_url` with `{key_lower}`).
+fn render_names(secrets: &[&EnvEntry], template: &str, service: &str) -> Result<Vec<String>> {
+    let mut names = Vec::with_capacity(secrets.len());
+    let mut owners: HashMap<String, &str> = HashMap::new();
+    let mut collisions = Vec::new();
+    for entry in secrets {
+        let name = render_name(template, &entry.key, service)?;
+        match owners.get(&name) {
+            Some(owner) if *owner != entry.key => {
+                collisions.push(format!("{} and {} -> {}", owner, entry.key, name));
+            }
+            Some(_) => {}
+            None => {
+                owners.insert(name.clone(), &entry.key);
+            }
+        }
+        names.push(name);
+    }
+    if !collisions.is_empty() {
+        bail!(
+            "credential name collision(s): {} (use --name-template, e.g. \"{{service}}-{{key}}\")",
+            collisions.join("; ")
+        );
     }
+    Ok(names)
+}
+
+/// Refuse to overwrite credentials that belong to other services only.
+fn check_foreign_names(vault: &VaultFile, names: &[String], service: &str) -> Result<()> {
+    let foreign: Vec<&str> = vault
+        .credentials
+        .iter()
+        .filter(|c| names.contains(&c.name))
+        .filter(|c| !c.services.is_empty() && !c.services.iter().any(|s| s == service))
+        .map(|c| c.name.as_str())
+        .collect();
+    if !foreign.is_empty() {
+        bail!(
+            "credential(s) already used by other services: {} (use --name-template to pick distinct names)",
+            foreign.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Fail if any value in `secrets` is outside the policy's secret size limits.
+fn check_secret_sizes(policy: &PolicySection, secrets: &[&EnvEntry]) -> Result<()> {
+    for entry in secrets {
+        policy
+            .check_secret_size(entry.value.len())
+            .with_context(|| format!("{} (line {})", entry.key, entry.line))?;
+    }
+    Ok(())
+}
 
+/// Rewrite the .env file atomically with imported keys commented out.
+fn redact_source_file(path: &Path, migrated: &HashMap<String, String>) -> Result<()> {
+    let content = Zeroizing::new(
+        fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?,
+    );
+    let redacted = Zeroizing::new(redact_env_content(&content, migrated));
+
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut tmp = vault_fs::secure_tempfile(dir, ".env-redact-", "")
+        .context("create temp env file")?;
+    tmp.write_all(redacted.as_bytes()).context("write temp env file")?;
+    tmp.flush().context("flush temp env file")?;
+    let perms = fs::metadata(path)
+        .with_context(|| format!("stat {}", path.display()))?
+        .permissions();
+    tmp.as_file()
+        .set_permissions(perms)
+        .context("preserve env file permissions")?;
+    tmp.persist(path)
+        .map_err(|e| anyhow::anyhow!("persist {}: {}", path.display(), e))?;
     Ok(())
 }
 
+/// Replace `KEY=value` lines for keys in `migrated` (key -> credential path)
+/// with a `# MIGRATED:` comment; all other lines are kept verbatim.
+fn redact_env_content(content: &str, migrated: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(content.len());
+    for line in content.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\r', '\n']);
+        let ending = &line[body.len()..];
+        let trimmed = body.trim();
+        let target = if trimmed.starts_with('#') {
+            None
+        } else {
+            trimmed
+                .split_once('=')
+                .and_then(|(key, _)| migrated.get(key.trim()).map(|t| (key.trim(), t)))
+        };
+        match target {
+            Some((key, cred)) => {
+                out.push_str(&format!("# MIGRATED: {} -> {}", key, cred));
+                out.push_str(ending);
+            }
+            None => out.push_str(line),
+        }
+    }
+    out
+}
+
 fn run_verify(paths: &VaultPaths, args: MigrateVerifyArgs) -> Result<()> {
     let map_path = paths.services.join(format!("{}.conf", args.service));
     if !map_path.is_file() {
@@ -264,8 +595,7 @@ fn run_verify(paths: &VaultPaths, args: MigrateVerifyArgs) -> Result<()> {
         }
 
         // Try to decrypt to verify
-        let tmp = tempfile::NamedTempFile::new()?;
-        match systemd::decrypt_to_file(&cred_path, tmp.path()) {
+        match systemd::verify_decryptable(&cred_path, None) {
             Ok(()) => {
                 println!("  [PASS] {}", entry.cred_name);
                 passed += 1;
@@ -288,11 +618,15 @@ fn run_verify(paths: &VaultPaths, args: MigrateVerifyArgs) -> Result<()> {
 }
 
 fn parse_env_file(path: &PathBuf) -> Result<Vec<EnvEntry>> {
-    let content = fs::read_to_string(path)
-        .with_context(|| format!("read {}", path.display()))?;
+    let content = Zeroizing::new(
+        fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?,
+    );
+    Ok(parse_env_content(&content))
+}
 
+fn parse_env_content(content: &str) -> Vec<EnvEntry> {
     let mut entries = Vec::new();
-    for line in content.lines() {
+    for (index, line) in content.lines().enumerate() {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') {
             continue;
@@ -305,10 +639,40 @@ fn parse_env_file(path: &PathBuf) -> Result<Vec<EnvEntry>> {
                 key,
                 value: Zeroizing::new(value),
                 is_secret,
+                line: index + 1,
             });
         }
     }
-    Ok(entries)
+    entries
+}
+
+/// Keys that occur more than once, in first-seen order, with their lines.
+fn duplicate_keys(entries: &[EnvEntry]) -> Vec<(String, Vec<usize>)> {
+    let mut seen: Vec<(String, Vec<usize>)> = Vec::new();
+    for entry in entries {
+        match seen.iter_mut().find(|(key, _)| *key == entry.key) {
+            Some((_, lines)) => lines.push(entry.line),
+            None => seen.push((entry.key.clone(), vec![entry.line])),
+        }
+    }
+    seen.retain(|(_, lines)| lines.len() > 1);
+    seen
+}
+
+/// Drop all but the last occurrence of each key (shell `source` semantics).
+fn keep_last_occurrence(entries: &mut Vec<EnvEntry>) {
+    let mut kept: Vec<EnvEntry> = Vec::with_capacity(entries.len());
+    for entry in entries.drain(..).rev() {
+        if !kept.iter().any(|e| e.key == entry.key) {
+            kept.push(entry);
+        }
+    }
+    kept.reverse();
+    *entries = kept;
+}
+
+fn join_lines(lines: &[usize]) -> String {
+    lines.iter().map(|l| l.to_string()).collect::<Vec<_>>().join(", ")
 }
 
 fn detect_secret(key: &str, value: &str) -> bool {
@@ -357,6 +721,134 @@ fn truncate_value(value: &str, max: usize) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_redact_env_content_only_migrated_keys() {
+        let content = "# app config\nDB_PASSWORD=\"hunter2\"\nAPI_TOKEN=abc\r\nPORT=8080\nLAST_SECRET=x";
+        let mut migrated = HashMap::new();
+        migrated.insert("DB_PASSWORD".to_string(), "credstore/db_password.cred".to_string());
+        migrated.insert("LAST_SECRET".to_string(), "credstore/last_secret.cred".to_string());
+
+        let redacted = redact_env_content(content, &migrated);
+        assert_eq!(
+            redacted,
+            "# app config\n# MIGRATED: DB_PASSWORD -> credstore/db_password.cred\nAPI_TOKEN=abc\r\nPORT=8080\n# MIGRATED: LAST_SECRET -> credstore/last_secret.cred"
+        );
+        assert!(!redacted.contains("hunter2"));
+    }
+
+    fn env_entry(key: &str) -> EnvEntry {
+        EnvEntry {
+            key: key.to_string(),
+            value: Zeroizing::new("x".to_string()),
+            is_secret: true,
+            line: 1,
+        }
+    }
+
+    #[test]
+    fn test_render_names_template_and_collisions() {
+        let entries = [env_entry("DB_PASSWORD"), env_entry("API_TOKEN")];
+        let refs: Vec<&EnvEntry> = entries.iter().collect();
+        assert_eq!(
+            render_names(&refs, "{service}-{key_lower}", "myservice").unwrap(),
+            vec!["myservice-db_password", "myservice-api_token"]
+        );
+        assert!(render_names(&refs, "{key}/x", "svc").is_err());
+
+        let entries = [env_entry("DB_URL"), env_entry("db_url")];
+        let refs: Vec<&EnvEntry> = entries.iter().collect();
+        let err = render_names(&refs, "{key_lower}", "svc").unwrap_err().to_string();
+        assert!(err.contains("DB_URL and db_url -> db_url"), "{}", err);
+        assert!(render_names(&refs, "{key}", "svc").is_ok());
+    }
+
+    #[test]
+    fn test_check_secret_sizes() {
+        let policy = PolicySection {
+            min_secret_size: Some(2),
+            ..Default::default()
+        };
+        let (a, b) = (env_entry("A"), env_entry("B"));
+        let err = check_secret_sizes(&policy, &[&a, &b]).unwrap_err();
+        assert!(format!("{:#}", err).starts_with("A (line 1): policy: secret is too short"));
+        assert!(check_secret_sizes(&PolicySection::default(), &[&a, &b]).is_ok());
+    }
+
+    #[test]
+    fn test_check_foreign_names() {
+        let mut vault = VaultFile::default();
+        for (name, svc) in [("shared", "other"), ("mine", "svc")] {
+            metadata::upsert_credential(
+                &mut vault,
+                CredentialMeta {
+                    name: name.to_string(),
+                    services: vec![svc.to_string()],
+                    ..Default::default()
+                },
+            );
+        }
+        assert!(check_foreign_names(&vault, &["mine".to_string()], "svc").is_ok());
+        assert!(check_foreign_names(&vault, &["shared".to_string()], "svc").is_err());
+    }
+
+    #[test]
+    fn test_scan_item_json_masks_secrets() {
+        let secret = EnvEntry {
+            key: "DB_PASSWORD".to_string(),
+            value: Zeroizing::new("hunter2hunter2".to_string()),
+            is_secret: true,
+            line: 1,
+        };
+        let item = ScanItem {
+            key: &secret.key,
+            is_secret: secret.is_secret,
+            value_preview: secret.preview(),
+        };
+        let json = serde_json::to_string(&item).unwrap();
+        assert_eq!(json, r#"{"key":"DB_PASSWORD","is_secret":true,"value_preview":"hu...r2"}"#);
+        assert!(!json.contains("hunter2hunter2"));
+    }
+
+    #[test]
+    fn test_duplicate_keys_and_last_wins() {
+        let content = "DB_PASSWORD=first\nPORT=80\n# note\nDB_PASSWORD=second\nPORT=81\nAPI_TOKEN=t\n";
+        let mut entries = parse_env_content(content);
+        assert_eq!(
+            duplicate_keys(&entries),
+            vec![
+                ("DB_PASSWORD".to_string(), vec![1, 4]),
+                ("PORT".to_string(), vec![2, 5]),
+            ]
+        );
+
+        keep_last_occurrence(&mut entries);
+        let kept: Vec<(&str, &str)> =
+            entries.iter().map(|e| (e.key.as_str(), e.value.as_str())).collect();
+        assert_eq!(kept, vec![("DB_PASSWORD", "second"), ("PORT", "81"), ("API_TOKEN", "t")]);
+        assert!(duplicate_keys(&entries).is_empty());
+    }
+
+    #[test]
+    fn test_key_filter() {
+        let pattern = |s: &str| parse_key_pattern(s).unwrap();
+        let mut entries = parse_env_content("DB_PASSWORD=a\nDB_HOST=b\nAPI_TOKEN=c\nDB_URL=d\n");
+        let filter = KeyFilterArgs {
+            include: vec![pattern("DB_*")],
+            exclude: vec![pattern("DB_HOST")],
+        };
+        filter.apply(&mut entries);
+        let keys: Vec<&str> = entries.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, vec!["DB_PASSWORD", "DB_URL"]);
+
+        let exclude_only = KeyFilterArgs {
+            include: vec![],
+            exclude: vec![pattern("*_URL")],
+        };
+        assert!(exclude_only.allows("API_TOKEN"));
+        assert!(!exclude_only.allows("DB_URL"));
+        assert!(parse_key_pattern("[").is_err());
+    }
+
     #[test]
     fn test_detect_secret_by_name() {
         assert!(detect_secret("DB_PASSWORD", "value"));
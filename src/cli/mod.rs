@@ -1,23 +1,42 @@
 //! CLI routing and command dispatch.
 
+use crate::core::audit_log::{self, AuditContext};
+use crate::core::error::VaultError;
+use crate::core::hooks::{self, HookEvent};
+use crate::core::metadata;
 use crate::core::paths::VaultPaths;
+use crate::core::vault::Vault;
 use crate::models::policy::PolicySection;
+use crate::models::vault_config::VaultFile;
 use crate::util::privilege;
+use crate::util::systemd;
 use crate::util::journald;
-use anyhow::Result;
+use crate::util::pager;
+use crate::util::syslog;
+use crate::util::table;
+use anyhow::{bail, Context, Result};
 use clap::{Parser, Subcommand};
+use ed25519_dalek::SigningKey;
+use std::io::IsTerminal;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 pub mod audit;
+pub mod completions;
 pub mod credential;
 pub mod dropin;
 pub mod health;
 pub mod init;
+pub mod metrics;
 pub mod migrate;
 pub mod plan;
+pub mod policy;
+pub mod reconcile;
 pub mod verify;
 pub mod doctor;
 pub mod test;
+pub mod trash;
+pub mod watch;
 
 /// Shared context passed to all command handlers.
 pub struct CliContext {
@@ -25,66 +44,488 @@ pub struct CliContext {
     pub non_interactive: bool,
     pub policy: PolicySection,
     pub policy_load_warning: Option<String>,
+    /// Actor recorded in audit entries instead of the detected user.
+    pub actor: Option<String>,
+    /// Styled (colored/bold) output allowed.
+    pub color: bool,
+    /// Program used to ask for secrets instead of the TTY prompt.
+    pub askpass: Option<PathBuf>,
+    /// `--journald` / `--no-journald`; overrides policy `journald_audit`.
+    pub journald_override: Option<bool>,
+    /// `-y`: answer yes to interactive confirmation prompts.
+    pub assume_yes: bool,
+    /// Long table output may go through `$PAGER` (off with `--no-pager`).
+    pub pager: bool,
+    /// `[audit] signing_key_path`, loaded on the first audited event.
+    pub audit_signing_key: OnceLock<Option<SigningKey>>,
+    /// `[audit] syslog_target`, resolved on the first audited event.
+    pub syslog: OnceLock<Option<syslog::SyslogSender>>,
 }
 
 impl CliContext {
-    /// Write an audit log line, and optionally forward it to journald.
+    /// Write an audit log line, and optionally forward it to journald and
+    /// remote syslog.
     pub fn audit_simple(&self, action: &str, credential: &str) {
         // core audit log errors should be visible to the operator
-        if let Err(e) = crate::core::audit_log::log(&self.paths, action, credential) {
+        let actor = audit_log::resolve_actor(self.actor.as_deref());
+        let key = self.audit_signing_key();
+        if let Err(e) = audit_log::log_action(&self.paths, action, credential, &actor, key) {
             eprintln!("warning: audit log failed: {}", e);
             return;
         }
 
-        if self.policy.journald_audit {
-            // Do not include secrets; audit.log already contains metadata only.
-            let line = format!(
-                "{{\"action\":\"{}\",\"credential\":\"{}\",\"vault\":\"{}\"}}",
-                action, credential, self.paths
-            );
-            journald::forward_line("goamet-vault", &line);
+        let audit = self.audit_ctx(action, credential);
+        let vault = self.paths.to_string();
+        self.forward_audit(journald_entry(&audit, &actor, &vault, None, None, false));
+    }
+
+    /// The audit signing key, read once per run. A key that cannot be
+    /// loaded leaves entries unsigned, which `audit verify --pubkey` reports.
+    fn audit_signing_key(&self) -> Option<&SigningKey> {
+        self.audit_signing_key
+            .get_or_init(|| match audit_log::signing_key_from_config(&self.paths) {
+                Ok(key) => key,
+                Err(e) => {
+                    eprintln!("warning: audit entries will not be signed: {:#}", e);
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    /// The `[audit] syslog_target` sender, set up once per run. A target
+    /// that cannot be read or resolved disables forwarding with one warning.
+    fn syslog_sender(&self) -> Option<&syslog::SyslogSender> {
+        self.syslog
+            .get_or_init(|| {
+                let section = match metadata::load_audit_section(&self.paths.vault_toml) {
+                    Ok(section) => section,
+                    Err(e) => {
+                        eprintln!("warning: syslog forwarding disabled: {:#}", e);
+                        return None;
+                    }
+                };
+                let target = section.syslog_target?;
+                match syslog::SyslogSender::new(&target) {
+                    Ok(sender) => Some(sender),
+                    Err(e) => {
+                        eprintln!("warning: syslog forwarding to {} disabled: {:#}", target, e);
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// Whether audit events go to journald: a `--journald`/`--no-journald`
+    /// flag wins over policy `journald_audit`.
+    pub fn journald_enabled(&self) -> bool {
+        self.journald_override.unwrap_or(self.policy.journald_audit)
+    }
+
+    /// Copy an audited event to journald and `[audit] syslog_target`, when
+    /// enabled. Both are best-effort.
+    fn forward_audit(&self, entry: JournaldEntry) {
+        if self.journald_enabled() {
+            let fields: Vec<(&str, &str)> =
+                entry.fields.iter().map(|(k, v)| (*k, v.as_str())).collect();
+            journald::forward("goamet-vault", &entry.message, entry.priority, &fields);
+        }
+        if let Some(sender) = self.syslog_sender() {
+            let sent = sender.send(syslog::FACILITY_AUTHPRIV, entry.priority, &entry.message);
+            if let Err(e) = sent {
+                eprintln!("warning: syslog forwarding to {} failed: {:#}", sender.target(), e);
+            }
+        }
+    }
+
+    /// Ask an interactive yes/no question (default no). `-y` and
+    /// `--non-interactive` answer yes; explicit `--confirm` gates are separate
+    /// and never satisfied here.
+    pub fn confirm(&self, prompt: &str) -> Result<bool> {
+        if self.assume_yes || self.non_interactive {
+            return Ok(true);
+        }
+        if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+            anyhow::bail!("no TTY available to confirm \"{}\"; re-run with -y", prompt);
+        }
+        dialoguer::Confirm::new()
+            .with_prompt(prompt)
+            .default(false)
+            .interact()
+            .context("read confirmation")
+    }
+
+    /// The library facade over this context's paths and policy.
+    pub fn vault(&self) -> Vault {
+        Vault::new(self.paths.clone(), self.policy.clone())
+    }
+
+    /// Print long output, through `$PAGER` when it does not fit the terminal.
+    pub fn page(&self, text: &str) {
+        pager::print(text, self.pager);
+    }
+
+    /// Create a table styled for the current stdout.
+    pub fn table(&self) -> comfy_table::Table {
+        table::new_table(self.color)
+    }
+
+    /// Start an audit context for `action` on `credential`; callers fill in
+    /// the optional forensic fields as they become known.
+    pub fn audit_ctx(&self, action: &str, credential: &str) -> AuditContext {
+        AuditContext {
+            action: action.to_string(),
+            credential: credential.to_string(),
+            actor: self.actor.clone(),
+            reason: None,
+            output_mode: None,
+            target_path: None,
+            with_key: None,
+            tpm2_pcrs: None,
+            service_context: None,
+        }
+    }
+
+    /// Run `op` and record its outcome in the audit log, including failures.
+    pub fn audited<T>(
+        &self,
+        mut audit: AuditContext,
+        op: impl FnOnce(&mut AuditContext) -> Result<T>,
+    ) -> Result<T> {
+        let result = op(&mut audit);
+        match &result {
+            Ok(_) => self.audit_result(audit, true, None),
+            Err(e) => self.audit_result(audit, false, Some(e)),
+        }
+        result
+    }
+
+    /// Write a forensics-grade audit entry with result, and optionally
+    /// forward it to journald and remote syslog.
+    pub fn audit_result(&self, audit: AuditContext, success: bool, error: Option<&anyhow::Error>) {
+        let actor = audit_log::resolve_actor(audit.actor.as_deref());
+        let vault = self.paths.to_string();
+        let policy_violation = error.is_some_and(is_policy_violation);
+        let error = error.map(|e| format!("{:#}", e));
+        let entry = journald_entry(
+            &audit,
+            &actor,
+            &vault,
+            Some(success),
+            error.as_deref(),
+            policy_violation,
+        );
+        let key = self.audit_signing_key();
+        if let Err(e) = audit_log::log_with_result(&self.paths, audit, success, error, key) {
+            eprintln!("warning: audit log failed: {}", e);
+            return;
+        }
+
+        self.forward_audit(entry);
+    }
+
+    /// Run the `[hooks]` command for `event` after a successful change,
+    /// auditing each run as `hook-result`. A failing hook only warns, unless
+    /// `fail_on_hook_error` is set: then `undo` reverts the change and the
+    /// command fails.
+    pub fn run_hooks(
+        &self,
+        event: HookEvent,
+        name: &str,
+        services: &[String],
+        undo: impl FnOnce() -> Result<()>,
+    ) -> Result<()> {
+        let section = match metadata::load_hooks_section(&self.paths.vault_toml) {
+            Ok(section) => section,
+            Err(e) => {
+                eprintln!("warning: {} hook not run: {:#}", event.as_str(), e);
+                return Ok(());
+            }
+        };
+        let Some(template) = event.template(&section) else {
+            return Ok(());
+        };
+        let mut failure = None;
+        for cmd in hooks::expand(template, name, services) {
+            let mut audit = self.audit_ctx("hook-result", name);
+            audit.output_mode = Some(event.as_str().to_string());
+            audit.target_path = Some(cmd.display());
+            audit.service_context = cmd.service.clone();
+            if let Err(e) = self.audited(audit, |_| hooks::run(event, name, &cmd)) {
+                eprintln!("warning: {:#}", e);
+                failure.get_or_insert(e);
+            }
+        }
+        match failure {
+            Some(e) if section.fail_on_hook_error => {
+                let mut audit = self.audit_ctx("hook-rollback", name);
+                audit.output_mode = Some(event.as_str().to_string());
+                self.audited(audit, |_| undo())
+                    .with_context(|| format!("{:#}; undoing the change failed", e))?;
+                bail!("{:#}; change undone (fail_on_hook_error)", e)
+            }
+            _ => Ok(()),
         }
     }
 }
 
+/// One audit event as journald sees it. Do not include secrets; audit.log
+/// already contains metadata only.
+#[derive(Debug)]
+struct JournaldEntry {
+    message: String,
+    priority: u8,
+    fields: Vec<(&'static str, String)>,
+}
+
+/// Whether `error` is a policy refusal ([`VaultError::PolicyViolation`])
+/// rather than a failure.
+fn is_policy_violation(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(cause.downcast_ref::<VaultError>(), Some(VaultError::PolicyViolation(_)))
+    })
+}
+
+/// Map an audit event to a journal priority and one `GOAMET_*` field per
+/// piece of forensic context: failures are `err`, policy refusals
+/// `warning`, everything else `info`.
+fn journald_entry(
+    audit: &AuditContext,
+    actor: &str,
+    vault: &str,
+    success: Option<bool>,
+    error: Option<&str>,
+    policy_violation: bool,
+) -> JournaldEntry {
+    let (priority, outcome) = match success {
+        Some(false) if policy_violation => (journald::PRIORITY_WARNING, "refused by policy"),
+        Some(false) => (journald::PRIORITY_ERR, "failed"),
+        Some(true) => (journald::PRIORITY_INFO, "ok"),
+        None => (journald::PRIORITY_INFO, "logged"),
+    };
+    let mut message = format!("{} '{}' by {}: {}", audit.action, audit.credential, actor, outcome);
+    if let Some(error) = error {
+        message.push_str(&format!(" ({})", error));
+    }
+    let message = message.replace('\n', " ");
+
+    let mut fields = vec![
+        ("GOAMET_ACTION", audit.action.clone()),
+        ("GOAMET_CREDENTIAL", audit.credential.clone()),
+        ("GOAMET_ACTOR", actor.to_string()),
+        ("GOAMET_VAULT", vault.to_string()),
+    ];
+    if let Some(success) = success {
+        fields.push(("GOAMET_SUCCESS", success.to_string()));
+    }
+    let optional = [
+        ("GOAMET_ERROR", error),
+        ("GOAMET_REASON", audit.reason.as_deref()),
+        ("GOAMET_OUTPUT_MODE", audit.output_mode.as_deref()),
+        ("GOAMET_TARGET_PATH", audit.target_path.as_deref()),
+        ("GOAMET_WITH_KEY", audit.with_key.as_deref()),
+        ("GOAMET_TPM2_PCRS", audit.tpm2_pcrs.as_deref()),
+        ("GOAMET_SERVICE_CONTEXT", audit.service_context.as_deref()),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            fields.push((key, value.to_string()));
+        }
+    }
+    JournaldEntry {
+        message,
+        priority,
+        fields,
+    }
+}
+
+/// Load vault.toml if it exists. Duplicate credential names do not stop the
+/// [vault] and [policy] sections from being honored.
+fn load_vault_config(paths: &VaultPaths) -> (Option<VaultFile>, Option<anyhow::Error>) {
+    if !paths.vault_toml.exists() {
+        return (None, None);
+    }
+    match crate::core::metadata::load_repair(&paths.vault_toml) {
+        Ok((vault, _)) => (Some(vault), None),
+        Err(e) => (None, Some(e)),
+    }
+}
+
+/// The `GOAMET_VAULT_POLICY_*` environment variables, sorted by name.
+fn policy_env_vars() -> Result<Vec<(String, String)>> {
+    let mut vars = Vec::new();
+    for (key, value) in std::env::vars_os() {
+        let Some(key) = key.to_str().filter(|k| k.starts_with(metadata::POLICY_ENV_PREFIX)) else {
+            continue;
+        };
+        let Some(value) = value.to_str() else {
+            bail!("{} is not valid UTF-8", key);
+        };
+        vars.push((key.to_string(), value.to_string()));
+    }
+    vars.sort();
+    Ok(vars)
+}
+
+/// An explicit `--askpass` always wins; `$SSH_ASKPASS` only stands in for
+/// the TTY prompt when there is no terminal on stdin (as in ssh itself).
+fn askpass_program(explicit: Option<PathBuf>, stdin_tty: bool) -> Option<PathBuf> {
+    let from_env = || {
+        (!stdin_tty)
+            .then(|| std::env::var_os("SSH_ASKPASS"))
+            .flatten()
+            .map(PathBuf::from)
+    };
+    explicit
+        .or_else(from_env)
+        .filter(|p| !p.as_os_str().is_empty())
+}
+
+fn parse_actor(s: &str) -> Result<String, String> {
+    if s.trim().is_empty() {
+        return Err("actor cannot be empty".into());
+    }
+    if s.chars().any(|c| c.is_control()) {
+        return Err("actor cannot contain newlines or control characters".into());
+    }
+    Ok(s.to_string())
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "goamet-vault", version, about = "Systemd credential wrapper for GoAmet services")]
 pub struct Cli {
     #[arg(long, global = true, value_name = "PATH")]
     pub root: Option<PathBuf>,
 
+    /// Encrypted credstore location (default: <root>/credstore; also
+    /// GOAMET_VAULT_CREDSTORE or [vault] credstore_path)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub credstore: Option<PathBuf>,
+
+    /// Load [policy] from this TOML file instead of <root>/vault.toml
+    #[arg(long, global = true, value_name = "PATH", env = "GOAMET_VAULT_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Apply the [profiles.<NAME>] overrides on top of [policy]
+    #[arg(long, global = true, value_name = "NAME", env = "GOAMET_VAULT_PROFILE")]
+    pub profile: Option<String>,
+
     /// Run in non-interactive mode (no prompts, suitable for automation)
     #[arg(long, global = true, env = "GOAMET_VAULT_NON_INTERACTIVE")]
     pub non_interactive: bool,
 
+    /// Actor recorded in the audit log (default: detected from SUDO_USER/USER)
+    #[arg(long, global = true, env = "GOAMET_VAULT_ACTOR", value_parser = parse_actor)]
+    pub actor: Option<String>,
+
+    /// Disable colored output (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Answer yes to interactive confirmation prompts (e.g. `delete`); does not
+    /// replace explicit --confirm flags such as `get --confirm` or `dropin apply --confirm`
+    #[arg(short = 'y', long, global = true)]
+    pub assume_yes: bool,
+
+    /// Forward audit events to journald even if policy journald_audit is off
+    #[arg(long, global = true, conflicts_with = "no_journald")]
+    pub journald: bool,
+
+    /// Do not forward audit events to journald, whatever policy journald_audit says
+    #[arg(long, global = true)]
+    pub no_journald: bool,
+
+    /// Print long tables directly instead of through $PAGER (never used as root or under sudo)
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// Print each systemd-creds command line (never secret contents) to stderr
+    #[arg(short = 'v', long, global = true, env = "GOAMET_VAULT_VERBOSE")]
+    pub verbose: bool,
+
+    /// Kill a systemd-creds call that has not finished after this many seconds
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        env = "GOAMET_VAULT_COMMAND_TIMEOUT",
+        default_value_t = crate::constants::DEFAULT_COMMAND_TIMEOUT_SECS,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub command_timeout: u64,
+
+    /// Askpass program used to read secrets instead of the TTY prompt
+    /// ($SSH_ASKPASS is used only when stdin is not a terminal)
+    #[arg(long, global = true, value_name = "PROG")]
+    pub askpass: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
 impl Cli {
     pub fn run(self) -> Result<()> {
+        // Completions need neither a vault nor a policy.
+        if let Commands::Completions(args) = &self.command {
+            return completions::run(args);
+        }
+
+        systemd::set_command_timeout(std::time::Duration::from_secs(self.command_timeout));
+        systemd::set_verbose(self.verbose);
         let paths = VaultPaths::resolve(self.root)?;
 
-        // Load policy from vault.toml if it exists (best-effort).
+        // vault.toml is read once, best-effort, for both [vault] credstore_path and [policy].
         // Non-root users may not be able to read it; that's ok for read-only commands like `doctor`.
+        let (vault, vault_load_error) = load_vault_config(&paths);
+        let paths = paths.with_credstore(
+            self.credstore,
+            vault.as_ref().and_then(|v| v.vault.credstore_path.clone()),
+        );
+
+        // Policy from --config (strict), else from vault.toml.
         let mut policy_load_warning: Option<String> = None;
-        let policy = if paths.vault_toml.exists() {
-            match crate::core::metadata::load(&paths.vault_toml) {
-                Ok(vault) => vault.policy,
-                Err(e) => {
-                    policy_load_warning = Some(format!("cannot read policy from vault.toml: {}", e));
-                    PolicySection::default()
+        let (policy, profiles) = if let Some(config) = &self.config {
+            crate::core::metadata::load_policy_file(config)?
+        } else if let Some(vault) = vault {
+            (vault.policy, vault.profiles)
+        } else {
+            if let Some(e) = vault_load_error {
+                // A requested profile cannot be checked without its file.
+                if let Some(profile) = &self.profile {
+                    return Err(e.context(format!("cannot load profile '{}'", profile)));
                 }
+                policy_load_warning = Some(format!("cannot read policy from vault.toml: {}", e));
             }
-        } else {
-            PolicySection::default()
+            (PolicySection::default(), Default::default())
+        };
+        let policy = match self.profile.as_deref().filter(|p| !p.is_empty()) {
+            Some(name) => crate::core::metadata::apply_profile(&policy, &profiles, name)?,
+            None => policy,
         };
+        // GOAMET_VAULT_POLICY_* variables win over the file and the profile.
+        let policy = metadata::apply_env_overrides(&policy, &policy_env_vars()?)?;
+        policy
+            .validate_secret_size_limits()
+            .map_err(|e| anyhow::anyhow!("invalid policy: {}", e))?;
 
         let ctx = CliContext {
             paths,
             non_interactive: self.non_interactive,
             policy,
             policy_load_warning,
+            actor: self.actor,
+            color: table::color_enabled(self.no_color),
+            askpass: askpass_program(self.askpass, std::io::stdin().is_terminal()),
+            assume_yes: self.assume_yes,
+            pager: !self.no_pager,
+            audit_signing_key: OnceLock::new(),
+            syslog: OnceLock::new(),
+            journald_override: match (self.journald, self.no_journald) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            },
         };
 
         // Enforce root for mutating commands
@@ -95,21 +536,31 @@ impl Cli {
         match self.command {
             Commands::Init(args) => init::run(&ctx, args),
             Commands::Create(args) => credential::run_create(&ctx, args),
+            Commands::CreateBatch(args) => credential::run_create_batch(&ctx, args),
             Commands::Get(args) => credential::run_get(&ctx, args),
+            Commands::GetMany(args) => credential::run_get_many(&ctx, args),
+            Commands::ExportAll(args) => credential::run_export_all(&ctx, args),
             Commands::List(args) => credential::run_list(&ctx, args),
             Commands::Delete(args) => credential::run_delete(&ctx, args),
+            Commands::Copy(args) => credential::run_copy(&ctx, args),
             Commands::Describe(args) => credential::run_describe(&ctx, args),
             Commands::Search(args) => credential::run_search(&ctx, args),
             Commands::Rotate(args) => credential::run_rotate(&ctx, args),
             Commands::Dropin { command } => dropin::run(&ctx, command),
             Commands::Migrate { command } => migrate::run(&ctx, command),
             Commands::Health(args) => health::run(&ctx, args),
+            Commands::Metrics(args) => metrics::run(&ctx, args),
             Commands::Audit { command } => audit::run(&ctx, command),
             Commands::Plan { command } => plan::run(&ctx, command),
+            Commands::Policy { command } => policy::run(&ctx, command),
+            Commands::Trash { command } => trash::run(&ctx, command),
             Commands::Verify { command } => verify::run(&ctx, command),
             Commands::Rollback { command } => credential::run_rollback(&ctx, command),
             Commands::Doctor(args) => doctor::run(&ctx, args),
+            Commands::Reconcile(args) => reconcile::run(&ctx, args),
             Commands::Test { command } => test::run(&ctx, command),
+            Commands::Watch(args) => watch::run(&ctx, args),
+            Commands::Completions(args) => completions::run(&args),
         }
     }
 }
@@ -120,12 +571,20 @@ pub enum Commands {
     Init(init::InitArgs),
     /// Create an encrypted credential
     Create(credential::CreateArgs),
+    /// Create many credentials from a `name=secret` stream on stdin
+    CreateBatch(credential::CreateBatchArgs),
     /// Decrypt and output a credential
     Get(credential::GetArgs),
+    /// Decrypt several credentials into `<DIR>/<name>` files in one call
+    GetMany(credential::GetManyArgs),
+    /// Decrypt every credential into a fresh directory (disaster recovery drills)
+    ExportAll(credential::ExportAllArgs),
     /// List credentials
     List(credential::ListArgs),
     /// Delete a credential
     Delete(credential::DeleteArgs),
+    /// Copy a credential's secret and metadata under a new name
+    Copy(credential::CopyArgs),
     /// Describe a credential (metadata)
     Describe(credential::DescribeArgs),
     /// Search credentials by name/description/tags
@@ -144,6 +603,8 @@ pub enum Commands {
     },
     /// Run health checks on the vault
     Health(health::HealthArgs),
+    /// Print Prometheus text-format metrics (read-only; for a textfile collector)
+    Metrics(metrics::MetricsArgs),
     /// View the audit trail
     Audit {
         #[command(subcommand)]
@@ -154,6 +615,16 @@ pub enum Commands {
         #[command(subcommand)]
         command: plan::PlanCommand,
     },
+    /// List or restore deleted credentials
+    Trash {
+        #[command(subcommand)]
+        command: trash::TrashCommand,
+    },
+    /// Show the effective policy or check an operation against it (read-only)
+    Policy {
+        #[command(subcommand)]
+        command: policy::PolicyCommand,
+    },
     /// Post-operation verification
     Verify {
         #[command(subcommand)]
@@ -166,53 +637,341 @@ pub enum Commands {
     },
     /// Diagnose installation and configuration (safe, read-only)
     Doctor(doctor::DoctorArgs),
+    /// Report (and with --fix repair) drift between vault.toml, credstore and service maps
+    Reconcile(reconcile::ReconcileArgs),
     /// Test transient-unit secret leakage protections (safe: no /etc writes)
     Test {
         #[command(subcommand)]
         command: test::TestCommand,
     },
+    /// Reload services when a credential they use changes (long-running)
+    Watch(watch::WatchArgs),
+    /// Print shell completion script to stdout
+    #[command(hide = true)]
+    Completions(completions::CompletionsArgs),
 }
 
 impl Commands {
-    /// Whether this command requires root privileges.
+    /// Whether this command requires root privileges. Pure dry runs do not.
     pub fn requires_root(&self) -> bool {
+        if self.is_dry_run() {
+            return false;
+        }
         matches!(
             self,
             Commands::Init(_)
                 | Commands::Create(_)
+                | Commands::CreateBatch(_)
+                | Commands::ExportAll(_)
                 | Commands::Delete(_)
-                | Commands::Rotate(_)
+                | Commands::Copy(_)
+                | Commands::Rotate(credential::RotateArgs { schedule: None, .. })
+                | Commands::Rotate(credential::RotateArgs { apply: true, .. })
                 | Commands::Dropin {
                     command: dropin::DropinCommand::Apply(_)
+                        | dropin::DropinCommand::Uninstall(_)
                 }
                 | Commands::Migrate {
                     command: migrate::MigrateCommand::Import(_)
+                        | migrate::MigrateCommand::Rekey(_)
                 }
                 | Commands::Rollback { .. }
+                | Commands::Trash {
+                    command: trash::TrashCommand::Restore(_) | trash::TrashCommand::Purge(_)
+                }
+                | Commands::Reconcile(reconcile::ReconcileArgs { fix: true, .. })
+                | Commands::Audit {
+                    command: audit::AuditCommand::Rotate(_)
+                }
                 | Commands::Test { .. }
+                | Commands::Watch(_)
         )
     }
 
+    /// Whether this is a `--dry-run` that reads but never writes.
+    pub fn is_dry_run(&self) -> bool {
+        match self {
+            Commands::Create(args) => args.dry_run,
+            Commands::Rotate(args) => args.dry_run,
+            _ => false,
+        }
+    }
+
     /// Command name for error messages.
     pub fn name(&self) -> &str {
         match self {
             Commands::Init(_) => "init",
             Commands::Create(_) => "create",
+            Commands::CreateBatch(_) => "create-batch",
             Commands::Get(_) => "get",
+            Commands::GetMany(_) => "get-many",
+            Commands::ExportAll(_) => "export-all",
             Commands::List(_) => "list",
             Commands::Delete(_) => "delete",
+            Commands::Copy(_) => "copy",
             Commands::Describe(_) => "describe",
             Commands::Search(_) => "search",
             Commands::Rotate(_) => "rotate",
             Commands::Dropin { .. } => "dropin",
             Commands::Migrate { .. } => "migrate",
             Commands::Health(_) => "health",
+            Commands::Metrics(_) => "metrics",
             Commands::Audit { .. } => "audit",
             Commands::Plan { .. } => "plan",
+            Commands::Policy { .. } => "policy",
+            Commands::Trash { .. } => "trash",
             Commands::Verify { .. } => "verify",
             Commands::Rollback { .. } => "rollback",
             Commands::Doctor(_) => "doctor",
+            Commands::Reconcile(_) => "reconcile",
             Commands::Test { .. } => "test",
+            Commands::Watch(_) => "watch",
+            Commands::Completions(_) => "completions",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_journald_entry_for_failed_rotate() {
+        let mut audit = AuditContext {
+            action: "rotate".into(),
+            credential: "db".into(),
+            actor: None,
+            reason: Some("quarterly".into()),
+            output_mode: None,
+            target_path: None,
+            with_key: Some("host+tpm2".into()),
+            tpm2_pcrs: Some("7".into()),
+            service_context: None,
+        };
+        let entry = journald_entry(
+            &audit,
+            "alice",
+            "/srv/vault",
+            Some(false),
+            Some("decrypt failed"),
+            false,
+        );
+        assert_eq!(entry.priority, journald::PRIORITY_ERR);
+        assert_eq!(entry.message, "rotate 'db' by alice: failed (decrypt failed)");
+        let field = |key: &str| {
+            entry.fields.iter().find(|(k, _)| *k == key).map(|(_, v)| v.as_str())
+        };
+        assert_eq!(field("GOAMET_ACTION"), Some("rotate"));
+        assert_eq!(field("GOAMET_CREDENTIAL"), Some("db"));
+        assert_eq!(field("GOAMET_ACTOR"), Some("alice"));
+        assert_eq!(field("GOAMET_SUCCESS"), Some("false"));
+        assert_eq!(field("GOAMET_ERROR"), Some("decrypt failed"));
+        assert_eq!(field("GOAMET_REASON"), Some("quarterly"));
+        assert_eq!(field("GOAMET_WITH_KEY"), Some("host+tpm2"));
+        assert_eq!(field("GOAMET_TPM2_PCRS"), Some("7"));
+        assert_eq!(field("GOAMET_TARGET_PATH"), None);
+
+        audit.reason = None;
+        let entry =
+            journald_entry(&audit, "alice", "/srv/vault", Some(false), Some("policy: nope"), true);
+        assert_eq!(entry.priority, journald::PRIORITY_WARNING);
+        let entry = journald_entry(&audit, "alice", "/srv/vault", Some(true), None, false);
+        assert_eq!(entry.priority, journald::PRIORITY_INFO);
+
+        let refused = anyhow::Error::new(VaultError::PolicyViolation("nope".into()));
+        assert!(is_policy_violation(&refused.context("create db")));
+        assert!(!is_policy_violation(&anyhow::anyhow!("policy file missing")));
+    }
+
+    fn test_ctx() -> (TempDir, CliContext) {
+        let dir = TempDir::new().unwrap();
+        let ctx = CliContext {
+            paths: VaultPaths::from_root(dir.path().to_path_buf()),
+            non_interactive: true,
+            policy: PolicySection::default(),
+            policy_load_warning: None,
+            actor: None,
+            color: false,
+            askpass: None,
+            journald_override: None,
+            assume_yes: false,
+            pager: false,
+            audit_signing_key: OnceLock::new(),
+            syslog: OnceLock::new(),
+        };
+        (dir, ctx)
+    }
+
+    #[test]
+    fn test_confirm_skipped_by_assume_yes_or_non_interactive() {
+        let (_dir, mut ctx) = test_ctx();
+        assert!(ctx.confirm("Delete?").unwrap());
+        ctx.non_interactive = false;
+        ctx.assume_yes = true;
+        assert!(ctx.confirm("Delete?").unwrap());
+
+        let cli = Cli::try_parse_from(["goamet-vault", "delete", "db", "-y", "--reason", "leaked"])
+            .unwrap();
+        assert!(cli.assume_yes);
+        match cli.command {
+            Commands::Delete(args) => assert_eq!(args.reason.as_deref(), Some("leaked")),
+            other => panic!("unexpected command {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_get_file_is_sandboxed_to_vault_root() {
+        let (dir, ctx) = test_ctx();
+        let inside = dir.path().join("import/db.cred");
+        std::fs::create_dir_all(inside.parent().unwrap()).unwrap();
+        std::fs::write(&inside, "x").unwrap();
+        assert!(credential::resolve_cred_file(&ctx, &inside, false).is_ok());
+
+        let other = TempDir::new().unwrap();
+        let outside = other.path().join("db.cred");
+        std::fs::write(&outside, "x").unwrap();
+        assert!(credential::resolve_cred_file(&ctx, &outside, false).is_err());
+        assert!(credential::resolve_cred_file(&ctx, &outside, true).is_ok());
+        let escape = dir.path().join("..").join(other.path().file_name().unwrap()).join("db.cred");
+        assert!(credential::resolve_cred_file(&ctx, &escape, false).is_err());
+
+        assert!(Cli::try_parse_from(["goamet-vault", "get", "--file", "x.cred"]).is_ok());
+        assert!(Cli::try_parse_from(["goamet-vault", "get", "db", "--file", "x.cred"]).is_err());
+        assert!(Cli::try_parse_from(["goamet-vault", "get"]).is_err());
+    }
+
+    #[test]
+    fn test_plan_rollback_validates_name() {
+        assert!(Cli::try_parse_from(["goamet-vault", "plan", "rollback", "db"]).is_ok());
+        assert!(Cli::try_parse_from(["goamet-vault", "plan", "rollback", "../x"]).is_err());
+    }
+
+    #[test]
+    fn test_rotate_schedule_rejects_write_options() {
+        let schedule = ["goamet-vault", "rotate", "db", "--schedule", "daily"];
+        assert!(Cli::try_parse_from(schedule).is_ok());
+        for extra in [&["--auto"][..], &["--with-key", "host"], &["--tpm2-pcrs", "7"]] {
+            let args = schedule.iter().chain(extra.iter());
+            assert!(Cli::try_parse_from(args).is_err(), "{:?}", extra);
+        }
+    }
+
+    #[test]
+    fn test_journald_flags_override_policy() {
+        let (_dir, mut ctx) = test_ctx();
+        assert!(!ctx.journald_enabled());
+        ctx.policy.journald_audit = true;
+        assert!(ctx.journald_enabled());
+        ctx.journald_override = Some(false);
+        assert!(!ctx.journald_enabled());
+        ctx.policy.journald_audit = false;
+        ctx.journald_override = Some(true);
+        assert!(ctx.journald_enabled());
+
+        assert!(Cli::try_parse_from(["goamet-vault", "--journald", "--no-journald", "list"]).is_err());
+    }
+
+    #[test]
+    fn test_audited_records_failure() {
+        let (_dir, ctx) = test_ctx();
+        let audit = ctx.audit_ctx("rotate", "db_pass");
+        let result: Result<()> = ctx.audited(audit, |audit| {
+            audit.with_key = Some("host".to_string());
+            anyhow::bail!("encrypt failed")
+        });
+        assert!(result.is_err());
+
+        let entries = audit_log::read_log(&ctx.paths, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        let outcome = entries[0].result.as_ref().unwrap();
+        assert!(!outcome.success);
+        assert_eq!(outcome.error.as_deref(), Some("encrypt failed"));
+        assert_eq!(entries[0].with_key.as_deref(), Some("host"));
+    }
+
+    #[test]
+    fn test_askpass_env_does_not_override_tty_prompt() {
+        let prog = PathBuf::from("/usr/bin/ssh-askpass");
+        assert_eq!(askpass_program(Some(prog.clone()), true), Some(prog.clone()));
+        assert_eq!(askpass_program(Some(prog.clone()), false), Some(prog));
+        assert_eq!(askpass_program(None, true), None);
+        assert_eq!(askpass_program(Some(PathBuf::new()), true), None);
+    }
+
+    #[test]
+    fn test_actor_override() {
+        assert!(parse_actor("ci-deploy").is_ok());
+        assert!(parse_actor("  ").is_err());
+        assert!(parse_actor("ci\n{\"action\":\"x\"}").is_err());
+
+        let (_dir, mut ctx) = test_ctx();
+        ctx.actor = Some("ci-deploy".to_string());
+        ctx.audit_simple("create", "db_pass");
+        let audit = ctx.audit_ctx("rotate", "db_pass");
+        ctx.audited(audit, |_| Ok(())).unwrap();
+
+        let entries = audit_log::read_log(&ctx.paths, None).unwrap();
+        assert!(entries.iter().all(|e| e.actor == "ci-deploy"));
+    }
+
+    #[test]
+    fn test_dry_run_skips_root_check() {
+        let root = |args: &[&str]| {
+            let argv = std::iter::once("goamet-vault").chain(args.iter().copied());
+            let cli = Cli::try_parse_from(argv).unwrap();
+            cli.command.requires_root()
+        };
+        assert!(root(&["create", "db_pass"]));
+        assert!(!root(&["create", "db_pass", "--dry-run"]));
+        assert!(root(&["rotate", "db_pass", "--auto"]));
+        assert!(!root(&["rotate", "db_pass", "--auto", "--dry-run"]));
+        assert!(root(&["delete", "db_pass"]));
+    }
+
+    #[test]
+    fn test_vault_config_sets_credstore_and_policy() {
+        let (dir, ctx) = test_ctx();
+        std::fs::write(
+            &ctx.paths.vault_toml,
+            "[vault]\nversion = 2\ncredstore_path = \"/mnt/store\"\n\n[policy]\nrequire_reason_for_rotate = true\n",
+        )
+        .unwrap();
+        let (vault, err) = load_vault_config(&ctx.paths);
+        assert!(err.is_none());
+        let vault = vault.unwrap();
+        assert!(vault.policy.require_reason_for_rotate);
+
+        let paths = VaultPaths::from_root(dir.path().to_path_buf())
+            .with_credstore(None, vault.vault.credstore_path.clone());
+        if std::env::var_os("GOAMET_VAULT_CREDSTORE").is_none() {
+            assert_eq!(paths.credstore, PathBuf::from("/mnt/store"));
         }
+        assert_eq!(paths.vault_toml, ctx.paths.vault_toml);
+    }
+
+    #[test]
+    fn test_journald_entry_keeps_fields_separate() {
+        let (_dir, ctx) = test_ctx();
+        let audit = ctx.audit_ctx("create", "db\nGOAMET_SUCCESS=true");
+        let entry =
+            journald_entry(&audit, "bob", "/v", Some(false), Some("line1\nline2"), false);
+        // Values go verbatim into their own field; the protocol length-prefixes newlines.
+        assert!(entry
+            .fields
+            .iter()
+            .any(|(k, v)| *k == "GOAMET_CREDENTIAL" && v == "db\nGOAMET_SUCCESS=true"));
+        // The message also feeds the systemd-cat fallback, one line per entry.
+        assert!(!entry.message.contains('\n'));
+    }
+
+    #[test]
+    fn test_audited_records_success() {
+        let (_dir, ctx) = test_ctx();
+        let audit = ctx.audit_ctx("delete", "db_pass");
+        ctx.audited(audit, |_| Ok(())).unwrap();
+
+        let entries = audit_log::read_log(&ctx.paths, None).unwrap();
+        assert!(entries[0].result.as_ref().unwrap().success);
     }
 }
@@ -1,12 +1,13 @@
 //! Dry-run preview of mutating operations.
 
-use crate::cli::CliContext;
+use crate::cli::{credential, CliContext};
 use crate::constants;
-use crate::core::service_map;
+use crate::core::{credstore, metadata, service_map};
 use crate::util::systemd;
 use anyhow::{bail, Result};
+use chrono::{DateTime, Local, Utc};
 use clap::{Args, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Subcommand, Debug)]
 pub enum PlanCommand {
@@ -16,6 +17,8 @@ pub enum PlanCommand {
     Dropin(PlanDropinArgs),
     /// Preview a migration import
     Migrate(PlanMigrateArgs),
+    /// Preview a rollback of a rotated credential
+    Rollback(PlanRollbackArgs),
 }
 
 #[derive(Args, Debug)]
@@ -57,11 +60,22 @@ pub struct PlanMigrateArgs {
     pub format: String,
 }
 
+#[derive(Args, Debug)]
+pub struct PlanRollbackArgs {
+    /// Credential name
+    #[arg(value_parser = credential::parse_credential_name)]
+    pub name: String,
+    /// Output format (text|json)
+    #[arg(long, default_value = "text")]
+    pub format: String,
+}
+
 pub fn run(ctx: &CliContext, cmd: PlanCommand) -> Result<()> {
     match cmd {
         PlanCommand::Rotate(args) => plan_rotate(ctx, args),
         PlanCommand::Dropin(args) => plan_dropin(ctx, args),
         PlanCommand::Migrate(args) => plan_migrate(ctx, args),
+        PlanCommand::Rollback(args) => plan_rollback(ctx, args),
     }
 }
 
@@ -91,6 +105,19 @@ fn plan_rotate(ctx: &CliContext, args: PlanRotateArgs) -> Result<()> {
         }
     }
 
+    if !ctx.policy.required_tags.is_empty() {
+        let vault = metadata::load(&paths.vault_toml)?;
+        let tags = vault
+            .credentials
+            .iter()
+            .find(|c| c.name == args.name)
+            .map(|c| c.tags.clone())
+            .unwrap_or_default();
+        if let Err(e) = ctx.policy.check_required_tags(&tags) {
+            issues.push(format!("{}; pass --tag when rotating", e));
+        }
+    }
+
     let key_type = if systemd::has_tpm2().unwrap_or(false) {
         constants::DEFAULT_KEY_TYPE_WITH_TPM2
     } else {
@@ -239,3 +266,113 @@ fn plan_migrate(ctx: &CliContext, args: PlanMigrateArgs) -> Result<()> {
 
     Ok(())
 }
+
+fn plan_rollback(ctx: &CliContext, args: PlanRollbackArgs) -> Result<()> {
+    let paths = &ctx.paths;
+    let cred_path = paths.credstore.join(format!("{}{}", args.name, constants::CRED_EXTENSION));
+    let prev_path = credstore::prev_cred_path(&paths.credstore, &args.name);
+
+    let current_modified = modified_time(&cred_path);
+    let prev_modified = modified_time(&prev_path);
+    let has_prev = prev_modified.is_some();
+
+    let mut issues: Vec<String> = Vec::new();
+    if !has_prev {
+        issues.push(format!("no .prev backup found for '{}' (rollback will fail)", args.name));
+    }
+    if current_modified.is_none() {
+        issues.push(format!("credential '{}' does not exist (backup will be restored as new)", args.name));
+    }
+
+    // Rollback restores the metadata snapshot taken at rotate time, or
+    // clears rotated_at when the credential has metadata but no snapshot.
+    // The .prev mtime is the rotate time, so only the snapshot dates it.
+    let snapshot = metadata::read_prev_snapshot(&metadata::prev_snapshot_path(
+        &paths.credstore,
+        &args.name,
+    ))?;
+    let has_snapshot = snapshot.is_some();
+    let prev_rotated = snapshot.as_ref().and_then(|s| s.rotated_at.or(s.created_at));
+    let has_meta = paths.vault_toml.exists()
+        && metadata::load(&paths.vault_toml)?
+            .credentials
+            .iter()
+            .any(|c| c.name == args.name);
+    let metadata_changes = has_snapshot || has_meta;
+    if has_meta && !has_snapshot {
+        issues.push("no metadata snapshot; rotated_at will be cleared".to_string());
+    }
+
+    if args.format == "json" {
+        let plan = serde_json::json!({
+            "action": "rollback rotate",
+            "credential": args.name,
+            "current_path": cred_path.display().to_string(),
+            "current_modified": current_modified.map(|t| t.to_rfc3339()),
+            "prev_path": prev_path.display().to_string(),
+            "prev_exists": has_prev,
+            "prev_modified": prev_modified.map(|t| t.to_rfc3339()),
+            "prev_rotated_at": prev_rotated.map(|t| t.to_rfc3339()),
+            "metadata_changes": metadata_changes,
+            "metadata_snapshot": has_snapshot,
+            "issues": issues,
+        });
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+    } else {
+        println!("Plan: rollback rotate '{}'", args.name);
+        println!("  current: {}", format_modified(current_modified));
+        println!("  previous: {}", format_previous(prev_modified, prev_rotated));
+        println!(
+            "  metadata: {}",
+            if has_snapshot {
+                "will be restored from snapshot"
+            } else if metadata_changes {
+                "rotated_at will be cleared"
+            } else {
+                "unchanged"
+            }
+        );
+        if issues.is_empty() {
+            println!("  status: ready");
+        } else {
+            for issue in &issues {
+                println!("  issue: {}", issue);
+            }
+        }
+        println!("\nNo changes made (dry-run).");
+    }
+
+    Ok(())
+}
+
+fn modified_time(path: &Path) -> Option<DateTime<Local>> {
+    if !path.is_file() {
+        return None;
+    }
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(DateTime::<Local>::from)
+}
+
+/// The `.prev` file's mtime (when rotate copied it) and, from the metadata
+/// snapshot, when that version was itself rotated in.
+fn format_previous(modified: Option<DateTime<Local>>, rotated: Option<DateTime<Utc>>) -> String {
+    match (modified, rotated) {
+        (None, _) => "missing".to_string(),
+        (Some(modified), Some(t)) => format!(
+            "{}, rotated {}",
+            format_modified(Some(modified)),
+            DateTime::<Local>::from(t).format("%Y-%m-%d %H:%M:%S")
+        ),
+        (Some(modified), None) => {
+            format!("{} (rotation date unknown)", format_modified(Some(modified)))
+        }
+    }
+}
+
+fn format_modified(modified: Option<DateTime<Local>>) -> String {
+    modified
+        .map(|t| format!("modified {}", t.format("%Y-%m-%d %H:%M:%S")))
+        .unwrap_or_else(|| "missing".to_string())
+}
@@ -0,0 +1,209 @@
+//! Inspect the effective policy (read-only).
+
+use crate::cli::{credential, CliContext};
+use crate::cli::credential::SecretCharset;
+use crate::core::vault;
+use anyhow::{bail, Context, Result};
+use clap::{Args, Subcommand};
+
+#[derive(Subcommand, Debug)]
+pub enum PolicyCommand {
+    /// Print the effective policy ([policy] with --profile and GOAMET_VAULT_POLICY_* applied)
+    Show(PolicyShowArgs),
+    /// Report whether an operation would pass every policy rule, without running it
+    Check(PolicyCheckArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct PolicyShowArgs {
+    /// Output format: toml|json
+    #[arg(long, default_value = "toml")]
+    pub format: String,
+}
+
+#[derive(Args, Debug)]
+pub struct PolicyCheckArgs {
+    /// Credential name (checked against name_prefix_by_service)
+    #[arg(long, value_parser = credential::parse_credential_name)]
+    pub name: Option<String>,
+
+    /// Service(s) the credential would be linked to
+    #[arg(long, value_name = "SERVICE")]
+    pub service: Vec<String>,
+
+    /// Key type (host|tpm2|host+tpm2|auto; default: what create would pick)
+    #[arg(long, value_parser = credential::parse_with_key)]
+    pub with_key: Option<String>,
+
+    /// Length of an auto-generated secret
+    #[arg(long)]
+    pub length: Option<usize>,
+
+    /// Charset of an auto-generated secret (alnum|alnum-symbols|hex|custom:CHARS)
+    #[arg(long, value_parser = credential::parse_charset)]
+    pub charset: Option<SecretCharset>,
+
+    /// Tag(s) the credential would carry
+    #[arg(long, value_name = "TAG")]
+    pub tag: Vec<String>,
+}
+
+pub fn run(ctx: &CliContext, cmd: PolicyCommand) -> Result<()> {
+    match cmd {
+        PolicyCommand::Show(args) => show(ctx, args),
+        PolicyCommand::Check(args) => check(ctx, args),
+    }
+}
+
+fn show(ctx: &CliContext, args: PolicyShowArgs) -> Result<()> {
+    if let Some(warning) = &ctx.policy_load_warning {
+        eprintln!("warning: {} (showing defaults)", warning);
+    }
+    match args.format.as_str() {
+        "json" => {
+            let json = serde_json::to_string_pretty(&ctx.policy).context("serialize policy")?;
+            println!("{}", json);
+        }
+        "toml" => {
+            // Nest under [policy] so tables come out as [policy.<key>].
+            let mut file = toml::Table::new();
+            let policy = toml::Value::try_from(&ctx.policy).context("serialize policy")?;
+            file.insert("policy".to_string(), policy);
+            print!("{}", toml::to_string_pretty(&file).context("serialize policy")?);
+        }
+        other => bail!("invalid format: {} (use toml|json)", other),
+    }
+    Ok(())
+}
+
+/// Outcome of one policy rule for `policy check`.
+#[derive(Debug, PartialEq, Eq)]
+struct RuleResult {
+    rule: String,
+    denied: Option<String>,
+}
+
+/// Run every policy predicate that applies to `args`.
+fn evaluate(ctx: &CliContext, args: &PolicyCheckArgs) -> Vec<RuleResult> {
+    let policy = &ctx.policy;
+    let mut results = Vec::new();
+    let mut push = |rule: String, outcome: Result<()>| {
+        results.push(RuleResult {
+            rule,
+            denied: outcome.err().map(|e| format!("{:#}", e)),
+        });
+    };
+
+    let with_key = vault::resolve_key_type(args.with_key.as_deref());
+    push(
+        format!("key type {}", with_key),
+        vault::check_key_policy(policy, &with_key, false).map_err(Into::into),
+    );
+    for svc in &args.service {
+        let allowed = if policy.is_service_allowed(svc) {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "policy: service '{}' not allowed (service_allowlist enforced)",
+                svc
+            ))
+        };
+        push(format!("service {}", svc), allowed);
+        if let Some(name) = &args.name {
+            push(
+                format!("name prefix for {}", svc),
+                policy.check_name_prefix(name, svc).map_err(Into::into),
+            );
+        }
+    }
+    if !policy.required_tags.is_empty() || !args.tag.is_empty() {
+        push(
+            "required tags".to_string(),
+            policy.check_required_tags(&args.tag).map_err(Into::into),
+        );
+    }
+    if let Some(length) = args.length {
+        push(
+            format!("auto length {}", length),
+            credential::check_auto_length(policy, length),
+        );
+    }
+    if args.charset.is_some() || policy.required_secret_charset.is_some() {
+        push(
+            "auto charset".to_string(),
+            credential::resolve_charset(policy, args.charset.clone(), false).map(|_| ()),
+        );
+    }
+    results
+}
+
+fn check(ctx: &CliContext, args: PolicyCheckArgs) -> Result<()> {
+    if let Some(warning) = &ctx.policy_load_warning {
+        eprintln!("warning: {} (checking against defaults)", warning);
+    }
+    let results = evaluate(ctx, &args);
+    let mut denied = 0u32;
+    for result in &results {
+        match &result.denied {
+            None => println!("  [PASS] {}", result.rule),
+            Some(reason) => {
+                println!("  [FAIL] {}: {}", result.rule, reason);
+                denied += 1;
+            }
+        }
+    }
+    println!();
+    if denied > 0 {
+        println!("Policy check: denied ({} of {} rule(s) failed)", denied, results.len());
+        std::process::exit(1);
+    }
+    println!("Policy check: allowed ({} rule(s) passed)", results.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::paths::VaultPaths;
+    use crate::models::policy::PolicySection;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_evaluate_reports_each_rule() {
+        let ctx = CliContext {
+            paths: VaultPaths::from_root("/nonexistent".into()),
+            non_interactive: true,
+            policy: PolicySection {
+                service_allowlist: vec!["api-*".into()],
+                name_prefix_by_service: HashMap::from([("api-web".into(), "web.".into())]),
+                min_auto_secret_length: Some(32),
+                ..Default::default()
+            },
+            policy_load_warning: None,
+            actor: None,
+            color: false,
+            askpass: None,
+            journald_override: None,
+            assume_yes: false,
+            pager: false,
+            audit_signing_key: Default::default(),
+            syslog: Default::default(),
+        };
+        let args = PolicyCheckArgs {
+            name: Some("db".into()),
+            service: vec!["api-web".into(), "mail".into()],
+            with_key: Some("host+tpm2".into()),
+            length: Some(16),
+            charset: None,
+            tag: Vec::new(),
+        };
+        let results = evaluate(&ctx, &args);
+        let denied: Vec<&str> = results
+            .iter()
+            .filter(|r| r.denied.is_some())
+            .map(|r| r.rule.as_str())
+            .collect();
+        assert_eq!(denied, vec!["name prefix for api-web", "service mail", "auto length 16"]);
+        assert!(results.iter().any(|r| r.rule == "key type host+tpm2" && r.denied.is_none()));
+    }
+}
@@ -0,0 +1,232 @@
+//! Reconcile vault.toml metadata with credstore and service maps.
+
+use crate::cli::{credential, CliContext};
+use crate::core::{credstore, file_lock::FileLock, metadata, service_map};
+use crate::models::credential::CredentialMeta;
+use crate::models::vault_config::VaultFile;
+use crate::util::systemd;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+#[derive(Args, Debug)]
+pub struct ReconcileArgs {
+    /// Add metadata stubs for orphan .cred files (and, with --confirm,
+    /// remove metadata for missing .cred files)
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Allow --fix to remove metadata entries
+    #[arg(long, requires = "fix")]
+    pub confirm: bool,
+}
+
+/// Differences between vault.toml, credstore and the service maps.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Drift {
+    /// Metadata entries without a `.cred` file.
+    missing_files: Vec<String>,
+    /// `.cred` files without a metadata entry.
+    orphan_files: Vec<String>,
+    /// (credential, only in maps, only in metadata) service mismatches.
+    service_links: Vec<(String, Vec<String>, Vec<String>)>,
+}
+
+impl Drift {
+    fn is_empty(&self) -> bool {
+        self.missing_files.is_empty() && self.orphan_files.is_empty() && self.service_links.is_empty()
+    }
+}
+
+fn find_drift(
+    vault: &VaultFile,
+    on_disk: &[String],
+    map_refs: &BTreeMap<String, Vec<String>>,
+) -> Drift {
+    let mut drift = Drift::default();
+    for meta in &vault.credentials {
+        if !on_disk.contains(&meta.name) {
+            drift.missing_files.push(meta.name.clone());
+            continue;
+        }
+        let mapped = map_refs.get(&meta.name).cloned().unwrap_or_default();
+        let (only_in_maps, only_in_metadata) = credential::service_drift(&meta.services, &mapped);
+        if !only_in_maps.is_empty() || !only_in_metadata.is_empty() {
+            drift.service_links.push((
+                meta.name.clone(),
+                only_in_maps.into_iter().map(str::to_string).collect(),
+                only_in_metadata.into_iter().map(str::to_string).collect(),
+            ));
+        }
+    }
+    for name in on_disk {
+        if !vault.credentials.iter().any(|c| &c.name == name) {
+            drift.orphan_files.push(name.clone());
+        }
+    }
+    drift.missing_files.sort();
+    drift.orphan_files.sort();
+    drift
+}
+
+pub fn run(ctx: &CliContext, args: ReconcileArgs) -> Result<()> {
+    let paths = &ctx.paths;
+    let _vault_lock = if args.fix {
+        Some(FileLock::exclusive(&paths.vault_lock)?)
+    } else {
+        None
+    };
+
+    let mut vault = if paths.vault_toml.exists() {
+        metadata::load(&paths.vault_toml)?
+    } else {
+        VaultFile::default()
+    };
+    let creds = if paths.credstore.is_dir() {
+        credstore::list_credentials(&paths.credstore)?
+    } else {
+        Vec::new()
+    };
+    let on_disk: Vec<String> = creds.iter().map(|c| c.name.clone()).collect();
+    let refs = service_map::scan_references(&paths.services, &paths.credstore);
+    let drift = find_drift(&vault, &on_disk, &refs.by_credential);
+
+    for err in &refs.unreadable {
+        println!("  [WARN] Cannot parse service map {}", err);
+    }
+    if drift.is_empty() {
+        println!("  [PASS] vault.toml, credstore and service maps agree");
+        return Ok(());
+    }
+
+    for name in &drift.missing_files {
+        println!("  [FAIL] {}: metadata present but .cred file missing", name);
+    }
+    for name in &drift.orphan_files {
+        println!("  [FAIL] {}: .cred file present but no metadata", name);
+    }
+    for (name, only_in_maps, only_in_metadata) in &drift.service_links {
+        if !only_in_maps.is_empty() {
+            println!(
+                "  [WARN] {}: referenced by map(s) {} but not in metadata services",
+                name,
+                only_in_maps.join(",")
+            );
+        }
+        if !only_in_metadata.is_empty() {
+            println!(
+                "  [WARN] {}: metadata services {} not referenced by any map",
+                name,
+                only_in_metadata.join(",")
+            );
+        }
+    }
+
+    if !args.fix {
+        println!();
+        println!("Run 'goamet-vault reconcile --fix' to add metadata stubs for orphan .cred files.");
+        if !drift.missing_files.is_empty() {
+            println!("Add --confirm to also remove metadata for missing .cred files.");
+        }
+        std::process::exit(1);
+    }
+
+    println!();
+    // (audit action, credential), audited once vault.toml is saved.
+    let mut applied: Vec<(&str, &String)> = Vec::new();
+    metadata::ensure_vault_section(&mut vault, paths.credstore_override());
+    for name in &drift.orphan_files {
+        let modified = creds
+            .iter()
+            .find(|c| &c.name == name)
+            .and_then(|c| c.modified)
+            .map(|t: SystemTime| DateTime::<Utc>::from(t));
+        // Read from the .cred header; left unset when not recognizable.
+        let key_type = systemd::detect_key_type(&credstore::cred_path(&paths.credstore, name))
+            .ok()
+            .flatten();
+        let meta = CredentialMeta {
+            name: name.clone(),
+            description: Some("Added by reconcile (orphan .cred file)".to_string()),
+            created_at: modified,
+            rotated_at: modified,
+            encryption_key: key_type.map(str::to_string),
+            services: refs.by_credential.get(name).cloned().unwrap_or_default(),
+            ..Default::default()
+        };
+        metadata::upsert_credential(&mut vault, meta);
+        applied.push(("reconcile-add", name));
+        println!(
+            "  [FIX] {}: added metadata stub (key: {})",
+            name,
+            key_type.unwrap_or("unknown")
+        );
+    }
+    if args.confirm {
+        for name in &drift.missing_files {
+            metadata::remove_credential(&mut vault, name);
+            applied.push(("reconcile-remove", name));
+            println!("  [FIX] {}: removed metadata", name);
+        }
+    } else if !drift.missing_files.is_empty() {
+        println!(
+            "  [SKIP] kept metadata for {} missing .cred file(s) (use --confirm to remove)",
+            drift.missing_files.len()
+        );
+    }
+    if !applied.is_empty() {
+        if let Err(e) = metadata::save(&paths.vault_toml, &vault) {
+            for (action, name) in &applied {
+                ctx.audit_result(ctx.audit_ctx(action, name), false, Some(&e));
+            }
+            return Err(e);
+        }
+        for (action, name) in &applied {
+            ctx.audit_result(ctx.audit_ctx(action, name), true, None);
+        }
+    }
+    println!(
+        "Reconcile: {} change(s) applied; service link drift is reported only",
+        applied.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(name: &str, services: &[&str]) -> CredentialMeta {
+        CredentialMeta {
+            name: name.to_string(),
+            services: services.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_drift() {
+        let vault = VaultFile {
+            credentials: vec![
+                meta("db", &["api.service"]),
+                meta("gone", &[]),
+                meta("token", &["old"]),
+            ],
+            ..Default::default()
+        };
+        let on_disk = vec!["db".to_string(), "token".to_string(), "stray".to_string()];
+        let mut map_refs = BTreeMap::new();
+        map_refs.insert("db".to_string(), vec!["api".to_string()]);
+        map_refs.insert("token".to_string(), vec!["web".to_string()]);
+
+        let drift = find_drift(&vault, &on_disk, &map_refs);
+        assert_eq!(drift.missing_files, vec!["gone"]);
+        assert_eq!(drift.orphan_files, vec!["stray"]);
+        assert_eq!(
+            drift.service_links,
+            vec![("token".to_string(), vec!["web".to_string()], vec!["old".to_string()])]
+        );
+    }
+}
@@ -72,11 +72,12 @@ fn run_leak_test(_ctx: &CliContext, args: TestRunArgs) -> Result<()> {
     }
 
     // Preflight binaries
-    let mut checks: Vec<CheckResult> = Vec::new();
-    checks.push(check_bin("systemd-run")?);
-    checks.push(check_bin("systemctl")?);
-    checks.push(check_bin("journalctl")?);
-    checks.push(check_bin("systemd-creds")?);
+    let mut checks: Vec<CheckResult> = vec![
+        check_bin("systemd-run")?,
+        check_bin("systemctl")?,
+        check_bin("journalctl")?,
+        check_bin("systemd-creds")?,
+    ];
 
     // Create a temp vault root under /tmp (keeps tests isolated from real credstore).
     // NOTE: using /dev/shm triggers credential setup failures on some systems (Protocol error).
@@ -91,9 +92,7 @@ fn run_leak_test(_ctx: &CliContext, args: TestRunArgs) -> Result<()> {
     let secret: Zeroizing<String> = Zeroizing::new(random_secret(48));
 
     // Write temp plaintext to credstore (0700 dir), then encrypt; temp file is removed automatically.
-    let tmp_plain = tempfile::Builder::new()
-        .prefix(".secret-")
-        .tempfile_in(&credstore)
+    let tmp_plain = vault_fs::secure_tempfile(&credstore, ".secret-", "")
         .context("create temp plaintext secret")?;
     {
         let mut f = tmp_plain.as_file();
@@ -0,0 +1,180 @@
+//! Inspect, restore and purge deleted credentials.
+
+use crate::cli::{credential, CliContext};
+use crate::core::trash::{self, TrashedCredential};
+use crate::core::{credstore, file_lock::FileLock, metadata};
+use crate::models::credential::CredentialMeta;
+use anyhow::{bail, Result};
+use chrono::{Duration, Local, Utc};
+use clap::{Args, Subcommand};
+use comfy_table::{Attribute, Cell};
+
+#[derive(Subcommand, Debug)]
+pub enum TrashCommand {
+    /// List deleted credentials that can still be restored
+    List,
+    /// Move a deleted credential (and its metadata) back into the credstore
+    Restore(TrashRestoreArgs),
+    /// Permanently remove trashed credentials
+    Purge(TrashPurgeArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct TrashRestoreArgs {
+    /// Credential name
+    #[arg(value_parser = credential::parse_credential_name)]
+    pub name: String,
+
+    /// Restore this deletion (see `trash list`) instead of the newest one
+    #[arg(long)]
+    pub stamp: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct TrashPurgeArgs {
+    /// Only purge entries deleted longer ago than this (e.g. 30d, 12h)
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    pub older_than: Option<Duration>,
+}
+
+/// Parse `<N>d` or `<N>h` with N > 0.
+fn parse_age(s: &str) -> Result<Duration, String> {
+    let err = || format!("invalid age '{}' (use e.g. 30d or 12h)", s);
+    let parse = |number: &str| number.parse::<i64>().ok().filter(|n| *n > 0).ok_or_else(err);
+    if let Some(days) = s.strip_suffix('d') {
+        Duration::try_days(parse(days)?).ok_or_else(err)
+    } else if let Some(hours) = s.strip_suffix('h') {
+        Duration::try_hours(parse(hours)?).ok_or_else(err)
+    } else {
+        Err(err())
+    }
+}
+
+pub fn run(ctx: &CliContext, cmd: TrashCommand) -> Result<()> {
+    match cmd {
+        TrashCommand::List => list(ctx),
+        TrashCommand::Restore(args) => {
+            let mut audit = ctx.audit_ctx("trash-restore", &args.name);
+            audit.target_path =
+                Some(credstore::cred_path(&ctx.paths.credstore, &args.name).display().to_string());
+            ctx.audited(audit, |audit| {
+                let entry = restore(ctx, &args)?;
+                audit.reason = Some(format!("trash stamp {}", entry.stamp));
+                Ok(())
+            })
+        }
+        TrashCommand::Purge(args) => purge(ctx, args),
+    }
+}
+
+fn list(ctx: &CliContext) -> Result<()> {
+    let items = trash::list(&ctx.paths)?;
+    if items.is_empty() {
+        println!("Trash is empty ({})", ctx.paths.trash.display());
+        return Ok(());
+    }
+    let mut table = ctx.table();
+    table.set_header(vec![
+        Cell::new("Name").add_attribute(Attribute::Bold),
+        Cell::new("Deleted").add_attribute(Attribute::Bold),
+        Cell::new("Stamp").add_attribute(Attribute::Bold),
+        Cell::new("Metadata").add_attribute(Attribute::Bold),
+    ]);
+    for item in items {
+        table.add_row(vec![
+            item.name,
+            item.deleted_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string(),
+            item.stamp,
+            if item.has_metadata { "yes" } else { "no" }.to_string(),
+        ]);
+    }
+    println!("{}", table);
+    Ok(())
+}
+
+/// Restore a trashed credential and its metadata. Returns the trash entry
+/// that was restored.
+pub(crate) fn restore(ctx: &CliContext, args: &TrashRestoreArgs) -> Result<TrashedCredential> {
+    let paths = &ctx.paths;
+    let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
+    // Load first so a broken vault.toml fails before the file moves.
+    let mut vault = metadata::load(&paths.vault_toml)?;
+    let (entry, meta, snapshot) = trash::restore(paths, &args.name, args.stamp.as_deref())?;
+
+    metadata::ensure_vault_section(&mut vault, paths.credstore_override());
+    let meta = meta.unwrap_or_else(|| CredentialMeta {
+        name: args.name.clone(),
+        description: Some("Restored from trash (no metadata snapshot)".to_string()),
+        ..Default::default()
+    });
+    metadata::upsert_credential(&mut vault, meta);
+    metadata::save(&paths.vault_toml, &vault)?;
+    if let Err(e) = trash::remove_snapshot(&snapshot) {
+        eprintln!("warning: {:#}", e);
+    }
+
+    println!("Restored {} (deleted {})", args.name, entry.stamp);
+    Ok(entry)
+}
+
+fn purge(ctx: &CliContext, args: TrashPurgeArgs) -> Result<()> {
+    let cutoff = args.older_than.map(|age| Utc::now() - age);
+    let targets: Vec<_> = trash::list(&ctx.paths)?
+        .into_iter()
+        .filter(|e| cutoff.is_none_or(|c| e.deleted_at < c))
+        .collect();
+    if targets.is_empty() {
+        println!("Nothing to purge");
+        return Ok(());
+    }
+    if !ctx.confirm(&format!(
+        "Permanently remove {} trashed credential(s)? This cannot be undone",
+        targets.len()
+    ))? {
+        bail!("aborted: trash not purged");
+    }
+
+    let _vault_lock = FileLock::exclusive(&ctx.paths.vault_lock)?;
+    // A restore may have run while the prompt was open; purge only what is left.
+    let still_trashed = trash::list(&ctx.paths)?;
+    let targets: Vec<_> = targets
+        .into_iter()
+        .filter(|t| still_trashed.iter().any(|e| e.name == t.name && e.stamp == t.stamp))
+        .collect();
+
+    let mut failed = 0u32;
+    for entry in &targets {
+        let mut audit = ctx.audit_ctx("trash-purge", &entry.name);
+        audit.target_path = Some(entry.cred_path(&ctx.paths.trash).display().to_string());
+        match ctx.audited(audit, |_| trash::purge(&ctx.paths, entry)) {
+            Ok(()) => println!("  Purged: {} ({})", entry.name, entry.stamp),
+            Err(e) => {
+                eprintln!("  Failed: {}: {:#}", entry.name, e);
+                failed += 1;
+            }
+        }
+    }
+    println!("\nPurge: {} removed, {} failed", targets.len() as u32 - failed, failed);
+    if failed > 0 {
+        bail!("{} trash entry/entries failed to purge", failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_age("12h").unwrap(), Duration::hours(12));
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("1w").is_err());
+        assert!(parse_age("0d").is_err());
+        assert!(parse_age("-3h").is_err());
+        assert!(parse_age("3é").is_err());
+        assert!(parse_age("").is_err());
+    }
+}
@@ -1,11 +1,12 @@
 //! Post-operation verification commands.
 
-use crate::cli::CliContext;
+use crate::cli::{credential, CliContext};
 use crate::constants;
 use crate::core::{metadata, service_map};
 use crate::util::systemd;
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Subcommand, Debug)]
 pub enum VerifyCommand {
@@ -15,12 +16,18 @@ pub enum VerifyCommand {
     Dropin(VerifyDropinArgs),
     /// Verify all credentials and service maps
     All(VerifyAllArgs),
+    /// Check whether this host can decrypt a .cred file, without importing it
+    File(VerifyFileArgs),
 }
 
 #[derive(Args, Debug)]
 pub struct VerifyRotateArgs {
     /// Credential name
     pub name: String,
+
+    /// Name the credential was encrypted under, if not the file stem
+    #[arg(long, value_name = "NAME", value_parser = credential::parse_credential_name)]
+    pub cred_name: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -32,11 +39,26 @@ pub struct VerifyDropinArgs {
 #[derive(Args, Debug)]
 pub struct VerifyAllArgs {}
 
+#[derive(Args, Debug)]
+pub struct VerifyFileArgs {
+    /// Path to the .cred file
+    pub path: PathBuf,
+
+    /// Allow a path outside the vault root
+    #[arg(long)]
+    pub allow_outside: bool,
+
+    /// Name the credential was encrypted under, if not the file stem
+    #[arg(long, value_name = "NAME", value_parser = credential::parse_credential_name)]
+    pub cred_name: Option<String>,
+}
+
 pub fn run(ctx: &CliContext, cmd: VerifyCommand) -> Result<()> {
     match cmd {
         VerifyCommand::Rotate(args) => verify_rotate(ctx, args),
         VerifyCommand::Dropin(args) => verify_dropin(ctx, args),
         VerifyCommand::All(_) => verify_all(ctx),
+        VerifyCommand::File(args) => verify_file(ctx, args),
     }
 }
 
@@ -59,8 +81,7 @@ fn verify_rotate(ctx: &CliContext, args: VerifyRotateArgs) -> Result<()> {
 
     // Try decrypt
     if cred_path.is_file() {
-        let tmp = tempfile::NamedTempFile::new()?;
-        match systemd::decrypt_to_file(&cred_path, tmp.path()) {
+        match systemd::verify_decryptable(&cred_path, args.cred_name.as_deref()) {
             Ok(()) => {
                 println!("  [PASS] Decryptable: {}", args.name);
                 passed += 1;
@@ -97,6 +118,56 @@ fn verify_rotate(ctx: &CliContext, args: VerifyRotateArgs) -> Result<()> {
     Ok(())
 }
 
+fn verify_file(ctx: &CliContext, args: VerifyFileArgs) -> Result<()> {
+    let cred_path = credential::resolve_cred_file(ctx, &args.path, args.allow_outside)?;
+    let display = cred_path.display();
+
+    let key_type = systemd::detect_key_type(&cred_path)?;
+    match key_type {
+        Some(key) => println!("  [INFO] Sealed with key type: {}", key),
+        None => println!("  [WARN] Key type not recognized (not a systemd-creds credential?)"),
+    }
+
+    let decryptable = match systemd::verify_decryptable(&cred_path, args.cred_name.as_deref()) {
+        Ok(()) => {
+            println!("  [PASS] Decryptable on this host: {}", display);
+            true
+        }
+        Err(e) => {
+            println!("  [FAIL] Cannot decrypt on this host: {} ({})", display, e);
+            if let Some(hint) = key_type.and_then(decrypt_failure_hint) {
+                println!("         {}", hint);
+            }
+            if args.cred_name.is_none() {
+                println!(
+                    "         encrypted under a name other than the file stem? pass --cred-name"
+                );
+            }
+            false
+        }
+    };
+
+    println!();
+    if decryptable {
+        println!("Verify file '{}': 1 passed, 0 failed", display);
+    } else {
+        println!("Verify file '{}': 0 passed, 1 failed", display);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Likely cause of a decrypt failure, by the key the credential was sealed with.
+fn decrypt_failure_hint(key_type: &str) -> Option<&'static str> {
+    if key_type.contains("tpm2") {
+        Some("TPM2-sealed: it only decrypts on the machine (and PCR state) it was sealed on")
+    } else if key_type == "host" {
+        Some("host-key sealed: it only decrypts with the credential.secret of its own machine")
+    } else {
+        None
+    }
+}
+
 fn verify_dropin(ctx: &CliContext, args: VerifyDropinArgs) -> Result<()> {
     let paths = &ctx.paths;
     let map_name = args
@@ -177,8 +248,7 @@ fn verify_all(ctx: &CliContext) -> Result<()> {
                 .credstore
                 .join(format!("{}{}", cred.name, constants::CRED_EXTENSION));
             if cred_path.is_file() {
-                let tmp = tempfile::NamedTempFile::new()?;
-                match systemd::decrypt_to_file(&cred_path, tmp.path()) {
+                match systemd::verify_decryptable(&cred_path, None) {
                     Ok(()) => {
                         println!("  [PASS] {}", cred.name);
                         total_passed += 1;
@@ -0,0 +1,160 @@
+//! Reload services when their credentials change on disk.
+//!
+//! `watch` follows the credstore with inotify. When a `.cred` is written or
+//! renamed into place (create, rotate, copy, trash restore), every service
+//! whose map references it gets `systemctl reload-or-restart`. Changes are
+//! collected until the credstore has been quiet for [`SETTLE`], so a batch of
+//! rotations restarts each service once.
+
+use crate::cli::{credential, dropin, CliContext};
+use crate::constants;
+use crate::core::service_map::{self, MapReferences};
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use clap::Args;
+use nix::errno::Errno;
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify};
+use std::collections::{BTreeMap, BTreeSet};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Quiet period before pending changes are acted on.
+const SETTLE: Duration = Duration::from_millis(500);
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// Log which services would be restarted instead of restarting them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+pub fn run(ctx: &CliContext, args: WatchArgs) -> Result<()> {
+    let credstore = &ctx.paths.credstore;
+    let inotify = Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)
+        .context("initialize inotify")?;
+    inotify
+        .add_watch(credstore, AddWatchFlags::IN_CLOSE_WRITE | AddWatchFlags::IN_MOVED_TO)
+        .with_context(|| format!("watch {}", credstore.display()))?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler_stop = Arc::clone(&stop);
+    ctrlc::set_handler(move || handler_stop.store(true, Ordering::SeqCst))
+        .context("install Ctrl-C handler")?;
+
+    println!(
+        "Watching {}{} (Ctrl-C to stop)...",
+        credstore.display(),
+        if args.dry_run { " [dry-run]" } else { "" }
+    );
+    let mut pending = BTreeSet::new();
+    let mut last_change = Instant::now();
+    while !stop.load(Ordering::SeqCst) {
+        match inotify.read_events() {
+            Ok(events) => {
+                for event in events {
+                    let name = event.name.as_deref().and_then(|n| n.to_str());
+                    if let Some(cred) = name.and_then(changed_credential) {
+                        pending.insert(cred);
+                        last_change = Instant::now();
+                    }
+                }
+            }
+            Err(Errno::EAGAIN) => {}
+            Err(e) => return Err(e).context("read inotify events"),
+        }
+        if !pending.is_empty() && last_change.elapsed() >= SETTLE {
+            restart_affected(ctx, &std::mem::take(&mut pending), args.dry_run);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+/// Credential name for a changed credstore file, if it is a `<name>.cred`
+/// (not a `.prev` backup or a temp file).
+fn changed_credential(file_name: &str) -> Option<String> {
+    let stem = file_name.strip_suffix(constants::CRED_EXTENSION)?;
+    credential::parse_credential_name(stem).ok()
+}
+
+/// Group changed credentials by the services (map file stems) using them.
+fn affected_services(
+    changed: &BTreeSet<String>,
+    refs: &MapReferences,
+) -> BTreeMap<String, Vec<String>> {
+    let mut by_service: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for cred in changed {
+        for svc in refs.by_credential.get(cred).into_iter().flatten() {
+            by_service.entry(svc.clone()).or_default().push(cred.clone());
+        }
+    }
+    by_service
+}
+
+fn restart_affected(ctx: &CliContext, changed: &BTreeSet<String>, dry_run: bool) {
+    let stamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+    let refs = service_map::scan_references(&ctx.paths.services, &ctx.paths.credstore);
+    for unreadable in &refs.unreadable {
+        eprintln!("warning: map not readable: {}", unreadable);
+    }
+    for cred in changed.iter().filter(|c| !refs.by_credential.contains_key(*c)) {
+        println!("{}  {} changed; no service map references it", stamp, cred);
+    }
+
+    for (svc, creds) in affected_services(changed, &refs) {
+        let (unit, _) = dropin::normalize_service_name(&svc);
+        let creds = creds.join(",");
+        if dry_run {
+            println!("{}  would reload-or-restart {} ({})", stamp, unit, creds);
+            continue;
+        }
+        let mut audit = ctx.audit_ctx("watch-restart", &creds);
+        audit.target_path = Some(unit.clone());
+        match ctx.audited(audit, |_| reload_or_restart(&unit)) {
+            Ok(()) => println!("{}  reloaded {} ({})", stamp, unit, creds),
+            Err(e) => eprintln!("{}  Failed: {}: {:#}", stamp, unit, e),
+        }
+    }
+}
+
+fn reload_or_restart(unit: &str) -> Result<()> {
+    let status = Command::new("systemctl")
+        .arg("reload-or-restart")
+        .arg(unit)
+        .status()
+        .context("run systemctl reload-or-restart")?;
+    if !status.success() {
+        bail!("systemctl reload-or-restart {} failed ({})", unit, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_credential_ignores_backups_and_temp_files() {
+        assert_eq!(changed_credential("db.v2.cred").as_deref(), Some("db.v2"));
+        assert!(changed_credential("db.cred.prev").is_none());
+        assert!(changed_credential("cred-Ab3x.cred.tmp").is_none());
+        assert!(changed_credential(".cred").is_none());
+    }
+
+    #[test]
+    fn test_affected_services_groups_by_service() {
+        let mut refs = MapReferences::default();
+        refs.by_credential.insert("db".into(), vec!["api".into(), "worker".into()]);
+        refs.by_credential.insert("token".into(), vec!["api".into()]);
+        let changed: BTreeSet<String> =
+            ["db", "token", "unused"].iter().map(|s| s.to_string()).collect();
+
+        let by_service = affected_services(&changed, &refs);
+        assert_eq!(by_service["api"], vec!["db", "token"]);
+        assert_eq!(by_service["worker"], vec!["db"]);
+        assert_eq!(by_service.len(), 2);
+    }
+}
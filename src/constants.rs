@@ -3,6 +3,9 @@
 /// Default vault root directory.
 pub const DEFAULT_VAULT_ROOT: &str = "/opt/services/vault";
 
+/// Marker file that identifies a vault root during auto-detection.
+pub const ROOT_MARKER_FILE: &str = ".goamet-vault-root";
+
 /// Path to the systemd host encryption key.
 pub const HOST_KEY_PATH: &str = "/var/lib/systemd/credential.secret";
 
@@ -12,6 +15,12 @@ pub const CREDSTORE_DIR_MODE: u32 = 0o700;
 /// Permission mode for individual credential files.
 pub const CRED_FILE_MODE: u32 = 0o600;
 
+/// Current vault.toml schema version (see `metadata::migrate`).
+pub const VAULT_SCHEMA_VERSION: u32 = 2;
+
+/// Permission mode temp files are created with, before any data is written.
+pub const TEMP_FILE_MODE: u32 = 0o600;
+
 /// Permission mode for vault.toml.
 pub const VAULT_TOML_MODE: u32 = 0o640;
 
@@ -24,9 +33,18 @@ pub const SERVICES_DIR_MODE: u32 = 0o755;
 /// Permission mode for the units directory.
 pub const UNITS_DIR_MODE: u32 = 0o755;
 
+/// Permission mode for generated unit files (timers and their services).
+pub const UNIT_FILE_MODE: u32 = 0o644;
+
+/// Default limit for one systemd-creds (or systemd-analyze) run, in seconds.
+pub const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 30;
+
 /// Maximum secret size in bytes (1 MiB).
 pub const MAX_SECRET_SIZE: usize = 1_048_576;
 
+/// Maximum `.cred` size inlined via `SetCredentialEncrypted=` (16 KiB).
+pub const MAX_INLINE_CRED_SIZE: u64 = 16 * 1024;
+
 /// File extension for encrypted credential files.
 pub const CRED_EXTENSION: &str = ".cred";
 
@@ -1,12 +1,29 @@
 //! Append-only audit trail for credential operations.
+//!
+//! Entries are hash-chained: each carries the `entry_hash` of the one before
+//! it in `prev_hash`. `audit rotate` renames a full log to `audit.log.1`
+//! (older files shift to `.2`, `.3`, ...); the first entry of the fresh
+//! `audit.log` chains from the last entry of `audit.log.1`, so
+//! `verify_chain_all` can check the history across files as one chain.
+//!
+//! With `[audit] signing_key_path` set, each entry's `entry_hash` is also
+//! signed with Ed25519 into `audit.log.sig`, so a full rewrite of the log
+//! (which would yield a valid SHA-256 chain) is still detectable. The
+//! signature is written before the entry, so a signing failure never leaves
+//! an unsigned entry behind.
 
 use crate::constants;
 use crate::core::file_lock::FileLock;
+use crate::core::metadata;
 use crate::core::paths::VaultPaths;
+use crate::util::{ct_eq, fs as vault_fs};
 use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fs::{self, OpenOptions};
 use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
 
@@ -68,6 +85,8 @@ fn detect_actor() -> String {
 pub struct AuditContext {
     pub action: String,
     pub credential: String,
+    /// Actor override; `None` uses the detected user.
+    pub actor: Option<String>,
     pub reason: Option<String>,
     pub output_mode: Option<String>,
     pub target_path: Option<String>,
@@ -76,21 +95,30 @@ pub struct AuditContext {
     pub service_context: Option<String>,
 }
 
-/// Log an action with auto-detected actor (simple API).
+/// Log an action with auto-detected actor (simple API), signed with the
+/// key from vault.toml when one is configured.
 pub fn log(paths: &VaultPaths, action: &str, credential: &str) -> Result<()> {
-    log_action(paths, action, credential, &detect_actor())
+    let key = signing_key_from_config(paths)?;
+    log_action(paths, action, credential, &detect_actor(), key.as_ref())
 }
 
-/// Write a simple audit entry to the append-only log.
+/// Return `actor` if set, else the detected user.
+pub fn resolve_actor(actor: Option<&str>) -> String {
+    actor.map(str::to_string).unwrap_or_else(detect_actor)
+}
+
+/// Write a simple audit entry to the append-only log, signed with
+/// `signing_key` when given.
 pub fn log_action(
     paths: &VaultPaths,
     action: &str,
     credential: &str,
     actor: &str,
+    signing_key: Option<&SigningKey>,
 ) -> Result<()> {
     let _lock = FileLock::exclusive(&paths.audit_lock)?;
     let audit_path = paths.root.join("audit.log");
-    let prev_hash = last_line_hash(&audit_path).unwrap_or(None);
+    let prev_hash = chain_tip(paths).unwrap_or(None);
 
     let mut entry = AuditEntry {
         timestamp: Utc::now(),
@@ -111,28 +139,30 @@ pub fn log_action(
     };
 
     // Compute entry hash using canonical JSON (without entry_hash field)
-    entry.entry_hash = Some(compute_entry_hash(&entry)?);
+    let entry_hash = compute_entry_hash(&entry)?;
+    entry.entry_hash = Some(entry_hash.clone());
 
     let line = serde_json::to_string(&entry).context("serialize audit entry")?;
-    append_line(&audit_path, &line)?;
-    Ok(())
+    write_entry(&audit_path, &line, &entry_hash, signing_key)
 }
 
-/// Write a forensics-grade audit entry with full context and result.
+/// Write a forensics-grade audit entry with full context and result,
+/// signed with `signing_key` when given.
 pub fn log_with_result(
     paths: &VaultPaths,
     ctx: AuditContext,
     success: bool,
     error: Option<String>,
+    signing_key: Option<&SigningKey>,
 ) -> Result<()> {
     let _lock = FileLock::exclusive(&paths.audit_lock)?;
     let audit_path = paths.root.join("audit.log");
-    let prev_hash = last_line_hash(&audit_path).unwrap_or(None);
+    let prev_hash = chain_tip(paths).unwrap_or(None);
 
     let mut entry = AuditEntry {
         timestamp: Utc::now(),
         action: ctx.action,
-        actor: detect_actor(),
+        actor: ctx.actor.unwrap_or_else(detect_actor),
         credential: ctx.credential,
         metadata_only: true,
         prev_hash,
@@ -150,11 +180,175 @@ pub fn log_with_result(
         hash_version: Some(2),
     };
 
-    entry.entry_hash = Some(compute_entry_hash(&entry)?);
+    let entry_hash = compute_entry_hash(&entry)?;
+    entry.entry_hash = Some(entry_hash.clone());
 
     let line = serde_json::to_string(&entry).context("serialize audit entry")?;
-    append_line(&audit_path, &line)?;
-    Ok(())
+    write_entry(&audit_path, &line, &entry_hash, signing_key)
+}
+
+/// Path of the detached signature file for a log file (`<log>.sig`).
+pub fn signature_path(log_path: &std::path::Path) -> std::path::PathBuf {
+    let mut name = log_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".sig");
+    log_path.with_file_name(name)
+}
+
+/// Decode a 32-byte Ed25519 key stored raw or as base64 text.
+fn decode_key_bytes(data: &[u8], path: &std::path::Path) -> Result<[u8; 32]> {
+    if let Ok(raw) = <[u8; 32]>::try_from(data) {
+        return Ok(raw);
+    }
+    let text = std::str::from_utf8(data)
+        .map_err(|_| anyhow::anyhow!("{}: not a 32-byte or base64 Ed25519 key", path.display()))?;
+    let decoded = zeroize::Zeroizing::new(
+        STANDARD
+            .decode(text.trim())
+            .with_context(|| format!("{}: invalid base64 key", path.display()))?,
+    );
+    <[u8; 32]>::try_from(decoded.as_slice())
+        .map_err(|_| anyhow::anyhow!("{}: Ed25519 key must be 32 bytes", path.display()))
+}
+
+/// Load the Ed25519 signing key (32 raw bytes or base64).
+pub fn load_signing_key(path: &std::path::Path) -> Result<SigningKey> {
+    let data = zeroize::Zeroizing::new(
+        fs::read(path).with_context(|| format!("read audit signing key {}", path.display()))?,
+    );
+    let bytes = zeroize::Zeroizing::new(decode_key_bytes(&data, path)?);
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+/// Load an Ed25519 public key (32 raw bytes or base64).
+pub fn load_verifying_key(path: &std::path::Path) -> Result<VerifyingKey> {
+    let data = fs::read(path).with_context(|| format!("read public key {}", path.display()))?;
+    let bytes = decode_key_bytes(&data, path)?;
+    VerifyingKey::from_bytes(&bytes)
+        .with_context(|| format!("{}: invalid Ed25519 public key", path.display()))
+}
+
+/// The signing key named by `[audit] signing_key_path`, if configured.
+pub fn signing_key_from_config(paths: &VaultPaths) -> Result<Option<SigningKey>> {
+    let section = metadata::load_audit_section(&paths.vault_toml)?;
+    section
+        .signing_key_path
+        .map(|path| load_signing_key(&path))
+        .transpose()
+}
+
+/// Append an entry line, first appending `<entry_hash> <base64 signature>`
+/// to the log's `.sig` file when a key is given. A failure to record the
+/// signature leaves the log untouched; an orphaned signature is harmless.
+/// Callers hold `audit_lock`.
+fn write_entry(
+    audit_path: &std::path::Path,
+    line: &str,
+    entry_hash: &str,
+    signing_key: Option<&SigningKey>,
+) -> Result<()> {
+    if let Some(key) = signing_key {
+        let signature = key.sign(entry_hash.as_bytes());
+        let sig_line = format!("{} {}", entry_hash, STANDARD.encode(signature.to_bytes()));
+        append_line(&signature_path(audit_path), &sig_line)?;
+    }
+    append_line(audit_path, line)
+}
+
+/// Signature results for one log file, from [`verify_signatures`].
+#[derive(Debug, Default)]
+pub struct SignatureCheck {
+    pub valid: usize,
+    /// Unsigned entries before the first signed one, written before signing
+    /// was enabled; they are not checked against the key.
+    pub predating: usize,
+    pub issues: Vec<ChainIssue>,
+    /// A signed entry was seen, in this file or an earlier one.
+    pub started: bool,
+    /// Position (index, line, offset) of the file's last entry.
+    last: Option<(usize, usize, u64)>,
+}
+
+/// Check every entry of `log_path` against its signature in `<log>.sig`.
+/// Unsigned entries are only failures once a signed entry has been seen
+/// (`started` carries that over from older files); earlier ones predate
+/// signing. Rewriting them would break the chain into the first signed
+/// entry, so skipping them does not hide tampering. A signature only counts
+/// when the entry still hashes to its signed `entry_hash` (compared in
+/// constant time, like the chain check).
+pub fn verify_signatures(
+    log_path: &std::path::Path,
+    key: &VerifyingKey,
+    started: bool,
+) -> Result<SignatureCheck> {
+    let sig_path = signature_path(log_path);
+    let mut signatures: HashMap<String, String> = HashMap::new();
+    if sig_path.exists() {
+        let content = fs::read_to_string(&sig_path)
+            .with_context(|| format!("read {}", sig_path.display()))?;
+        for line in content.lines() {
+            if let Some((hash, sig)) = line.trim().split_once(' ') {
+                signatures.insert(hash.to_string(), sig.to_string());
+            }
+        }
+    }
+
+    let file = log_path.file_name().map(|n| n.to_string_lossy().to_string());
+    let mut check = SignatureCheck {
+        started,
+        ..Default::default()
+    };
+    let mut index = 0usize;
+    for_each_positioned(log_path, 0, 0, |positioned| {
+        index += 1;
+        check.last = Some((index, positioned.line, positioned.offset));
+        let issue = |kind, detail: &str| ChainIssue {
+            file: file.clone(),
+            index,
+            line: positioned.line,
+            offset: positioned.offset,
+            kind,
+            expected: None,
+            actual: None,
+            detail: Some(detail.to_string()),
+        };
+        let hash = positioned.entry.entry_hash.as_deref();
+        let Some((hash, encoded)) = hash.and_then(|h| Some((h, signatures.get(h)?))) else {
+            if !check.started {
+                check.predating += 1;
+            } else if hash.is_none() {
+                check
+                    .issues
+                    .push(issue(ChainIssueKind::SignatureMissing, "entry has no entry_hash"));
+            } else {
+                check
+                    .issues
+                    .push(issue(ChainIssueKind::SignatureMissing, "no signature in .sig file"));
+            }
+            return Ok(());
+        };
+        check.started = true;
+        let hash_matches = compute_entry_hash(&positioned.entry)
+            .is_ok_and(|computed| ct_eq(computed.as_bytes(), hash.as_bytes()));
+        let verified = STANDARD
+            .decode(encoded)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .is_some_and(|sig| key.verify_strict(hash.as_bytes(), &sig).is_ok());
+        if !hash_matches {
+            check.issues.push(issue(
+                ChainIssueKind::SignatureInvalid,
+                "entry does not match its signed entry_hash",
+            ));
+        } else if verified {
+            check.valid += 1;
+        } else {
+            check
+                .issues
+                .push(issue(ChainIssueKind::SignatureInvalid, "signature does not verify"));
+        }
+        Ok(())
+    })?;
+    Ok(check)
 }
 
 /// Compute canonical hash for an entry (excludes entry_hash field).
@@ -193,9 +387,21 @@ fn canonicalize_value(value: &serde_json::Value) -> serde_json::Value {
 fn append_line(audit_path: &std::path::Path, line: &str) -> Result<()> {
     let mut file = OpenOptions::new()
         .create(true)
+        .read(true)
         .append(true)
         .open(audit_path)
         .with_context(|| format!("open audit log {}", audit_path.display()))?;
+
+    // Terminate a partial line left by a crash so the new entry stays parseable.
+    let len = file.metadata().context("stat audit log")?.len();
+    if len > 0 {
+        let mut last = [0u8; 1];
+        file.seek(SeekFrom::Start(len - 1)).context("seek audit log")?;
+        file.read_exact(&mut last).context("read audit log")?;
+        if last[0] != b'\n' {
+            writeln!(file).context("terminate partial audit line")?;
+        }
+    }
     writeln!(file, "{}", line).context("write audit entry")?;
 
     #[cfg(unix)]
@@ -208,6 +414,15 @@ fn append_line(audit_path: &std::path::Path, line: &str) -> Result<()> {
     Ok(())
 }
 
+/// Hash the next entry must chain from: the last entry of `audit.log`, or of
+/// `audit.log.1` when the live log was just rotated and is still empty.
+fn chain_tip(paths: &VaultPaths) -> Result<Option<String>> {
+    match last_line_hash(&audit_log_path(paths))? {
+        Some(hash) => Ok(Some(hash)),
+        None => last_line_hash(&rotated_log_path(paths, 1)),
+    }
+}
+
 fn last_line_hash(path: &std::path::Path) -> Result<Option<String>> {
     if !path.exists() {
         return Ok(None);
@@ -225,6 +440,7 @@ fn last_line_hash(path: &std::path::Path) -> Result<Option<String>> {
     const CHUNK: u64 = 8192;
     let mut offset = len;
     let mut buf = Vec::new();
+    let mut unparseable = 0usize;
 
     while offset > 0 {
         let read_size = std::cmp::min(CHUNK, offset);
@@ -236,116 +452,853 @@ fn last_line_hash(path: &std::path::Path) -> Result<Option<String>> {
             .with_context(|| format!("read {}", path.display()))?;
         buf.splice(0..0, tmp);
 
-        if buf.contains(&b'\n') || offset == 0 {
-            for line in buf.split(|b| *b == b'\n').rev() {
-                if line.iter().all(|b| b.is_ascii_whitespace()) {
-                    continue;
-                }
-                // Try to extract entry_hash from the JSON
-                if let Ok(entry) = serde_json::from_slice::<AuditEntry>(line) {
-                    if let Some(hash) = entry.entry_hash {
-                        return Ok(Some(hash));
-                    }
-                }
-                // Fallback: raw SHA-256 of the line (v1 compatibility)
-                let hash = Sha256::digest(line);
-                return Ok(Some(format!("{:064x}", hash)));
+        // Bytes before the first newline may belong to a line that starts in
+        // an earlier chunk; only scan them once the file start is reached.
+        let complete_from = if offset == 0 {
+            0
+        } else {
+            match buf.iter().position(|b| *b == b'\n') {
+                Some(pos) => pos + 1,
+                None => continue,
             }
-            return Ok(None);
+        };
+
+        for line in buf[complete_from..].split(|b| *b == b'\n').rev() {
+            if line.iter().all(|b| b.is_ascii_whitespace()) {
+                continue;
+            }
+            // A line that is not a valid entry is most likely a partial write
+            // from a crash; chaining from it would poison the next entry.
+            let Ok(entry) = serde_json::from_slice::<AuditEntry>(line) else {
+                unparseable += 1;
+                continue;
+            };
+            warn_unparseable_tail(unparseable);
+            if let Some(hash) = entry.entry_hash {
+                return Ok(Some(hash));
+            }
+            // Fallback: raw SHA-256 of the line (v1 compatibility)
+            let hash = Sha256::digest(line);
+            return Ok(Some(format!("{:064x}", hash)));
         }
+        buf.truncate(complete_from);
     }
 
+    warn_unparseable_tail(unparseable);
     Ok(None)
 }
 
+fn warn_unparseable_tail(count: usize) {
+    if count > 0 {
+        eprintln!(
+            "warning: skipped {} unparseable trailing audit line(s) (partial write?); chaining from the last valid entry",
+            count
+        );
+    }
+}
+
 /// Read audit entries from the log file.
+///
+/// With `limit`, only the last `limit` entries are kept in memory while the
+/// log is streamed. Use [`for_each_entry`] to process large logs entirely.
 pub fn read_log(paths: &VaultPaths, limit: Option<usize>) -> Result<Vec<AuditEntry>> {
-    let audit_path = paths.root.join("audit.log");
-    if !audit_path.exists() {
-        return Ok(Vec::new());
+    match limit {
+        Some(limit) => {
+            let mut ring = VecDeque::with_capacity(limit.min(1024));
+            for_each_entry(paths, |entry| {
+                if limit > 0 {
+                    if ring.len() == limit {
+                        ring.pop_front();
+                    }
+                    ring.push_back(entry);
+                }
+                Ok(())
+            })?;
+            Ok(ring.into())
+        }
+        None => {
+            let mut entries = Vec::new();
+            for_each_entry(paths, |entry| {
+                entries.push(entry);
+                Ok(())
+            })?;
+            Ok(entries)
+        }
     }
+}
 
-    let file = fs::File::open(&audit_path)
-        .with_context(|| format!("open audit log {}", audit_path.display()))?;
-    let reader = BufReader::new(file);
-    let mut entries = Vec::new();
+/// Stream every audit entry, in order, to `f` without loading the log into
+/// memory. Malformed lines are skipped with a warning.
+pub fn for_each_entry(
+    paths: &VaultPaths,
+    mut f: impl FnMut(AuditEntry) -> Result<()>,
+) -> Result<()> {
+    for_each_positioned(&audit_log_path(paths), 0, 0, |positioned| f(positioned.entry))
+}
+
+/// Kind of integrity failure found by [`verify_chain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainIssueKind {
+    PrevHashMismatch,
+    EntryHashMismatch,
+    HashError,
+    CheckpointMismatch,
+    SignatureMissing,
+    SignatureInvalid,
+}
+
+/// A single integrity failure, located in the raw log file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainIssue {
+    /// Log file name, set when verifying across rotated files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// 1-based index among the parsed entries.
+    pub index: usize,
+    /// 1-based line number in the log file.
+    pub line: usize,
+    /// Byte offset of the start of the line in the log file.
+    pub offset: u64,
+    pub kind: ChainIssueKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl std::fmt::Display for ChainIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file)?;
+        }
+        write!(
+            f,
+            "entry {} (line {}, offset {}): ",
+            self.index, self.line, self.offset
+        )?;
+        match self.kind {
+            ChainIssueKind::PrevHashMismatch => write!(
+                f,
+                "prev_hash mismatch (expected {:?}, got {:?})",
+                self.expected, self.actual
+            ),
+            ChainIssueKind::EntryHashMismatch => write!(f, "entry_hash mismatch (tampered?)"),
+            ChainIssueKind::HashError => write!(
+                f,
+                "cannot compute hash: {}",
+                self.detail.as_deref().unwrap_or("unknown error")
+            ),
+            ChainIssueKind::CheckpointMismatch => write!(
+                f,
+                "log no longer matches checkpoint (expected {}, got {}); truncated?",
+                self.expected.as_deref().unwrap_or("?"),
+                self.actual.as_deref().unwrap_or("?")
+            ),
+            ChainIssueKind::SignatureMissing | ChainIssueKind::SignatureInvalid => write!(
+                f,
+                "{}",
+                self.detail.as_deref().unwrap_or("signature check failed")
+            ),
+        }
+    }
+}
+
+/// Result of verifying the audit chain.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChainReport {
+    /// Entries in the log, including those covered by a checkpoint.
+    pub total: usize,
+    /// Entries skipped because a checkpoint already covered them.
+    pub skipped: usize,
+    /// Entries with a valid Ed25519 signature (only when a public key was given).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signatures_verified: Option<usize>,
+    /// Unsigned entries older than the first signed one (only when a public
+    /// key was given); they predate signing and are not checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signatures_predating: Option<usize>,
+    /// Per-file results, oldest first (only with [`verify_chain_all`]).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<ChainSegment>,
+    pub issues: Vec<ChainIssue>,
+    /// Checkpoint to persist after a clean verification.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<AuditCheckpoint>,
+}
+
+/// One log file covered by [`verify_chain_all`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainSegment {
+    pub file: String,
+    pub entries: usize,
+    pub issues: usize,
+}
+
+/// A parsed log entry and where it sits in the raw file.
+struct PositionedEntry {
+    line: usize,
+    offset: u64,
+    /// Offset just past the entry's newline; `None` for an unterminated final line.
+    end: Option<u64>,
+    entry: AuditEntry,
+}
+
+/// Stream entries starting at `offset` (which is at line `line_no + 1`),
+/// together with their 1-based line number and byte offset, to `f`.
+fn for_each_positioned(
+    path: &std::path::Path,
+    mut offset: u64,
+    mut line_no: usize,
+    mut f: impl FnMut(PositionedEntry) -> Result<()>,
+) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let mut file = fs::File::open(path)
+        .with_context(|| format!("open audit log {}", path.display()))?;
+    file.seek(SeekFrom::Start(offset))
+        .with_context(|| format!("seek {}", path.display()))?;
+    let mut reader = BufReader::new(file);
     let mut malformed = 0usize;
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        let n = reader
+            .read_until(b'\n', &mut buf)
+            .context("read audit log line")?;
+        if n == 0 {
+            break;
+        }
+        line_no += 1;
+        let start = offset;
+        offset += n as u64;
 
-    for line in reader.lines() {
-        let line = line.context("read audit log line")?;
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
+        let line = buf.trim_ascii();
+        if line.is_empty() {
             continue;
         }
-        match serde_json::from_str::<AuditEntry>(trimmed) {
-            Ok(entry) => entries.push(entry),
-            Err(_) => {
-                malformed += 1;
-            }
+        match serde_json::from_slice::<AuditEntry>(line) {
+            Ok(entry) => f(PositionedEntry {
+                line: line_no,
+                offset: start,
+                end: buf.ends_with(b"\n").then_some(offset),
+                entry,
+            })?,
+            Err(_) => malformed += 1,
         }
     }
 
     if malformed > 0 {
         eprintln!("warning: {} malformed audit entries skipped", malformed);
     }
+    Ok(())
+}
+
+/// Persisted position up to which the audit chain has been verified.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditCheckpoint {
+    /// Number of entries verified.
+    pub index: usize,
+    /// Number of lines consumed, including blank and malformed ones.
+    pub line: usize,
+    /// Byte offset just past the last verified line.
+    pub offset: u64,
+    /// Chain hash of the last verified entry.
+    pub entry_hash: String,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Return the path to the audit checkpoint file.
+pub fn checkpoint_path(paths: &VaultPaths) -> std::path::PathBuf {
+    paths.root.join("audit.checkpoint")
+}
+
+/// Load the verification checkpoint, if one exists.
+pub fn read_checkpoint(paths: &VaultPaths) -> Result<Option<AuditCheckpoint>> {
+    let path = checkpoint_path(paths);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("read audit checkpoint {}", path.display()))?;
+    let checkpoint = serde_json::from_str(&content)
+        .with_context(|| format!("parse audit checkpoint {}", path.display()))?;
+    Ok(Some(checkpoint))
+}
+
+/// Atomically persist a verification checkpoint.
+pub fn write_checkpoint(paths: &VaultPaths, checkpoint: &AuditCheckpoint) -> Result<()> {
+    let json = serde_json::to_string_pretty(checkpoint).context("serialize audit checkpoint")?;
+    write_state_file(paths, &checkpoint_path(paths), &json, "audit checkpoint")
+}
+
+/// Return the path to the `audit log --new` bookmark file.
+pub fn seen_path(paths: &VaultPaths) -> std::path::PathBuf {
+    paths.root.join("audit.seen")
+}
+
+/// Load the last-seen entry hash, if a bookmark exists.
+pub fn read_seen(paths: &VaultPaths) -> Result<Option<String>> {
+    let path = seen_path(paths);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("read audit bookmark {}", path.display()))?;
+    let hash = content.trim();
+    Ok((!hash.is_empty()).then(|| hash.to_string()))
+}
+
+/// Atomically persist the last-seen entry hash.
+pub fn write_seen(paths: &VaultPaths, entry_hash: &str) -> Result<()> {
+    write_state_file(paths, &seen_path(paths), &format!("{}\n", entry_hash), "audit bookmark")
+}
+
+fn write_state_file(
+    paths: &VaultPaths,
+    path: &std::path::Path,
+    content: &str,
+    what: &str,
+) -> Result<()> {
+    let mut tmp = vault_fs::secure_tempfile(&paths.root, ".tmp", "")
+        .with_context(|| format!("create temp {}", what))?;
+    tmp.write_all(content.as_bytes())
+        .with_context(|| format!("write {}", what))?;
+    tmp.flush().with_context(|| format!("flush {}", what))?;
+
+    #[cfg(unix)]
+    {
+        let perm = fs::Permissions::from_mode(constants::AUDIT_LOG_MODE);
+        tmp.as_file()
+            .set_permissions(perm)
+            .with_context(|| format!("set permissions on {}", what))?;
+    }
+
+    tmp.persist(path)
+        .map_err(|err| anyhow::anyhow!("persist {}: {}", what, err))?;
+    Ok(())
+}
+
+/// Entries appended after a bookmark (see [`entries_since`]).
+#[derive(Debug, Default)]
+pub struct NewEntries {
+    pub entries: Vec<AuditEntry>,
+    /// A bookmark was given but its hash is no longer in `audit.log` (e.g.
+    /// after rotation); `entries` then holds the whole log.
+    pub bookmark_missing: bool,
+}
+
+/// Return the entries after the one whose hash is `bookmark`. Without a
+/// bookmark, or when it is not found, every entry is returned.
+pub fn entries_since(paths: &VaultPaths, bookmark: Option<&str>) -> Result<NewEntries> {
+    let mut entries = Vec::new();
+    let mut found = false;
+    for_each_entry(paths, |entry| {
+        if bookmark.is_some() && entry.entry_hash.as_deref() == bookmark {
+            entries.clear();
+            found = true;
+        } else {
+            entries.push(entry);
+        }
+        Ok(())
+    })?;
+    Ok(NewEntries {
+        entries,
+        bookmark_missing: bookmark.is_some() && !found,
+    })
+}
+
+/// Verify the integrity of the whole audit chain.
+pub fn verify_chain(paths: &VaultPaths) -> Result<ChainReport> {
+    verify_chain_from(paths, None)
+}
+
+/// Verify the audit chain, starting after `checkpoint` when given.
+///
+/// Entries before the checkpoint are not re-read; the first entry after it
+/// must chain from the stored hash. The log is streamed: only the previous
+/// entry's hash is kept, so memory use does not grow with the log.
+pub fn verify_chain_from(
+    paths: &VaultPaths,
+    checkpoint: Option<&AuditCheckpoint>,
+) -> Result<ChainReport> {
+    let (report, _) = verify_file(&audit_log_path(paths), checkpoint, None, None)?;
+    Ok(report)
+}
+
+/// Verify every rotated file (`audit.log.N` ... `audit.log.1`) and then
+/// `audit.log` as one chain: the first entry of each file must carry the
+/// last hash of the file before it.
+pub fn verify_chain_all(paths: &VaultPaths) -> Result<ChainReport> {
+    let mut files = rotated_log_paths(paths);
+    files.reverse();
+    files.push(audit_log_path(paths));
 
-    if let Some(limit) = limit {
-        if entries.len() > limit {
-            entries = entries.split_off(entries.len() - limit);
+    let mut report = ChainReport::default();
+    let mut prev_hash: Option<String> = None;
+    for path in files {
+        let label = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (file_report, tip) = verify_file(&path, None, prev_hash.clone(), Some(&label))?;
+        report.total += file_report.total;
+        report.segments.push(ChainSegment {
+            file: label,
+            entries: file_report.total,
+            issues: file_report.issues.len(),
+        });
+        report.issues.extend(file_report.issues);
+        report.checkpoint = file_report.checkpoint;
+        if tip.is_some() {
+            prev_hash = tip;
         }
     }
+    if !report.issues.is_empty() {
+        report.checkpoint = None;
+    }
+    Ok(report)
+}
 
-    Ok(entries)
+/// Check Ed25519 signatures for the files covered by `report` (all rotated
+/// files when `all_files`, else `audit.log`) and fold the results into it.
+/// Any signature failure drops the checkpoint, like a chain failure, and so
+/// does a log in which no entry is signed at all.
+pub fn verify_report_signatures(
+    paths: &VaultPaths,
+    report: &mut ChainReport,
+    key: &VerifyingKey,
+    all_files: bool,
+) -> Result<()> {
+    let mut files = if all_files {
+        let mut files = rotated_log_paths(paths);
+        files.reverse();
+        files
+    } else {
+        Vec::new()
+    };
+    files.push(audit_log_path(paths));
+
+    let mut verified = 0usize;
+    let mut predating = 0usize;
+    let mut started = false;
+    let mut newest = None;
+    for path in files {
+        let mut check = verify_signatures(&path, key, started)?;
+        verified += check.valid;
+        predating += check.predating;
+        started = check.started;
+        let label = path.file_name().map(|n| n.to_string_lossy().to_string());
+        if let Some((index, line, offset)) = check.last {
+            newest = Some((label.clone(), index, line, offset));
+        }
+        if !started && path == audit_log_path(paths) {
+            // Nothing signed anywhere: report it on the newest entry.
+            if let Some((file, index, line, offset)) = newest.take() {
+                check.issues.push(ChainIssue {
+                    file,
+                    index,
+                    line,
+                    offset,
+                    kind: ChainIssueKind::SignatureMissing,
+                    expected: None,
+                    actual: None,
+                    detail: Some("no entry in the log is signed".to_string()),
+                });
+            }
+        }
+        if let Some(segment) = report
+            .segments
+            .iter_mut()
+            .find(|s| Some(&s.file) == label.as_ref())
+        {
+            segment.issues += check.issues.len();
+        }
+        report.issues.extend(check.issues);
+    }
+    report.signatures_verified = Some(verified);
+    report.signatures_predating = Some(predating);
+    if !report.issues.is_empty() {
+        report.checkpoint = None;
+    }
+    Ok(())
 }
 
-/// Verify the integrity of the audit chain. Returns (total, errors).
-pub fn verify_chain(paths: &VaultPaths) -> Result<(usize, Vec<String>)> {
-    let entries = read_log(paths, None)?;
-    let mut errors = Vec::new();
-    let mut prev_entry_hash: Option<String> = None;
+/// Stream-verify one log file. `prev_hash` is the tip of the previous
+/// (older) file when verifying across rotations; a checkpoint overrides it.
+/// Returns the report and the hash of the file's last entry.
+fn verify_file(
+    audit_path: &std::path::Path,
+    checkpoint: Option<&AuditCheckpoint>,
+    prev_hash: Option<String>,
+    file: Option<&str>,
+) -> Result<(ChainReport, Option<String>)> {
+    let (skipped, start_line, start_offset, mut prev_entry_hash) = match checkpoint {
+        Some(cp) => (cp.index, cp.line, cp.offset, Some(cp.entry_hash.clone())),
+        None => (0, 0, 0, prev_hash),
+    };
+    let file = file.map(str::to_string);
 
-    for (i, entry) in entries.iter().enumerate() {
-        // Check prev_hash chain
-        if i > 0 && entry.prev_hash != prev_entry_hash {
-            errors.push(format!(
-                "entry {}: prev_hash mismatch (expected {:?}, got {:?})",
-                i + 1,
-                prev_entry_hash,
-                entry.prev_hash
-            ));
+    if let Some(cp) = checkpoint {
+        let len = fs::metadata(audit_path).map(|m| m.len()).unwrap_or(0);
+        if len < cp.offset {
+            let report = ChainReport {
+                total: cp.index,
+                skipped,
+                issues: vec![ChainIssue {
+                    file,
+                    index: cp.index,
+                    line: cp.line,
+                    offset: len,
+                    kind: ChainIssueKind::CheckpointMismatch,
+                    expected: Some(format!("at least {} bytes", cp.offset)),
+                    actual: Some(format!("{} bytes", len)),
+                    detail: None,
+                }],
+                ..Default::default()
+            };
+            return Ok((report, None));
+        }
+    }
+
+    let mut issues = Vec::new();
+    let mut next_checkpoint = checkpoint.cloned();
+    let mut index = skipped;
+
+    for_each_positioned(audit_path, start_offset, start_line, |positioned| {
+        let PositionedEntry { line, offset, end, entry } = positioned;
+        index += 1;
+        let issue = |kind| ChainIssue {
+            file: file.clone(),
+            index,
+            line,
+            offset,
+            kind,
+            expected: None,
+            actual: None,
+            detail: None,
+        };
+
+        // Check prev_hash chain (the very first entry has nothing to chain from)
+        let chained = match (&prev_entry_hash, &entry.prev_hash) {
+            (Some(expected), Some(actual)) => ct_eq(expected.as_bytes(), actual.as_bytes()),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        if !chained {
+            issues.push(ChainIssue {
+                expected: prev_entry_hash.clone(),
+                actual: entry.prev_hash.clone(),
+                ..issue(ChainIssueKind::PrevHashMismatch)
+            });
         }
 
         // Verify entry_hash if present (v2)
         if entry.hash_version == Some(2) {
             if let Some(ref stored_hash) = entry.entry_hash {
-                match compute_entry_hash(entry) {
+                match compute_entry_hash(&entry) {
                     Ok(computed) => {
-                        if &computed != stored_hash {
-                            errors.push(format!(
-                                "entry {}: entry_hash mismatch (tampered?)",
-                                i + 1
-                            ));
+                        if !ct_eq(computed.as_bytes(), stored_hash.as_bytes()) {
+                            issues.push(issue(ChainIssueKind::EntryHashMismatch));
                         }
                     }
                     Err(e) => {
-                        errors.push(format!("entry {}: cannot compute hash: {}", i + 1, e));
+                        issues.push(ChainIssue {
+                            detail: Some(e.to_string()),
+                            ..issue(ChainIssueKind::HashError)
+                        });
                     }
                 }
             }
         }
 
         // Compute hash for next entry's prev_hash check
-        if let Some(ref hash) = entry.entry_hash {
-            prev_entry_hash = Some(hash.clone());
+        let chain_hash = if let Some(ref hash) = entry.entry_hash {
+            hash.clone()
         } else {
             // v1 entry: compute raw line hash
-            let json = serde_json::to_string(entry).unwrap_or_default();
+            let json = serde_json::to_string(&entry).unwrap_or_default();
             let hash = Sha256::digest(json.as_bytes());
-            prev_entry_hash = Some(format!("{:064x}", hash));
+            format!("{:064x}", hash)
+        };
+        prev_entry_hash = Some(chain_hash.clone());
+
+        // Only checkpoint complete lines; a partial tail may still grow.
+        if let Some(end) = end {
+            next_checkpoint = Some(AuditCheckpoint {
+                index,
+                line,
+                offset: end,
+                entry_hash: chain_hash,
+                verified_at: Utc::now(),
+            });
         }
+        Ok(())
+    })?;
+
+    let report = ChainReport {
+        total: index,
+        skipped,
+        checkpoint: if issues.is_empty() { next_checkpoint } else { None },
+        issues,
+        ..Default::default()
+    };
+    Ok((report, prev_entry_hash))
+}
+
+fn rename_with_signature(from: &std::path::Path, to: &std::path::Path) -> Result<()> {
+    fs::rename(from, to)
+        .with_context(|| format!("rename {} to {}", from.display(), to.display()))?;
+    let sig = signature_path(from);
+    if sig.exists() {
+        let sig_to = signature_path(to);
+        fs::rename(&sig, &sig_to)
+            .with_context(|| format!("rename {} to {}", sig.display(), sig_to.display()))?;
     }
+    Ok(())
+}
 
-    Ok((entries.len(), errors))
+/// Path of the `n`th rotated log (`audit.log.1` is the most recent).
+pub fn rotated_log_path(paths: &VaultPaths, n: usize) -> std::path::PathBuf {
+    paths.root.join(format!("audit.log.{}", n))
+}
+
+/// Existing rotated log numbers, newest (`1`) first. Gaps are kept so the
+/// cross-file chain check can flag a missing segment.
+fn rotated_log_numbers(paths: &VaultPaths) -> Vec<usize> {
+    let mut numbers: Vec<usize> = fs::read_dir(&paths.root)
+        .map(|dir| {
+            dir.flatten()
+                .filter_map(|e| {
+                    e.file_name()
+                        .to_str()?
+                        .strip_prefix("audit.log.")?
+                        .parse::<usize>()
+                        .ok()
+                })
+                .filter(|n| *n > 0)
+                .collect()
+        })
+        .unwrap_or_default();
+    numbers.sort_unstable();
+    numbers
+}
+
+/// Existing rotated logs, newest (`audit.log.1`) first.
+pub fn rotated_log_paths(paths: &VaultPaths) -> Vec<std::path::PathBuf> {
+    rotated_log_numbers(paths)
+        .into_iter()
+        .map(|n| rotated_log_path(paths, n))
+        .collect()
+}
+
+/// Rotate `audit.log` to `audit.log.1` when it is larger than `max_size`
+/// bytes, shifting older files up and keeping at most `keep` of them.
+///
+/// The chain continues across files: the next entry written to the fresh
+/// `audit.log` chains from the last hash of `audit.log.1` (see
+/// `chain_tip`). The verification checkpoint is removed because its offsets
+/// refer to the rotated file. Returns the rotated path, if any.
+pub fn rotate_log(paths: &VaultPaths, max_size: u64, keep: usize) -> Result<Option<std::path::PathBuf>> {
+    if keep == 0 {
+        anyhow::bail!("keep must be at least 1");
+    }
+    let _lock = FileLock::exclusive(&paths.audit_lock)?;
+    let audit_path = audit_log_path(paths);
+    let len = match fs::metadata(&audit_path) {
+        Ok(m) => m.len(),
+        Err(_) => return Ok(None),
+    };
+    if len <= max_size {
+        return Ok(None);
+    }
+
+    // Drop files beyond the retention window, then shift the rest up (oldest
+    // first). Signature files travel with their log.
+    for n in rotated_log_numbers(paths).into_iter().rev() {
+        let from = rotated_log_path(paths, n);
+        if n >= keep {
+            for path in [signature_path(&from), from] {
+                if path.exists() {
+                    fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+                }
+            }
+            continue;
+        }
+        rename_with_signature(&from, &rotated_log_path(paths, n + 1))?;
+    }
+    let rotated = rotated_log_path(paths, 1);
+    rename_with_signature(&audit_path, &rotated)?;
+
+    let checkpoint = checkpoint_path(paths);
+    if checkpoint.exists() {
+        fs::remove_file(&checkpoint)
+            .with_context(|| format!("remove {}", checkpoint.display()))?;
+    }
+    Ok(Some(rotated))
+}
+
+/// Incremental reader for `tail -f` style following of the audit log.
+///
+/// Reads without taking `audit_lock`; bytes after the last newline are
+/// buffered until the writer completes the line.
+pub struct AuditFollower {
+    path: std::path::PathBuf,
+    offset: u64,
+    pending: Vec<u8>,
+}
+
+impl AuditFollower {
+    /// Start following at the current end of the log.
+    pub fn from_end(paths: &VaultPaths) -> Result<Self> {
+        let path = audit_log_path(paths);
+        let offset = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Ok(Self {
+            path,
+            offset,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Return entries appended since the previous poll.
+    pub fn poll(&mut self) -> Result<Vec<AuditEntry>> {
+        let len = match fs::metadata(&self.path) {
+            Ok(m) => m.len(),
+            Err(_) => return Ok(Vec::new()),
+        };
+        if len < self.offset {
+            // Truncated or replaced: start over from the beginning.
+            self.offset = 0;
+            self.pending.clear();
+        }
+        if len == self.offset {
+            return Ok(Vec::new());
+        }
+
+        let mut file = fs::File::open(&self.path)
+            .with_context(|| format!("open audit log {}", self.path.display()))?;
+        file.seek(SeekFrom::Start(self.offset))
+            .with_context(|| format!("seek {}", self.path.display()))?;
+        let mut chunk = Vec::new();
+        file.take(len - self.offset)
+            .read_to_end(&mut chunk)
+            .with_context(|| format!("read {}", self.path.display()))?;
+        self.offset += chunk.len() as u64;
+        self.pending.extend_from_slice(&chunk);
+
+        let mut entries = Vec::new();
+        while let Some(pos) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=pos).collect();
+            let line = &line[..line.len() - 1];
+            if line.iter().all(|b| b.is_ascii_whitespace()) {
+                continue;
+            }
+            match serde_json::from_slice::<AuditEntry>(line) {
+                Ok(entry) => entries.push(entry),
+                Err(_) => eprintln!("warning: malformed audit entry skipped"),
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Count successful entries with one of `actions` at or after `since`, for
+/// each of `credentials`. Entries that recorded a failure are not counted.
+/// Entries covering several credentials (`get-many`) list them
+/// comma-separated and count for each.
+pub fn count_recent<'a>(
+    entries: impl IntoIterator<Item = &'a AuditEntry>,
+    actions: &[&str],
+    credentials: &[&str],
+    since: DateTime<Utc>,
+) -> BTreeMap<String, usize> {
+    let mut counts = credentials.iter().map(|c| (c.to_string(), 0)).collect();
+    for entry in entries {
+        tally_recent(&mut counts, entry, actions, since);
+    }
+    counts
+}
+
+/// [`count_recent`] over the active log, in a single streaming pass.
+pub fn count_recent_in_log(
+    paths: &VaultPaths,
+    actions: &[&str],
+    credentials: &[&str],
+    since: DateTime<Utc>,
+) -> Result<BTreeMap<String, usize>> {
+    let mut counts = credentials.iter().map(|c| (c.to_string(), 0)).collect();
+    for_each_entry(paths, |entry| {
+        tally_recent(&mut counts, &entry, actions, since);
+        Ok(())
+    })?;
+    Ok(counts)
+}
+
+fn tally_recent(
+    counts: &mut BTreeMap<String, usize>,
+    entry: &AuditEntry,
+    actions: &[&str],
+    since: DateTime<Utc>,
+) {
+    if entry.timestamp < since
+        || !actions.contains(&entry.action.as_str())
+        || entry.result.as_ref().is_some_and(|r| !r.success)
+    {
+        return;
+    }
+    for credential in entry.credential.split(',') {
+        if let Some(count) = counts.get_mut(credential) {
+            *count += 1;
+        }
+    }
+}
+
+/// Aggregate counts over a set of audit entries.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuditStats {
+    pub total: usize,
+    pub failed: usize,
+    pub first: Option<DateTime<Utc>>,
+    pub last: Option<DateTime<Utc>>,
+    pub by_action: BTreeMap<String, usize>,
+    pub by_actor: BTreeMap<String, usize>,
+    pub by_credential: BTreeMap<String, usize>,
+}
+
+impl AuditStats {
+    /// Fold one entry into the counts (for streaming with [`for_each_entry`]).
+    pub fn add(&mut self, entry: &AuditEntry) {
+        self.total += 1;
+        *self.by_action.entry(entry.action.clone()).or_default() += 1;
+        *self.by_actor.entry(entry.actor.clone()).or_default() += 1;
+        // `get-many` entries list several credentials, comma-separated.
+        for credential in entry.credential.split(',') {
+            *self.by_credential.entry(credential.to_string()).or_default() += 1;
+        }
+        if entry.result.as_ref().is_some_and(|r| !r.success) {
+            self.failed += 1;
+        }
+        if self.first.is_none_or(|t| entry.timestamp < t) {
+            self.first = Some(entry.timestamp);
+        }
+        if self.last.is_none_or(|t| entry.timestamp > t) {
+            self.last = Some(entry.timestamp);
+        }
+    }
+}
+
+/// Summarize audit entries per action, actor, and credential.
+pub fn compute_stats(entries: &[AuditEntry]) -> AuditStats {
+    let mut stats = AuditStats::default();
+    for entry in entries {
+        stats.add(entry);
+    }
+    stats
 }
 
 /// Return the path to the audit log file.
@@ -392,7 +1345,7 @@ mod tests {
     #[test]
     fn test_log_and_read_roundtrip() {
         let (_dir, paths) = test_paths();
-        log_action(&paths, "create", "test_cred", "tester").unwrap();
+        log_action(&paths, "create", "test_cred", "tester", None).unwrap();
         let entries = read_log(&paths, None).unwrap();
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].action, "create");
@@ -404,10 +1357,209 @@ mod tests {
     fn test_read_log_with_limit() {
         let (_dir, paths) = test_paths();
         for i in 0..5 {
-            log_action(&paths, &format!("action_{}", i), "cred", "tester").unwrap();
+            log_action(&paths, &format!("action_{}", i), "cred", "tester", None).unwrap();
         }
         let entries = read_log(&paths, Some(2)).unwrap();
         assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "action_3");
+        assert_eq!(entries[1].action, "action_4");
+        assert!(read_log(&paths, Some(0)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_for_each_entry_streams_in_order() {
+        let (_dir, paths) = test_paths();
+        for i in 0..3 {
+            log_action(&paths, &format!("action_{}", i), "cred", "tester", None).unwrap();
+        }
+        let mut actions = Vec::new();
+        for_each_entry(&paths, |entry| {
+            actions.push(entry.action);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(actions, vec!["action_0", "action_1", "action_2"]);
+
+        let mut stats = AuditStats::default();
+        for_each_entry(&paths, |entry| {
+            stats.add(&entry);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(stats.total, 3);
+
+        let err = for_each_entry(&paths, |_| anyhow::bail!("stop")).unwrap_err();
+        assert_eq!(err.to_string(), "stop");
+    }
+
+    #[test]
+    fn test_rotate_keeps_chain_across_files() {
+        let (_dir, paths) = test_paths();
+        for i in 0..3 {
+            log_action(&paths, &format!("action_{}", i), "cred", "tester", None).unwrap();
+        }
+        let tail = read_log(&paths, Some(1)).unwrap()[0].entry_hash.clone();
+
+        assert!(rotate_log(&paths, u64::MAX, 2).unwrap().is_none());
+        let rotated = rotate_log(&paths, 0, 2).unwrap().unwrap();
+        assert_eq!(rotated, rotated_log_path(&paths, 1));
+        assert!(!audit_log_path(&paths).exists());
+
+        log_action(&paths, "after", "cred", "tester", None).unwrap();
+        assert_eq!(read_log(&paths, None).unwrap()[0].prev_hash, tail);
+
+        let report = verify_chain_all(&paths).unwrap();
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        assert_eq!(report.total, 4);
+        let files: Vec<_> = report.segments.iter().map(|s| (s.file.as_str(), s.entries)).collect();
+        assert_eq!(files, vec![("audit.log.1", 3), ("audit.log", 1)]);
+
+        // Retention: a third rotation with keep=2 drops the oldest file.
+        rotate_log(&paths, 0, 2).unwrap();
+        log_action(&paths, "later", "cred", "tester", None).unwrap();
+        rotate_log(&paths, 0, 2).unwrap();
+        log_action(&paths, "latest", "cred", "tester", None).unwrap();
+        assert_eq!(rotated_log_paths(&paths).len(), 2);
+        assert!(verify_chain_all(&paths).unwrap().issues.is_empty());
+    }
+
+    #[test]
+    fn test_verify_all_detects_missing_rotated_file() {
+        let (_dir, paths) = test_paths();
+        log_action(&paths, "one", "cred", "tester", None).unwrap();
+        rotate_log(&paths, 0, 5).unwrap();
+        log_action(&paths, "two", "cred", "tester", None).unwrap();
+        rotate_log(&paths, 0, 5).unwrap();
+        log_action(&paths, "three", "cred", "tester", None).unwrap();
+
+        // Drop the middle file and close the gap: the chain must break.
+        fs::rename(rotated_log_path(&paths, 2), rotated_log_path(&paths, 1)).unwrap();
+        let report = verify_chain_all(&paths).unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].kind, ChainIssueKind::PrevHashMismatch);
+        assert_eq!(report.issues[0].file.as_deref(), Some("audit.log"));
+    }
+
+    #[test]
+    fn test_verify_all_reports_segment_gap() {
+        let (_dir, paths) = test_paths();
+        for action in ["one", "two", "three"] {
+            log_action(&paths, action, "cred", "tester", None).unwrap();
+            rotate_log(&paths, 0, 5).unwrap();
+        }
+        log_action(&paths, "four", "cred", "tester", None).unwrap();
+        fs::remove_file(rotated_log_path(&paths, 2)).unwrap();
+
+        let report = verify_chain_all(&paths).unwrap();
+        let files: Vec<_> = report.segments.iter().map(|s| s.file.as_str()).collect();
+        assert_eq!(files, vec!["audit.log.3", "audit.log.1", "audit.log"]);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].file.as_deref(), Some("audit.log.1"));
+    }
+
+    #[test]
+    fn test_entries_since_bookmark() {
+        let (_dir, paths) = test_paths();
+        for action in ["one", "two", "three"] {
+            log_action(&paths, action, "cred", "tester", None).unwrap();
+        }
+        assert_eq!(read_seen(&paths).unwrap(), None);
+        assert_eq!(entries_since(&paths, None).unwrap().entries.len(), 3);
+
+        let second = read_log(&paths, None).unwrap()[1].entry_hash.clone().unwrap();
+        write_seen(&paths, &second).unwrap();
+        assert_eq!(read_seen(&paths).unwrap().as_deref(), Some(second.as_str()));
+        let new = entries_since(&paths, Some(&second)).unwrap();
+        assert!(!new.bookmark_missing);
+        assert_eq!(new.entries.len(), 1);
+        assert_eq!(new.entries[0].action, "three");
+
+        // After rotation the bookmark is gone: everything is returned.
+        rotate_log(&paths, 0, 5).unwrap();
+        log_action(&paths, "four", "cred", "tester", None).unwrap();
+        let new = entries_since(&paths, Some(&second)).unwrap();
+        assert!(new.bookmark_missing);
+        assert_eq!(new.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_signed_entries_verify_and_detect_tamper() {
+        let (dir, paths) = test_paths();
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let key_path = dir.path().join("audit.key");
+        fs::write(&key_path, STANDARD.encode(key.to_bytes())).unwrap();
+        fs::write(
+            &paths.vault_toml,
+            format!("[audit]\nsigning_key_path = {:?}\n", key_path.display().to_string()),
+        )
+        .unwrap();
+        let key = signing_key_from_config(&paths).unwrap().unwrap();
+
+        log_action(&paths, "one", "cred", "tester", Some(&key)).unwrap();
+        rotate_log(&paths, 0, 5).unwrap();
+        log_action(&paths, "two", "cred", "tester", Some(&key)).unwrap();
+        assert!(signature_path(&rotated_log_path(&paths, 1)).exists());
+
+        let public = key.verifying_key();
+        let mut report = verify_chain_all(&paths).unwrap();
+        verify_report_signatures(&paths, &mut report, &public, true).unwrap();
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        assert_eq!(report.signatures_verified, Some(2));
+        assert_eq!(report.signatures_predating, Some(0));
+
+        // A different key must not verify.
+        let other = SigningKey::from_bytes(&[9u8; 32]).verifying_key();
+        let check = verify_signatures(&audit_log_path(&paths), &other, true).unwrap();
+        assert_eq!(check.valid, 0);
+        assert_eq!(check.issues[0].kind, ChainIssueKind::SignatureInvalid);
+
+        // Editing a signed entry but keeping its entry_hash must not verify.
+        let log = audit_log_path(&paths);
+        let original = fs::read_to_string(&log).unwrap();
+        fs::write(&log, original.replace("\"two\"", "\"tw0\"")).unwrap();
+        let check = verify_signatures(&log, &public, true).unwrap();
+        assert_eq!(check.valid, 0);
+        assert_eq!(check.issues[0].kind, ChainIssueKind::SignatureInvalid);
+        fs::write(&log, original).unwrap();
+
+        // An entry appended without a signature is reported as missing.
+        log_action(&paths, "unsigned", "cred", "tester", None).unwrap();
+        let check = verify_signatures(&audit_log_path(&paths), &public, true).unwrap();
+        assert_eq!(check.valid, 1);
+        assert_eq!(check.issues.len(), 1);
+        assert_eq!(check.issues[0].kind, ChainIssueKind::SignatureMissing);
+        assert_eq!(check.issues[0].index, 2);
+    }
+
+    #[test]
+    fn test_entries_before_signing_are_reported_separately() {
+        let (_dir, paths) = test_paths();
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let public = key.verifying_key();
+
+        log_action(&paths, "old", "cred", "tester", None).unwrap();
+        log_action(&paths, "older", "cred", "tester", None).unwrap();
+        let mut report = verify_chain_from(&paths, None).unwrap();
+        verify_report_signatures(&paths, &mut report, &public, false).unwrap();
+        assert_eq!(report.issues.len(), 1, "nothing signed at all");
+        assert_eq!(report.issues[0].index, 2);
+
+        log_action(&paths, "signed", "cred", "tester", Some(&key)).unwrap();
+        let mut report = verify_chain_from(&paths, None).unwrap();
+        verify_report_signatures(&paths, &mut report, &public, false).unwrap();
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        assert_eq!(report.signatures_verified, Some(1));
+        assert_eq!(report.signatures_predating, Some(2));
+    }
+
+    #[test]
+    fn test_signature_is_written_before_the_entry() {
+        let (_dir, paths) = test_paths();
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        // A directory where the .sig file should be makes signing fail.
+        fs::create_dir(signature_path(&audit_log_path(&paths))).unwrap();
+        assert!(log_action(&paths, "one", "cred", "tester", Some(&key)).is_err());
+        assert!(read_log(&paths, None).unwrap().is_empty());
     }
 
     #[test]
@@ -432,19 +1584,19 @@ mod tests {
     #[test]
     fn test_verify_chain_ok() {
         let (_dir, paths) = test_paths();
-        log_action(&paths, "create", "cred1", "tester").unwrap();
-        log_action(&paths, "rotate", "cred1", "tester").unwrap();
-        log_action(&paths, "delete", "cred1", "tester").unwrap();
-        let (total, errors) = verify_chain(&paths).unwrap();
-        assert_eq!(total, 3);
-        assert!(errors.is_empty(), "errors: {:?}", errors);
+        log_action(&paths, "create", "cred1", "tester", None).unwrap();
+        log_action(&paths, "rotate", "cred1", "tester", None).unwrap();
+        log_action(&paths, "delete", "cred1", "tester", None).unwrap();
+        let report = verify_chain(&paths).unwrap();
+        assert_eq!(report.total, 3);
+        assert!(report.issues.is_empty(), "issues: {:?}", report.issues);
     }
 
     #[test]
     fn test_verify_chain_detects_tamper() {
         let (_dir, paths) = test_paths();
-        log_action(&paths, "create", "cred1", "tester").unwrap();
-        log_action(&paths, "rotate", "cred1", "tester").unwrap();
+        log_action(&paths, "create", "cred1", "tester", None).unwrap();
+        log_action(&paths, "rotate", "cred1", "tester", None).unwrap();
 
         // Tamper with the log
         let audit_path = paths.root.join("audit.log");
@@ -452,9 +1604,102 @@ mod tests {
         let tampered = content.replace("rotate", "DELETE_TAMPERED");
         fs::write(&audit_path, tampered).unwrap();
 
-        let (total, errors) = verify_chain(&paths).unwrap();
-        assert_eq!(total, 2);
-        assert!(!errors.is_empty());
+        let report = verify_chain(&paths).unwrap();
+        assert_eq!(report.total, 2);
+        assert!(!report.issues.is_empty());
+    }
+
+    #[test]
+    fn test_verify_chain_locates_tampered_line() {
+        let (_dir, paths) = test_paths();
+        log_action(&paths, "create", "cred1", "tester", None).unwrap();
+        log_action(&paths, "rotate", "cred1", "tester", None).unwrap();
+        log_action(&paths, "delete", "cred1", "tester", None).unwrap();
+
+        let audit_path = audit_log_path(&paths);
+        let content = fs::read_to_string(&audit_path).unwrap();
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+        lines[1] = lines[1].replace("tester", "mallory");
+        // A blank line shifts line numbers but not entry indices.
+        let tampered = format!("{}\n\n{}\n{}\n", lines[0], lines[1], lines[2]);
+        fs::write(&audit_path, &tampered).unwrap();
+
+        let report = verify_chain(&paths).unwrap();
+        assert_eq!(report.issues.len(), 1);
+        let issue = &report.issues[0];
+        assert_eq!(issue.kind, ChainIssueKind::EntryHashMismatch);
+        assert_eq!(issue.index, 2);
+        assert_eq!(issue.line, 3);
+        assert_eq!(issue.offset as usize, lines[0].len() + 2);
+        assert!(tampered[issue.offset as usize..].starts_with(&lines[1]));
+        assert!(issue.to_string().starts_with("entry 2 (line 3, offset "));
+    }
+
+    #[test]
+    fn test_incremental_verify_skips_checkpointed_entries() {
+        let (_dir, paths) = test_paths();
+        log_action(&paths, "create", "cred1", "tester", None).unwrap();
+        log_action(&paths, "rotate", "cred1", "tester", None).unwrap();
+        let report = verify_chain(&paths).unwrap();
+        let checkpoint = report.checkpoint.expect("clean run yields a checkpoint");
+        assert_eq!(checkpoint.index, 2);
+        write_checkpoint(&paths, &checkpoint).unwrap();
+
+        log_action(&paths, "delete", "cred1", "tester", None).unwrap();
+
+        // Tamper with an entry covered by the checkpoint.
+        let audit_path = audit_log_path(&paths);
+        let content = fs::read_to_string(&audit_path).unwrap();
+        fs::write(&audit_path, content.replacen("create", "CREATE", 1)).unwrap();
+
+        let checkpoint = read_checkpoint(&paths).unwrap().unwrap();
+        let incremental = verify_chain_from(&paths, Some(&checkpoint)).unwrap();
+        assert_eq!(incremental.total, 3);
+        assert_eq!(incremental.skipped, 2);
+        assert!(incremental.issues.is_empty(), "issues: {:?}", incremental.issues);
+        assert_eq!(incremental.checkpoint.unwrap().index, 3);
+
+        let full = verify_chain(&paths).unwrap();
+        assert_eq!(full.issues.len(), 1);
+        assert_eq!(full.issues[0].index, 1);
+        assert!(full.checkpoint.is_none());
+    }
+
+    #[test]
+    fn test_incremental_verify_detects_truncation() {
+        let (_dir, paths) = test_paths();
+        log_action(&paths, "create", "cred1", "tester", None).unwrap();
+        log_action(&paths, "rotate", "cred1", "tester", None).unwrap();
+        let checkpoint = verify_chain(&paths).unwrap().checkpoint.unwrap();
+
+        let audit_path = audit_log_path(&paths);
+        let content = fs::read_to_string(&audit_path).unwrap();
+        let first = content.lines().next().unwrap();
+        fs::write(&audit_path, format!("{}\n", first)).unwrap();
+
+        let report = verify_chain_from(&paths, Some(&checkpoint)).unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].kind, ChainIssueKind::CheckpointMismatch);
+    }
+
+    #[test]
+    fn test_truncated_final_line_does_not_poison_chain() {
+        let (_dir, paths) = test_paths();
+        log_action(&paths, "create", "cred1", "tester", None).unwrap();
+        let audit_path = audit_log_path(&paths);
+        let content = fs::read_to_string(&audit_path).unwrap();
+        let valid_hash = read_log(&paths, None).unwrap()[0].entry_hash.clone();
+
+        // Simulate a crash halfway through writing the next entry.
+        let partial = &content[..content.len() / 2];
+        fs::write(&audit_path, format!("{}{}", content, partial)).unwrap();
+        assert_eq!(last_line_hash(&audit_path).unwrap(), valid_hash);
+
+        log_action(&paths, "rotate", "cred1", "tester", None).unwrap();
+        let entries = read_log(&paths, None).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].prev_hash, valid_hash);
+        assert!(verify_chain(&paths).unwrap().issues.is_empty());
     }
 
     #[test]
@@ -463,6 +1708,7 @@ mod tests {
         let ctx = AuditContext {
             action: "rotate".to_string(),
             credential: "db_pass".to_string(),
+            actor: None,
             reason: Some("scheduled rotation".to_string()),
             output_mode: None,
             target_path: None,
@@ -470,7 +1716,7 @@ mod tests {
             tpm2_pcrs: None,
             service_context: Some("myservice".to_string()),
         };
-        log_with_result(&paths, ctx, true, None).unwrap();
+        log_with_result(&paths, ctx, true, None, None).unwrap();
         let entries = read_log(&paths, None).unwrap();
         assert_eq!(entries.len(), 1);
         assert_eq!(entries[0].reason, Some("scheduled rotation".to_string()));
@@ -478,6 +1724,115 @@ mod tests {
         assert_eq!(entries[0].with_key, Some("host+tpm2".to_string()));
     }
 
+    #[test]
+    fn test_follower_buffers_partial_lines() {
+        let (_dir, paths) = test_paths();
+        log_action(&paths, "create", "old", "tester", None).unwrap();
+        let mut follower = AuditFollower::from_end(&paths).unwrap();
+        assert!(follower.poll().unwrap().is_empty());
+
+        log_action(&paths, "rotate", "db", "tester", None).unwrap();
+        let line = fs::read_to_string(audit_log_path(&paths))
+            .unwrap()
+            .lines()
+            .last()
+            .unwrap()
+            .to_string();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(audit_log_path(&paths))
+            .unwrap();
+        let (head, tail) = line.split_at(line.len() / 2);
+        file.write_all(head.as_bytes()).unwrap();
+
+        let entries = follower.poll().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].credential, "db");
+        assert!(follower.poll().unwrap().is_empty());
+
+        writeln!(file, "{}", tail).unwrap();
+        let entries = follower.poll().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, "rotate");
+    }
+
+    #[test]
+    fn test_count_recent_near_and_over_limit() {
+        let now = Utc::now();
+        let entry = |action: &str, credential: &str, minutes_ago: i64, success: bool| AuditEntry {
+            timestamp: now - chrono::Duration::minutes(minutes_ago),
+            action: action.into(),
+            actor: "tester".into(),
+            credential: credential.into(),
+            metadata_only: true,
+            prev_hash: None,
+            reason: None,
+            result: Some(AuditResult {
+                success,
+                error: None,
+            }),
+            output_mode: None,
+            target_path: None,
+            with_key: None,
+            tpm2_pcrs: None,
+            service_context: None,
+            entry_hash: None,
+            hash_version: None,
+        };
+        let since = now - chrono::Duration::hours(1);
+
+        let mut entries = vec![
+            entry("get", "db", 90, true),  // outside the window
+            entry("get", "db", 50, true),
+            entry("get", "db", 10, false), // refused, not counted
+            entry("get", "api", 5, true),  // other credential
+            entry("rotate", "db", 5, true),
+            entry("get", "db", 1, true),
+        ];
+        assert_eq!(count_recent(&entries, &["get"], &["db"], since)["db"], 2);
+
+        entries.push(entry("get", "db", 0, true));
+        assert_eq!(count_recent(&entries, &["get"], &["db"], since)["db"], 3);
+
+        entries.push(entry("get-many", "api,db", 2, true));
+        let counts = count_recent(&entries, &["get-many"], &["db", "d", "api"], since);
+        assert_eq!((counts["db"], counts["d"], counts["api"]), (1, 0, 1));
+        let counts = count_recent(&entries, &["get", "get-many"], &["db", "api"], since);
+        assert_eq!((counts["db"], counts["api"]), (4, 2));
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let (_dir, paths) = test_paths();
+        log_action(&paths, "create", "db", "alice", None).unwrap();
+        log_action(&paths, "rotate", "db", "bob", None).unwrap();
+        let ctx = AuditContext {
+            action: "rotate".to_string(),
+            credential: "api".to_string(),
+            actor: Some("ci-deploy".to_string()),
+            reason: None,
+            output_mode: None,
+            target_path: None,
+            with_key: None,
+            tpm2_pcrs: None,
+            service_context: None,
+        };
+        log_with_result(&paths, ctx, false, Some("encrypt failed".into()), None).unwrap();
+        log_action(&paths, "get-many", "db,api", "alice", None).unwrap();
+
+        let entries = read_log(&paths, None).unwrap();
+        let stats = compute_stats(&entries);
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.by_action["rotate"], 2);
+        assert_eq!(stats.by_actor["alice"], 2);
+        assert_eq!(stats.by_actor["ci-deploy"], 1);
+        assert_eq!(stats.by_credential["db"], 3);
+        assert_eq!(stats.by_credential["api"], 2);
+        assert!(!stats.by_credential.contains_key("db,api"));
+        assert!(stats.first.unwrap() <= stats.last.unwrap());
+    }
+
     #[test]
     fn test_backwards_compatible_entry() {
         // Old-format entry (no new fields) should parse fine
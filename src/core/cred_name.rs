@@ -0,0 +1,86 @@
+//! Credential name rules shared by argument parsing, service maps and
+//! decrypt.
+//!
+//! One name is used three ways: as the `.cred` file stem, as the `--name`
+//! given to `systemd-creds encrypt` (checked again on decrypt), and as the
+//! ID in `LoadCredentialEncrypted=ID:PATH`, i.e. the file the service finds
+//! at `/run/credentials/<unit>/<name>`. [`validate`] only accepts names that
+//! work for all three.
+
+use crate::constants;
+
+/// systemd credential IDs are file names, so `NAME_MAX` applies.
+pub const MAX_LEN: usize = 255;
+
+/// Check `name` against the rules; the error is a short reason.
+pub fn validate(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("name cannot be empty".into());
+    }
+    if name == "." || name.contains("..") {
+        return Err("path traversal not allowed".into());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
+    {
+        return Err("only [a-zA-Z0-9._-] allowed".into());
+    }
+    if name.len() > MAX_LEN {
+        return Err(format!("longer than {} characters", MAX_LEN));
+    }
+    Ok(())
+}
+
+/// Things about a valid name that tend to break a service at runtime.
+pub fn warnings(name: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if name.starts_with('.') {
+        warnings.push(format!(
+            "'{}' starts with '.': it is a hidden file in $CREDENTIALS_DIRECTORY",
+            name
+        ));
+    }
+    if name.starts_with('-') {
+        warnings.push(format!(
+            "'{}' starts with '-': tools reading the credential may take it for an option",
+            name
+        ));
+    }
+    if name.ends_with(constants::CRED_EXTENSION) {
+        warnings.push(format!(
+            "'{}' ends with '{}': it is stored as {}{} and services see the name with the suffix",
+            name,
+            constants::CRED_EXTENSION,
+            name,
+            constants::CRED_EXTENSION
+        ));
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("DATABASE_URL").is_ok());
+        assert!(validate("api.token-2").is_ok());
+        assert!(validate("").is_err());
+        assert!(validate(".").is_err());
+        assert!(validate("a..b").is_err());
+        assert!(validate("db:prod").is_err());
+        assert!(validate("db/prod").is_err());
+        assert!(validate(&"a".repeat(MAX_LEN)).is_ok());
+        assert!(validate(&"a".repeat(MAX_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_warnings() {
+        assert!(warnings("DATABASE_URL").is_empty());
+        assert_eq!(warnings(".hidden").len(), 1);
+        assert_eq!(warnings("-x").len(), 1);
+        assert_eq!(warnings("db.cred").len(), 1);
+    }
+}
@@ -1,6 +1,10 @@
 //! Credential file listing and discovery.
 
-use anyhow::{Context, Result};
+use crate::constants;
+use crate::core::error::{VaultError, VaultResult};
+use crate::models::credential::CredentialMeta;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
@@ -14,12 +18,15 @@ pub struct CredEntry {
 }
 
 /// List all .cred files in the credstore directory.
-pub fn list_credentials(cred_dir: &Path) -> Result<Vec<CredEntry>> {
+pub fn list_credentials(cred_dir: &Path) -> VaultResult<Vec<CredEntry>> {
     let mut entries = Vec::new();
-    let dir = fs::read_dir(cred_dir)
-        .with_context(|| format!("open credstore directory {}", cred_dir.display()))?;
+    let dir = fs::read_dir(cred_dir).map_err(|e| {
+        VaultError::io(format!("open credstore directory {}", cred_dir.display()), e)
+    })?;
     for entry in dir {
-        let entry = entry?;
+        let entry = entry.map_err(|e| {
+            VaultError::io(format!("read credstore directory {}", cred_dir.display()), e)
+        })?;
         let path = entry.path();
         if !path.is_file() {
             continue;
@@ -32,7 +39,8 @@ pub fn list_credentials(cred_dir: &Path) -> Result<Vec<CredEntry>> {
             continue;
         }
         let name = file_name.trim_end_matches(".cred").to_string();
-        let meta = fs::metadata(&path)?;
+        let meta = fs::metadata(&path)
+            .map_err(|e| VaultError::io(format!("stat {}", path.display()), e))?;
         entries.push(CredEntry {
             name,
             path,
@@ -43,3 +51,128 @@ pub fn list_credentials(cred_dir: &Path) -> Result<Vec<CredEntry>> {
     entries.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(entries)
 }
+
+/// SHA-256 (hex) of a `.cred` file's ciphertext, for `cred_sha256`.
+pub fn cred_sha256(path: &Path) -> VaultResult<String> {
+    let data = fs::read(path).map_err(|e| VaultError::io(format!("read {}", path.display()), e))?;
+    Ok(format!("{:064x}", Sha256::digest(&data)))
+}
+
+/// How a `.cred` compares with the `cred_sha256` recorded in metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Integrity {
+    Match,
+    /// No checksum recorded (written before checksums existed).
+    Unrecorded,
+    /// The file is missing or unreadable.
+    Unreadable,
+    /// Hash differs but the file is no newer than the last vault write:
+    /// corruption at rest.
+    Corrupted,
+    /// Hash differs and the file changed after the last vault write:
+    /// someone replaced it without going through the tool.
+    ModifiedOutOfBand {
+        modified: DateTime<Utc>,
+        rotated_at: DateTime<Utc>,
+    },
+}
+
+/// Check `meta.cred_sha256` against the file at `path`.
+pub fn check_integrity(meta: &CredentialMeta, path: &Path) -> Integrity {
+    let Some(expected) = &meta.cred_sha256 else {
+        return Integrity::Unrecorded;
+    };
+    let Ok(actual) = cred_sha256(path) else {
+        return Integrity::Unreadable;
+    };
+    if &actual == expected {
+        return Integrity::Match;
+    }
+    let modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(DateTime::<Utc>::from);
+    match (modified, meta.rotated_at.or(meta.created_at)) {
+        (Some(modified), Some(rotated_at)) if modified > rotated_at => {
+            Integrity::ModifiedOutOfBand {
+                modified,
+                rotated_at,
+            }
+        }
+        _ => Integrity::Corrupted,
+    }
+}
+
+/// Path of `<name>.cred` in the credstore, whether or not it exists.
+pub fn cred_path(cred_dir: &Path, name: &str) -> PathBuf {
+    cred_dir.join(format!("{}{}", name, constants::CRED_EXTENSION))
+}
+
+/// Path of the `<name>.cred.prev` backup kept by rotate.
+pub fn prev_cred_path(cred_dir: &Path, name: &str) -> PathBuf {
+    cred_dir.join(format!("{}{}.prev", name, constants::CRED_EXTENSION))
+}
+
+/// Path of an existing `<name>.cred`, or `CredentialNotFound`.
+pub fn existing_cred_path(cred_dir: &Path, name: &str) -> VaultResult<PathBuf> {
+    let path = cred_path(cred_dir, name);
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(VaultError::CredentialNotFound {
+            name: name.to_string(),
+            path,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cred_sha256() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = cred_path(dir.path(), "db");
+        fs::write(&path, "abc").unwrap();
+        assert_eq!(
+            cred_sha256(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(matches!(
+            cred_sha256(&dir.path().join("missing.cred")),
+            Err(VaultError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_integrity_tells_tampering_from_corruption() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = cred_path(dir.path(), "db");
+        fs::write(&path, "abc").unwrap();
+        let written = DateTime::<Utc>::from(fs::metadata(&path).unwrap().modified().unwrap());
+        let mut meta = CredentialMeta {
+            name: "db".into(),
+            rotated_at: Some(written),
+            ..Default::default()
+        };
+        assert_eq!(check_integrity(&meta, &path), Integrity::Unrecorded);
+        meta.cred_sha256 = Some(cred_sha256(&path).unwrap());
+        assert_eq!(check_integrity(&meta, &path), Integrity::Match);
+
+        // Same mtime as the last vault write, different bytes: bit-rot.
+        meta.cred_sha256 = Some("0".repeat(64));
+        assert_eq!(check_integrity(&meta, &path), Integrity::Corrupted);
+
+        // The file changed after the last vault write: replaced directly.
+        meta.rotated_at = Some(written - chrono::Duration::hours(1));
+        assert!(matches!(
+            check_integrity(&meta, &path),
+            Integrity::ModifiedOutOfBand { .. }
+        ));
+        assert_eq!(
+            check_integrity(&meta, &dir.path().join("gone.cred")),
+            Integrity::Unreadable
+        );
+    }
+}
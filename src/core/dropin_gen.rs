@@ -1,9 +1,16 @@
-//! Systemd drop-in generator from service map entries.
+//! Systemd drop-in generator from service map entries, and the timer/service
+//! pair for scheduled rotation.
 
 use crate::core::service_map::{self, ServiceMapEntry};
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::path::Path;
 
+/// Unit name stem for the scheduled rotation of `name`.
+pub fn rotate_unit_stem(name: &str) -> String {
+    format!("goamet-vault-rotate-{}", name)
+}
+
 /// Generate a systemd drop-in from a service map file.
 ///
 /// Convenience wrapper that parses the map file, then generates the drop-in.
@@ -23,15 +30,38 @@ pub fn generate_dropin_from_entries(
     entries: &[ServiceMapEntry],
     no_env: bool,
     hardening: bool,
+) -> String {
+    generate_dropin_with_inline(entries, &HashMap::new(), no_env, hardening)
+}
+
+/// Generate a systemd drop-in, inlining the given `.cred` contents.
+///
+/// Entries whose name has text in `inline` are emitted as
+/// `SetCredentialEncrypted=<name>:<text>`; all others keep
+/// `LoadCredentialEncrypted=` (pure function). The text is the `.cred`
+/// file as written by systemd-creds, which is already Base64; only its
+/// line breaks are stripped.
+pub fn generate_dropin_with_inline(
+    entries: &[ServiceMapEntry],
+    inline: &HashMap<String, String>,
+    no_env: bool,
+    hardening: bool,
 ) -> String {
     let mut out = String::new();
     out.push_str("[Service]\n");
     for entry in entries {
-        out.push_str(&format!(
-            "LoadCredentialEncrypted={}:{}\n",
-            entry.cred_name,
-            entry.cred_path.display()
-        ));
+        match inline.get(&entry.cred_name) {
+            Some(text) => out.push_str(&format!(
+                "SetCredentialEncrypted={}:{}\n",
+                entry.cred_name,
+                text.split_whitespace().collect::<String>()
+            )),
+            None => out.push_str(&format!(
+                "LoadCredentialEncrypted={}:{}\n",
+                entry.cred_name,
+                entry.cred_path.display()
+            )),
+        }
         if !no_env {
             if let Some(env_var) = &entry.env_var {
                 out.push_str(&format!(
@@ -57,6 +87,53 @@ pub fn generate_dropin_from_entries(
     out
 }
 
+/// Render the `.service` and `.timer` for rotating `name` on `on_calendar`
+/// (pure function). The service runs `exe` against the given root and
+/// credstore with an auto-generated secret.
+pub fn generate_rotate_units(
+    name: &str,
+    on_calendar: &str,
+    exe: &Path,
+    root: &Path,
+    credstore: &Path,
+) -> (String, String) {
+    let timer_name = format!("{}.timer", rotate_unit_stem(name));
+    let service = format!(
+        "[Unit]\n\
+         Description=Rotate goamet-vault credential {name}\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         ExecStart={} --root {} --credstore {} --non-interactive rotate {name} --auto --reason \"scheduled by {timer_name}\"\n",
+        unit_arg(&exe.display().to_string()),
+        unit_arg(&root.display().to_string()),
+        unit_arg(&credstore.display().to_string()),
+    );
+    let timer = format!(
+        "[Unit]\n\
+         Description=Scheduled rotation of goamet-vault credential {name}\n\
+         \n\
+         [Timer]\n\
+         OnCalendar={on_calendar}\n\
+         Persistent=true\n\
+         \n\
+         [Install]\n\
+         WantedBy=timers.target\n"
+    );
+    (service, timer)
+}
+
+/// Quote an `ExecStart=` argument when needed; `%` is a specifier in unit
+/// files and is doubled.
+fn unit_arg(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%");
+    if escaped.chars().any(|c| c.is_whitespace() || c == '"' || c == '\'' || c == '\\') {
+        format!("\"{}\"", escaped.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        escaped
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +156,24 @@ mod tests {
         assert!(result.contains("Environment=DB_PASS_FILE=%d/db_password"));
     }
 
+    #[test]
+    fn test_generate_rotate_units() {
+        let (service, timer) = generate_rotate_units(
+            "db",
+            "Sun *-*-* 03:00:00",
+            Path::new("/usr/local/bin/goamet-vault"),
+            Path::new("/srv/my vault"),
+            Path::new("/srv/my vault/credstore"),
+        );
+        assert!(service.contains(
+            "ExecStart=/usr/local/bin/goamet-vault --root \"/srv/my vault\" --credstore \"/srv/my vault/credstore\" --non-interactive rotate db --auto --reason \"scheduled by goamet-vault-rotate-db.timer\"\n"
+        ));
+        assert!(service.contains("Type=oneshot\n"));
+        assert!(timer.contains("OnCalendar=Sun *-*-* 03:00:00\n"));
+        assert!(timer.contains("WantedBy=timers.target\n"));
+        assert_eq!(unit_arg("50%"), "50%%");
+    }
+
     #[test]
     fn test_generate_no_env() {
         let map = write_map("db_password DB_PASS_FILE\n");
@@ -118,10 +213,34 @@ mod tests {
                 env_var: Some("DB_PASS_FILE".to_string()),
                 line_number: 1,
                 is_custom_path: false,
+                origin: None,
             },
         ];
         let result = generate_dropin_from_entries(&entries, false, false);
         assert!(result.contains("LoadCredentialEncrypted=db_pass:/creds/db_pass.cred"));
         assert!(result.contains("Environment=DB_PASS_FILE=%d/db_pass"));
     }
+
+    #[test]
+    fn test_generate_with_inline() {
+        let map = write_map("db_pass DB_PASS_FILE\napi_token\n");
+        let entries = service_map::parse_service_map(map.path(), Path::new("/creds")).unwrap();
+        let mut inline = HashMap::new();
+        // Real systemd-creds output: Base64 wrapped at 64 columns.
+        inline.insert(
+            "db_pass".to_string(),
+            "Whxqht+dQJax1aZeCGLxmiAAAAABAAAADAAAABAAAABEuYYGXDlo/Yz7v/8AAAAAnxuut4i6Px1Z0q7\n\
+             ttoEK66ZZCDXFAV8hZcOqMjmUane+hev1tHKd9aT1Fl46pA==\n"
+                .to_string(),
+        );
+        let result = generate_dropin_with_inline(&entries, &inline, false, false);
+        assert!(result.contains(
+            "SetCredentialEncrypted=db_pass:\
+             Whxqht+dQJax1aZeCGLxmiAAAAABAAAADAAAABAAAABEuYYGXDlo/Yz7v/8AAAAAnxuut4i6Px1Z0q7\
+             ttoEK66ZZCDXFAV8hZcOqMjmUane+hev1tHKd9aT1Fl46pA==\n"
+        ));
+        assert!(!result.contains("LoadCredentialEncrypted=db_pass"));
+        assert!(result.contains("Environment=DB_PASS_FILE=%d/db_pass"));
+        assert!(result.contains("LoadCredentialEncrypted=api_token:/creds/api_token.cred"));
+    }
 }
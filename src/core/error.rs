@@ -0,0 +1,98 @@
+//! Typed errors for callers that embed the crate as a library.
+//!
+//! Core functions whose failures callers need to tell apart return
+//! [`VaultResult`]: credstore lookups, locks, policy checks and the
+//! systemd-creds wrappers. `VaultError` implements `std::error::Error`, so
+//! the CLI keeps using `anyhow` and `?` converts at the boundary.
+//!
+//! [`crate::core::vault::Vault`] returns `anyhow::Result`, because its
+//! operations also read and write vault.toml, the audit log and the trash,
+//! whose failures are I/O or parse errors that callers only report. A
+//! missing credential, a policy refusal, a decrypt failure or a timed-out
+//! command still reaches the caller as a `VaultError`:
+//! `downcast_ref::<VaultError>()` on the `anyhow::Error` recovers it.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum VaultError {
+    /// No `.cred` file for this credential.
+    CredentialNotFound { name: String, path: PathBuf },
+    /// The target credential already exists.
+    AlreadyExists { name: String, path: PathBuf },
+    /// A `[policy]` rule rejected the operation; the message names the rule.
+    PolicyViolation(String),
+    /// `systemd-creds decrypt` failed (wrong key, PCR mismatch, corrupt file).
+    DecryptFailed { path: PathBuf, detail: String },
+    /// An external command (systemd-creds) was killed after running too long.
+    CommandTimeout { command: String, timeout: Duration },
+    /// An I/O failure, with what was being done.
+    Io {
+        context: String,
+        source: std::io::Error,
+    },
+}
+
+pub type VaultResult<T> = std::result::Result<T, VaultError>;
+
+impl VaultError {
+    pub(crate) fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        VaultError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+}
+
+impl std::fmt::Display for VaultError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultError::CredentialNotFound { name, path } => {
+                write!(f, "credential '{}' not found: {}", name, path.display())
+            }
+            VaultError::AlreadyExists { name, path } => {
+                write!(f, "credential '{}' already exists: {}", name, path.display())
+            }
+            VaultError::PolicyViolation(message) => write!(f, "{}", message),
+            VaultError::DecryptFailed { path, detail } => {
+                write!(f, "decrypt {} failed: {}", path.display(), detail.trim())
+            }
+            VaultError::CommandTimeout { command, timeout } => write!(
+                f,
+                "{} did not finish within {}s and was killed (see --command-timeout)",
+                command,
+                timeout.as_secs()
+            ),
+            VaultError::Io { context, source } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for VaultError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VaultError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anyhow_roundtrip_keeps_variant() {
+        let err: anyhow::Error = VaultError::CredentialNotFound {
+            name: "db".to_string(),
+            path: PathBuf::from("/c/db.cred"),
+        }
+        .into();
+        assert_eq!(err.to_string(), "credential 'db' not found: /c/db.cred");
+        assert!(matches!(
+            err.downcast_ref::<VaultError>(),
+            Some(VaultError::CredentialNotFound { .. })
+        ));
+    }
+}
@@ -1,6 +1,6 @@
 //! File-based locking using flock(2) for concurrent access protection.
 
-use anyhow::{Context, Result};
+use crate::core::error::{VaultError, VaultResult};
 use fs2::FileExt;
 use std::fs::{File, OpenOptions};
 use std::path::Path;
@@ -12,37 +12,36 @@ pub struct FileLock {
 
 impl FileLock {
     /// Acquire an exclusive lock, blocking until available.
-    pub fn exclusive(path: &Path) -> Result<Self> {
-        let file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(false)
-            .open(path)
-            .with_context(|| format!("open lock file {}", path.display()))?;
+    pub fn exclusive(path: &Path) -> VaultResult<Self> {
+        let file = open_lock_file(path)?;
         file.lock_exclusive()
-            .with_context(|| format!("acquire lock {}", path.display()))?;
+            .map_err(|e| VaultError::io(format!("acquire lock {}", path.display()), e))?;
         Ok(Self { _file: file })
     }
 
     /// Try to acquire an exclusive lock without blocking.
     /// Returns `Ok(Some(lock))` if acquired, `Ok(None)` if already held.
-    pub fn try_exclusive(path: &Path) -> Result<Option<Self>> {
-        let file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(false)
-            .open(path)
-            .with_context(|| format!("open lock file {}", path.display()))?;
+    pub fn try_exclusive(path: &Path) -> VaultResult<Option<Self>> {
+        let file = open_lock_file(path)?;
         match file.try_lock_exclusive() {
             Ok(()) => Ok(Some(Self { _file: file })),
             Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(None),
             // fs2 on Linux may return Other instead of WouldBlock
             Err(ref e) if e.raw_os_error() == Some(11) => Ok(None), // EAGAIN
-            Err(e) => Err(e).with_context(|| format!("try lock {}", path.display())),
+            Err(e) => Err(VaultError::io(format!("try lock {}", path.display()), e)),
         }
     }
 }
 
+fn open_lock_file(path: &Path) -> VaultResult<File> {
+    OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|e| VaultError::io(format!("open lock file {}", path.display()), e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
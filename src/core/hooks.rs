@@ -0,0 +1,140 @@
+//! Post-change hooks from the `[hooks]` section of vault.toml.
+//!
+//! A hook is a command template run after a successful create, rotate or
+//! delete, e.g. to make a service reload. The template is split on
+//! whitespace and `{name}` / `{service}` are substituted per argument; no
+//! shell is involved, so quoting and pipes need a wrapper script. Hooks get
+//! the credential name and service, never the secret.
+
+use crate::models::vault_config::HooksSection;
+use anyhow::{bail, Context, Result};
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PostCreate,
+    PostRotate,
+    PostDelete,
+}
+
+impl HookEvent {
+    /// The `[hooks]` key, also recorded in the audit entry.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::PostCreate => "post_create",
+            HookEvent::PostRotate => "post_rotate",
+            HookEvent::PostDelete => "post_delete",
+        }
+    }
+
+    pub fn template(self, hooks: &HooksSection) -> Option<&str> {
+        match self {
+            HookEvent::PostCreate => hooks.post_create.as_deref(),
+            HookEvent::PostRotate => hooks.post_rotate.as_deref(),
+            HookEvent::PostDelete => hooks.post_delete.as_deref(),
+        }
+    }
+}
+
+/// One command to run for a hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookCommand {
+    pub argv: Vec<String>,
+    /// The service substituted for `{service}`, if the template uses it.
+    pub service: Option<String>,
+}
+
+impl HookCommand {
+    pub fn display(&self) -> String {
+        self.argv.join(" ")
+    }
+}
+
+/// Expand `template` for credential `name`. A template using `{service}`
+/// runs once per linked service, and not at all (with a warning) when there
+/// are none; any other template runs once.
+pub fn expand(template: &str, name: &str, services: &[String]) -> Vec<HookCommand> {
+    let words: Vec<&str> = template.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let substitute = |service: &str| -> Vec<String> {
+        words
+            .iter()
+            .map(|w| w.replace("{name}", name).replace("{service}", service))
+            .collect()
+    };
+    if !template.contains("{service}") {
+        return vec![HookCommand {
+            argv: substitute(""),
+            service: None,
+        }];
+    }
+    if services.is_empty() {
+        eprintln!(
+            "warning: hook '{}' uses {{service}} but '{}' has no linked services; not run",
+            template, name
+        );
+        return Vec::new();
+    }
+    services
+        .iter()
+        .map(|svc| HookCommand {
+            argv: substitute(svc),
+            service: Some(svc.clone()),
+        })
+        .collect()
+}
+
+/// Run `cmd` and fail unless it exits 0. The event, credential and service
+/// are also passed as `GOAMET_HOOK_EVENT`, `GOAMET_CREDENTIAL` and
+/// `GOAMET_SERVICE`.
+pub fn run(event: HookEvent, name: &str, cmd: &HookCommand) -> Result<()> {
+    let Some((program, args)) = cmd.argv.split_first() else {
+        bail!("empty {} hook", event.as_str());
+    };
+    let status = Command::new(program)
+        .args(args)
+        .env("GOAMET_HOOK_EVENT", event.as_str())
+        .env("GOAMET_CREDENTIAL", name)
+        .env("GOAMET_SERVICE", cmd.service.as_deref().unwrap_or(""))
+        .stdin(Stdio::null())
+        .status()
+        .with_context(|| format!("run {} hook '{}'", event.as_str(), program))?;
+    if !status.success() {
+        match status.code() {
+            Some(code) => bail!("{} hook '{}' exited with status {}", event.as_str(), program, code),
+            None => bail!("{} hook '{}' was killed by a signal", event.as_str(), program),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_substitutes_per_argument() {
+        let services = vec!["api".to_string(), "worker".to_string()];
+        let cmds = expand("systemctl try-reload-or-restart {service}.service", "db", &services);
+        assert_eq!(cmds.len(), 2);
+        assert_eq!(cmds[1].argv, vec!["systemctl", "try-reload-or-restart", "worker.service"]);
+        assert_eq!(cmds[1].service.as_deref(), Some("worker"));
+
+        let cmds = expand("/usr/local/bin/notify --cred={name}", "db; rm -rf /", &services);
+        assert_eq!(cmds.len(), 1);
+        assert_eq!(cmds[0].argv, vec!["/usr/local/bin/notify", "--cred=db; rm -rf /"]);
+        assert!(expand("  ", "db", &services).is_empty());
+        assert!(expand("systemctl restart {service}.service", "db", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_run_reports_exit_status() {
+        let ok = expand("true {name}", "db", &[]);
+        assert!(run(HookEvent::PostCreate, "db", &ok[0]).is_ok());
+        let fail = expand("false", "db", &[]);
+        let err = run(HookEvent::PostRotate, "db", &fail[0]).unwrap_err();
+        assert_eq!(err.to_string(), "post_rotate hook 'false' exited with status 1");
+    }
+}
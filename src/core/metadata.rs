@@ -2,28 +2,296 @@
 
 use crate::constants;
 use crate::models::credential::CredentialMeta;
-use crate::models::vault_config::{VaultFile, VaultSection};
-use anyhow::{Context, Result};
+use crate::models::policy::{PolicyProfiles, PolicySection};
+use crate::models::vault_config::{AuditSection, HooksSection, VaultFile, VaultSection};
+use crate::util::fs as vault_fs;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
 use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
 /// Load vault metadata from a TOML file.
+///
+/// Older schema versions are migrated in memory; the upgraded shape is
+/// written back on the next `save`. Duplicate credential names are rejected.
 pub fn load(path: &Path) -> Result<VaultFile> {
+    let vault = load_unvalidated(path)?;
+    let dups = duplicate_names(&vault.credentials);
+    if !dups.is_empty() {
+        bail!(
+            "duplicate credential names in {}: {} (run `init --repair` to keep the newest of each)",
+            path.display(),
+            dups.join(", ")
+        );
+    }
+    Ok(vault)
+}
+
+/// Load vault metadata, collapsing duplicate credential names instead of
+/// rejecting them. Returns the repaired file and the names that were
+/// deduplicated; the caller decides whether to `save`.
+pub fn load_repair(path: &Path) -> Result<(VaultFile, Vec<String>)> {
+    let mut vault = load_unvalidated(path)?;
+    let dups = dedup_credentials(&mut vault.credentials);
+    Ok((vault, dups))
+}
+
+fn load_unvalidated(path: &Path) -> Result<VaultFile> {
     if !path.exists() {
-        return Ok(VaultFile::default());
+        let mut vault = VaultFile::default();
+        migrate(&mut vault)?;
+        return Ok(vault);
     }
     let content = fs::read_to_string(path)
         .with_context(|| format!("read vault metadata {}", path.display()))?;
     let mut vault: VaultFile = toml::from_str(&content)
         .with_context(|| format!("parse vault metadata {}", path.display()))?;
+    migrate(&mut vault)
+        .with_context(|| format!("migrate vault metadata {}", path.display()))?;
+    Ok(vault)
+}
+
+/// Load only the `[policy]` and `[profiles.*]` sections from an explicit
+/// config file.
+///
+/// Unlike the best-effort load from vault.toml, a missing or unparseable
+/// file is an error. Other sections in the file are ignored.
+pub fn load_policy_file(path: &Path) -> Result<(PolicySection, PolicyProfiles)> {
+    #[derive(serde::Deserialize)]
+    struct PolicyFile {
+        #[serde(default)]
+        policy: PolicySection,
+        #[serde(default)]
+        profiles: PolicyProfiles,
+    }
+
+    if !path.is_file() {
+        bail!("config file not found: {}", path.display());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("read config {}", path.display()))?;
+    let file: PolicyFile = toml::from_str(&content)
+        .with_context(|| format!("parse config {}", path.display()))?;
+    Ok((file.policy, file.profiles))
+}
+
+/// Overlay profile `name` on the base policy. Keys the profile does not set
+/// keep their `[policy]` value; unknown keys are rejected so typos surface.
+pub fn apply_profile(
+    base: &PolicySection,
+    profiles: &PolicyProfiles,
+    name: &str,
+) -> Result<PolicySection> {
+    let Some(overrides) = profiles.get(name) else {
+        let available: Vec<&str> = profiles.keys().map(String::as_str).collect();
+        bail!(
+            "profile '{}' not found (available: {})",
+            name,
+            if available.is_empty() { "none".to_string() } else { available.join(", ") }
+        );
+    };
+    let mut table = toml::Table::try_from(base).context("serialize [policy]")?;
+    for (key, value) in overrides {
+        table.insert(key.clone(), value.clone());
+    }
+    let merged: PolicySection = table
+        .try_into()
+        .with_context(|| format!("invalid [profiles.{}]", name))?;
+
+    // Known keys set by the profile survive a round trip; unknown ones are dropped.
+    let known = toml::Table::try_from(&merged).context("serialize merged policy")?;
+    let unknown: Vec<&str> = overrides
+        .keys()
+        .filter(|k| !known.contains_key(*k))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        bail!("unknown key(s) in [profiles.{}]: {}", name, unknown.join(", "));
+    }
+    Ok(merged)
+}
+
+/// Prefix of environment variables that override one `[policy]` key each,
+/// e.g. `GOAMET_VAULT_POLICY_MIN_AUTO_SECRET_LENGTH=32`.
+pub const POLICY_ENV_PREFIX: &str = "GOAMET_VAULT_POLICY_";
+
+/// Overlay `GOAMET_VAULT_POLICY_<KEY>` variables from `vars` on the policy.
+/// A value is read as TOML (`32`, `true`, `["a", "b"]`), else as a plain
+/// string, else as a comma-separated list. Empty values are ignored; unknown
+/// keys and values of the wrong type are errors.
+pub fn apply_env_overrides(
+    base: &PolicySection,
+    vars: &[(String, String)],
+) -> Result<PolicySection> {
+    let mut table = toml::Table::try_from(base).context("serialize [policy]")?;
+    for (var, raw) in vars {
+        let Some(key) = var.strip_prefix(POLICY_ENV_PREFIX) else {
+            continue;
+        };
+        if raw.trim().is_empty() {
+            continue;
+        }
+        let key = key.to_ascii_lowercase();
+        let mut first_err = None;
+        let mut applied = false;
+        for value in env_value_candidates(raw) {
+            let mut candidate = table.clone();
+            candidate.insert(key.clone(), value);
+            match candidate.clone().try_into::<PolicySection>() {
+                Ok(merged) => {
+                    // Known keys survive a round trip; unknown ones are dropped.
+                    let known = toml::Table::try_from(&merged).context("serialize merged policy")?;
+                    if !known.contains_key(&key) {
+                        bail!("{}: '{}' is not a [policy] key", var, key);
+                    }
+                    table = candidate;
+                    applied = true;
+                    break;
+                }
+                Err(e) => {
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+        if !applied {
+            let reason = first_err.map(|e| e.to_string()).unwrap_or_default();
+            let reason = reason.lines().next().unwrap_or_default();
+            bail!("{}: invalid value '{}': {}", var, raw, reason);
+        }
+    }
+    table.try_into().context("apply policy environment overrides")
+}
+
+/// Interpretations of an override value, tried in order.
+fn env_value_candidates(raw: &str) -> Vec<toml::Value> {
+    let mut values = Vec::new();
+    if let Ok(mut parsed) = format!("v = {}", raw).parse::<toml::Table>() {
+        if let Some(value) = parsed.remove("v") {
+            values.push(value);
+        }
+    }
+    values.push(toml::Value::String(raw.to_string()));
+    let items = raw
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| toml::Value::String(s.to_string()))
+        .collect();
+    values.push(toml::Value::Array(items));
+    values
+}
+
+/// Load only the `[audit]` section of vault.toml (default when missing).
+pub fn load_audit_section(path: &Path) -> Result<AuditSection> {
+    #[derive(serde::Deserialize)]
+    struct AuditFile {
+        #[serde(default)]
+        audit: AuditSection,
+    }
+
+    if !path.exists() {
+        return Ok(AuditSection::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("read vault metadata {}", path.display()))?;
+    let file: AuditFile = toml::from_str(&content)
+        .with_context(|| format!("parse vault metadata {}", path.display()))?;
+    Ok(file.audit)
+}
+
+/// Load only the `[hooks]` section of vault.toml (default when missing).
+pub fn load_hooks_section(path: &Path) -> Result<HooksSection> {
+    #[derive(serde::Deserialize)]
+    struct HooksFile {
+        #[serde(default)]
+        hooks: HooksSection,
+    }
+
+    if !path.exists() {
+        return Ok(HooksSection::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("read vault metadata {}", path.display()))?;
+    let file: HooksFile = toml::from_str(&content)
+        .with_context(|| format!("parse vault metadata {}", path.display()))?;
+    Ok(file.hooks)
+}
+
+/// Names that occur more than once, in first-seen order.
+fn duplicate_names(creds: &[CredentialMeta]) -> Vec<String> {
+    let mut dups: Vec<String> = Vec::new();
+    for (i, cred) in creds.iter().enumerate() {
+        if creds[..i].iter().any(|c| c.name == cred.name) && !dups.contains(&cred.name) {
+            dups.push(cred.name.clone());
+        }
+    }
+    dups
+}
+
+/// Collapse duplicate names, keeping the most recently rotated entry.
+/// Returns the names that had duplicates.
+fn dedup_credentials(creds: &mut Vec<CredentialMeta>) -> Vec<String> {
+    let dups = duplicate_names(creds);
+    let mut kept: Vec<CredentialMeta> = Vec::with_capacity(creds.len());
+    for cred in creds.drain(..) {
+        match kept.iter_mut().find(|c| c.name == cred.name) {
+            Some(existing) => {
+                if last_changed(&cred) > last_changed(existing) {
+                    *existing = cred;
+                }
+            }
+            None => kept.push(cred),
+        }
+    }
+    kept.sort_by(|a, b| a.name.cmp(&b.name));
+    *creds = kept;
+    dups
+}
+
+/// A schema migration upgrading a `VaultFile` by exactly one version.
+type Migration = fn(&mut VaultFile);
+
+/// Ordered migrations: entry `i` upgrades version `i + 1` to `i + 2`.
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+/// Apply all pending schema migrations and bump `vault.version`.
+///
+/// Returns whether any migration ran. Files written by a newer release
+/// are rejected rather than silently downgraded.
+pub fn migrate(vault: &mut VaultFile) -> Result<bool> {
     if vault.vault.version == 0 {
         vault.vault.version = 1;
     }
-    Ok(vault)
+    if vault.vault.version > constants::VAULT_SCHEMA_VERSION {
+        bail!(
+            "vault.toml schema version {} is newer than supported version {}",
+            vault.vault.version,
+            constants::VAULT_SCHEMA_VERSION
+        );
+    }
+
+    let mut changed = false;
+    while vault.vault.version < constants::VAULT_SCHEMA_VERSION {
+        let step = MIGRATIONS[(vault.vault.version - 1) as usize];
+        step(vault);
+        vault.vault.version += 1;
+        changed = true;
+    }
+    Ok(changed)
+}
+
+/// v1 -> v2: introduce `expires_at` (v1 had no expiry, so it stays `None`
+/// via serde default). Duplicate credential names are not merged here:
+/// `load` rejects them as for any v2 file, and `init --repair` collapses
+/// them explicitly.
+fn migrate_v1_to_v2(_vault: &mut VaultFile) {}
+
+fn last_changed(cred: &CredentialMeta) -> Option<DateTime<Utc>> {
+    cred.rotated_at.or(cred.created_at)
 }
 
 /// Save vault metadata to a TOML file atomically.
@@ -33,10 +301,9 @@ pub fn save(path: &Path, vault: &VaultFile) -> Result<()> {
         fs::create_dir_all(parent)
             .with_context(|| format!("create dir {}", parent.display()))?;
     }
-    let mut tmp = tempfile::NamedTempFile::new_in(
-        path.parent().unwrap_or_else(|| Path::new(".")),
-    )
-    .context("create temp vault metadata")?;
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut tmp =
+        vault_fs::secure_tempfile(dir, ".tmp", "").context("create temp vault metadata")?;
     tmp.write_all(content.as_bytes())
         .context("write vault metadata")?;
     tmp.flush().context("flush vault metadata")?;
@@ -82,11 +349,151 @@ pub fn ensure_vault_section(vault: &mut VaultFile, credstore_path: Option<String
     }
 }
 
+/// Path of the metadata snapshot kept alongside `<name>.cred.prev`.
+pub fn prev_snapshot_path(credstore: &Path, name: &str) -> PathBuf {
+    credstore.join(format!("{}.meta.prev.json", name))
+}
+
+/// Persist the pre-rotation metadata so a rollback can restore it.
+pub fn write_prev_snapshot(path: &Path, meta: &CredentialMeta) -> Result<()> {
+    let json = serde_json::to_string_pretty(meta).context("serialize metadata snapshot")?;
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut tmp = vault_fs::secure_tempfile(dir, ".tmp", "")
+        .context("create temp metadata snapshot")?;
+    tmp.write_all(json.as_bytes())
+        .context("write metadata snapshot")?;
+    tmp.flush().context("flush metadata snapshot")?;
+
+    #[cfg(unix)]
+    {
+        let perm = fs::Permissions::from_mode(constants::CRED_FILE_MODE);
+        tmp.as_file()
+            .set_permissions(perm)
+            .context("set permissions on metadata snapshot")?;
+    }
+
+    tmp.persist(path)
+        .map_err(|err| anyhow::anyhow!("persist metadata snapshot: {}", err))?;
+    Ok(())
+}
+
+/// Read a metadata snapshot, if one exists.
+pub fn read_prev_snapshot(path: &Path) -> Result<Option<CredentialMeta>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("read metadata snapshot {}", path.display()))?;
+    let meta = serde_json::from_str(&content)
+        .with_context(|| format!("parse metadata snapshot {}", path.display()))?;
+    Ok(Some(meta))
+}
+
+/// Restore metadata for a rolled-back credential.
+///
+/// With a snapshot the pre-rotation entry is restored verbatim. Without one
+/// the old rotation date is unknown (the `.prev` file's mtime is when it was
+/// copied, not when it was written), so `rotated_at` is cleared and age
+/// checks fall back to `created_at`; `cred_sha256` is set to `restored_sha256`
+/// (the restored file's checksum) and the unknown PCR binding is cleared.
+/// Returns `false` when no metadata could be restored exactly.
+pub fn rollback_credential(
+    vault: &mut VaultFile,
+    name: &str,
+    snapshot: Option<CredentialMeta>,
+    restored_sha256: Option<String>,
+) -> bool {
+    if let Some(meta) = snapshot {
+        upsert_credential(vault, meta);
+        return true;
+    }
+    if let Some(existing) = vault.credentials.iter_mut().find(|c| c.name == name) {
+        existing.rotated_at = None;
+        existing.cred_sha256 = restored_sha256;
+        existing.tpm2_pcrs = None;
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_load_policy_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("policy.toml");
+        assert!(load_policy_file(&path).is_err());
+
+        fs::write(
+            &path,
+            "[vault]\nversion = 2\n\n[policy]\nservice_allowlist = [\"api\"]\nmax_gets_per_hour = 3\n",
+        )
+        .unwrap();
+        let (policy, _) = load_policy_file(&path).unwrap();
+        assert_eq!(policy.service_allowlist, vec!["api".to_string()]);
+        assert_eq!(policy.max_gets_per_hour, Some(3));
+
+        fs::write(&path, "[policy]\nmax_gets_per_hour = \"many\"\n").unwrap();
+        let err = format!("{:#}", load_policy_file(&path).unwrap_err());
+        assert!(err.contains("parse config"), "{}", err);
+    }
+
+    #[test]
+    fn test_apply_profile_overrides_policy() {
+        let vault: VaultFile = toml::from_str(
+            "[policy]\nservice_allowlist = [\"api\"]\nmax_gets_per_hour = 10\n\n\
+             [profiles.prod]\nmax_gets_per_hour = 2\nrequire_reason_for_rotate = true\n\n\
+             [profiles.typo]\nmax_get_per_hour = 2\n",
+        )
+        .unwrap();
+
+        let prod = apply_profile(&vault.policy, &vault.profiles, "prod").unwrap();
+        assert_eq!(prod.max_gets_per_hour, Some(2));
+        assert!(prod.require_reason_for_rotate);
+        assert_eq!(prod.service_allowlist, vec!["api".to_string()]);
+        assert!(prod.require_reason_for_stdout);
+
+        let err = apply_profile(&vault.policy, &vault.profiles, "staging").unwrap_err();
+        assert!(err.to_string().contains("available: prod, typo"), "{}", err);
+        let err = apply_profile(&vault.policy, &vault.profiles, "typo").unwrap_err();
+        assert!(err.to_string().contains("max_get_per_hour"), "{}", err);
+    }
+
+    #[test]
+    fn test_apply_env_overrides() {
+        let var = |key: &str, value: &str| {
+            (format!("{}{}", POLICY_ENV_PREFIX, key), value.to_string())
+        };
+        let base = PolicySection {
+            max_gets_per_hour: Some(10),
+            ..Default::default()
+        };
+        let vars = vec![
+            var("MIN_AUTO_SECRET_LENGTH", "32"),
+            var("FORBID_HOST_ONLY_WHEN_TPM2", "true"),
+            var("DEFAULT_TPM2_PCRS", "7"),
+            var("SERVICE_ALLOWLIST", "api-*, worker"),
+            var("REQUIRED_TAGS", "[\"env:\"]"),
+            var("MAX_GETS_PER_HOUR", ""),
+            ("GOAMET_VAULT_ROOT".to_string(), "/srv/vault".to_string()),
+        ];
+        let policy = apply_env_overrides(&base, &vars).unwrap();
+        assert_eq!(policy.min_auto_secret_length, Some(32));
+        assert!(policy.forbid_host_only_when_tpm2);
+        assert_eq!(policy.default_tpm2_pcrs.as_deref(), Some("7"));
+        assert_eq!(policy.service_allowlist, vec!["api-*", "worker"]);
+        assert_eq!(policy.required_tags, vec!["env:"]);
+        assert_eq!(policy.max_gets_per_hour, Some(10));
+
+        let err = apply_env_overrides(&base, &[var("REQUIRE_TPM2", "yes")]).unwrap_err();
+        let err = err.to_string();
+        assert!(err.starts_with("GOAMET_VAULT_POLICY_REQUIRE_TPM2: invalid value 'yes'"), "{}", err);
+        let err = apply_env_overrides(&base, &[var("MAX_GET_PER_HOUR", "2")]).unwrap_err();
+        assert!(err.to_string().contains("'max_get_per_hour' is not a [policy] key"), "{}", err);
+    }
+
     #[test]
     fn test_upsert_new_credential() {
         let mut vault = VaultFile::default();
@@ -161,6 +568,153 @@ mod tests {
         assert_eq!(vault.credentials[1].name, "z");
     }
 
+    #[test]
+    fn test_load_migrates_v1_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("vault.toml");
+        fs::write(
+            &path,
+            r#"
+[vault]
+version = 1
+
+[[credentials]]
+name = "db"
+description = "old"
+rotated_at = "2025-01-01T00:00:00Z"
+
+[[credentials]]
+name = "api"
+
+[[credentials]]
+name = "db"
+description = "new"
+rotated_at = "2025-06-01T00:00:00Z"
+"#,
+        )
+        .unwrap();
+
+        let err = load(&path).unwrap_err().to_string();
+        assert!(err.contains("duplicate credential names"), "{}", err);
+        assert!(err.contains("init --repair"), "{}", err);
+
+        let (loaded, dups) = load_repair(&path).unwrap();
+        assert_eq!(dups, vec!["db".to_string()]);
+        assert_eq!(loaded.vault.version, constants::VAULT_SCHEMA_VERSION);
+        assert_eq!(loaded.credentials.len(), 2);
+        assert_eq!(loaded.credentials[0].name, "api");
+        assert_eq!(loaded.credentials[1].name, "db");
+        assert_eq!(loaded.credentials[1].description, Some("new".into()));
+        assert!(loaded.credentials.iter().all(|c| c.expires_at.is_none()));
+        assert!(loaded.credentials.iter().all(|c| c.group.is_none()));
+    }
+
+    const DUPLICATED_V2: &str = r#"
+[vault]
+version = 2
+
+[[credentials]]
+name = "db"
+rotated_at = "2025-06-01T00:00:00Z"
+
+[[credentials]]
+name = "db"
+rotated_at = "2025-01-01T00:00:00Z"
+"#;
+
+    #[test]
+    fn test_load_rejects_duplicate_names() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("vault.toml");
+        fs::write(&path, DUPLICATED_V2).unwrap();
+        let err = load(&path).unwrap_err().to_string();
+        assert!(err.contains("duplicate credential names"), "{}", err);
+        assert!(err.contains("db"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_repair_keeps_newest() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("vault.toml");
+        fs::write(&path, DUPLICATED_V2).unwrap();
+        let (vault, dups) = load_repair(&path).unwrap();
+        assert_eq!(dups, vec!["db".to_string()]);
+        assert_eq!(vault.credentials.len(), 1);
+        assert_eq!(
+            vault.credentials[0].rotated_at.unwrap().to_rfc3339(),
+            "2025-06-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_migrate_is_noop_at_current_version() {
+        let mut vault = VaultFile::default();
+        assert!(migrate(&mut vault).unwrap());
+        assert!(!migrate(&mut vault).unwrap());
+        assert_eq!(vault.vault.version, constants::VAULT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_version() {
+        let mut vault = VaultFile::default();
+        vault.vault.version = constants::VAULT_SCHEMA_VERSION + 1;
+        assert!(migrate(&mut vault).is_err());
+    }
+
+    #[test]
+    fn test_rotate_then_rollback_restores_metadata() {
+        let dir = TempDir::new().unwrap();
+        let before = CredentialMeta {
+            name: "db".into(),
+            rotated_at: Some("2025-01-01T00:00:00Z".parse().unwrap()),
+            encryption_key: Some("host".into()),
+            tags: vec!["prod".into()],
+            ..Default::default()
+        };
+        let mut vault = VaultFile::default();
+        upsert_credential(&mut vault, before.clone());
+
+        // rotate: snapshot, then overwrite
+        let snap = prev_snapshot_path(dir.path(), "db");
+        write_prev_snapshot(&snap, &before).unwrap();
+        upsert_credential(
+            &mut vault,
+            CredentialMeta {
+                rotated_at: Some(Utc::now()),
+                encryption_key: Some("host+tpm2".into()),
+                ..before.clone()
+            },
+        );
+
+        // rollback
+        let snapshot = read_prev_snapshot(&snap).unwrap();
+        assert!(rollback_credential(&mut vault, "db", snapshot, None));
+        let meta = &vault.credentials[0];
+        assert_eq!(meta.rotated_at, before.rotated_at);
+        assert_eq!(meta.encryption_key, Some("host".into()));
+        assert_eq!(meta.tags, vec!["prod".to_string()]);
+    }
+
+    #[test]
+    fn test_rollback_without_snapshot_resets_rotation_fields() {
+        let mut vault = VaultFile::default();
+        upsert_credential(
+            &mut vault,
+            CredentialMeta {
+                name: "db".into(),
+                rotated_at: Some(Utc::now()),
+                cred_sha256: Some("a".repeat(64)),
+                tpm2_pcrs: Some("7+11".into()),
+                ..Default::default()
+            },
+        );
+        assert!(!rollback_credential(&mut vault, "db", None, Some("b".repeat(64))));
+        let meta = &vault.credentials[0];
+        assert_eq!(meta.rotated_at, None);
+        assert_eq!(meta.cred_sha256, Some("b".repeat(64)));
+        assert_eq!(meta.tpm2_pcrs, None);
+    }
+
     #[test]
     fn test_save_and_load_roundtrip() {
         let dir = TempDir::new().unwrap();
@@ -172,6 +726,7 @@ mod tests {
             CredentialMeta {
                 name: "test".into(),
                 description: Some("desc".into()),
+                group: Some("payments".into()),
                 ..Default::default()
             },
         );
@@ -179,5 +734,6 @@ mod tests {
         let loaded = load(&path).unwrap();
         assert_eq!(loaded.credentials.len(), 1);
         assert_eq!(loaded.credentials[0].name, "test");
+        assert_eq!(loaded.credentials[0].group, Some("payments".into()));
     }
 }
\ No newline at end of file
@@ -1,9 +1,14 @@
 //! Core business logic modules.
 
 pub mod audit_log;
+pub mod cred_name;
 pub mod credstore;
 pub mod dropin_gen;
+pub mod error;
 pub mod file_lock;
+pub mod hooks;
 pub mod metadata;
 pub mod paths;
 pub mod service_map;
+pub mod trash;
+pub mod vault;
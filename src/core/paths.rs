@@ -1,4 +1,9 @@
 //! Vault path resolution and directory structure.
+//!
+//! `VaultPaths::resolve` and `with_credstore` read the process environment
+//! and working directory and are meant for the CLI. Library users should
+//! build paths explicitly with [`VaultPaths::from_root`] or
+//! [`VaultPaths::builder`], which touch neither.
 
 use crate::constants;
 use anyhow::{Context, Result};
@@ -14,10 +19,15 @@ pub struct VaultPaths {
     pub vault_toml: PathBuf,
     pub vault_lock: PathBuf,
     pub audit_lock: PathBuf,
+    /// Deleted credentials kept for `trash restore`.
+    pub trash: PathBuf,
 }
 
 impl VaultPaths {
-    /// Resolve vault paths from CLI arg, env var, or auto-detection.
+    /// Resolve vault paths from CLI arg, env var, or auto-detection (CLI only).
+    ///
+    /// The credstore is `<root>/credstore` until [`VaultPaths::with_credstore`]
+    /// applies an override; vault.toml can only be read once the root is known.
     pub fn resolve(root_arg: Option<PathBuf>) -> Result<Self> {
         if let Some(root) = root_arg {
             return Ok(Self::from_root(root));
@@ -31,6 +41,32 @@ impl VaultPaths {
         Ok(Self::from_root(PathBuf::from(constants::DEFAULT_VAULT_ROOT)))
     }
 
+    /// Move the credstore: `--credstore` > `GOAMET_VAULT_CREDSTORE` >
+    /// `[vault] credstore_path` (`configured`, from the caller's vault.toml
+    /// load) > `<root>/credstore`.
+    pub fn with_credstore(mut self, flag: Option<PathBuf>, configured: Option<String>) -> Self {
+        let env_credstore = env::var("GOAMET_VAULT_CREDSTORE").ok();
+        if let Some(credstore) = select_credstore(&self.root, flag, env_credstore, configured) {
+            self.credstore = credstore;
+        }
+        self
+    }
+
+    /// The credstore as `[vault] credstore_path` should record it: `None`
+    /// for the default `<root>/credstore`, so only a real override is
+    /// written to vault.toml.
+    pub fn credstore_override(&self) -> Option<String> {
+        (self.credstore != self.root.join("credstore"))
+            .then(|| self.credstore.display().to_string())
+    }
+
+    /// Start from the `from_root` layout and override individual paths.
+    pub fn builder(root: impl Into<PathBuf>) -> VaultPathsBuilder {
+        VaultPathsBuilder {
+            paths: Self::from_root(root.into()),
+        }
+    }
+
     /// Create vault paths from a root directory.
     pub fn from_root(root: PathBuf) -> Self {
         let credstore = root.join("credstore");
@@ -39,6 +75,7 @@ impl VaultPaths {
         let vault_toml = root.join("vault.toml");
         let vault_lock = root.join("vault.lock");
         let audit_lock = root.join("audit.lock");
+        let trash = root.join(".trash");
         Self {
             root,
             credstore,
@@ -47,18 +84,96 @@ impl VaultPaths {
             vault_toml,
             vault_lock,
             audit_lock,
+            trash,
         }
     }
 }
 
+/// Builder for a fully specified [`VaultPaths`]; see [`VaultPaths::builder`].
+///
+/// The audit log and its rotated files always live in the root.
+#[derive(Debug, Clone)]
+pub struct VaultPathsBuilder {
+    paths: VaultPaths,
+}
+
+impl VaultPathsBuilder {
+    pub fn credstore(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.credstore = path.into();
+        self
+    }
+
+    pub fn services(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.services = path.into();
+        self
+    }
+
+    pub fn units(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.units = path.into();
+        self
+    }
+
+    pub fn vault_toml(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.vault_toml = path.into();
+        self
+    }
+
+    pub fn vault_lock(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.vault_lock = path.into();
+        self
+    }
+
+    pub fn audit_lock(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.audit_lock = path.into();
+        self
+    }
+
+    pub fn trash(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.trash = path.into();
+        self
+    }
+
+    pub fn build(self) -> VaultPaths {
+        self.paths
+    }
+}
+
+/// Pick the credstore override: flag > env > vault.toml. A relative
+/// `credstore_path` from vault.toml is taken relative to the vault root.
+fn select_credstore(
+    root: &Path,
+    flag: Option<PathBuf>,
+    env_value: Option<String>,
+    configured: Option<String>,
+) -> Option<PathBuf> {
+    let non_empty = |s: String| (!s.trim().is_empty()).then_some(s);
+    // Older releases recorded `<root>/credstore` as given on the command line,
+    // which may be relative to the old working directory; that is the default.
+    let default = root.join("credstore").display().to_string();
+    flag.filter(|p| !p.as_os_str().is_empty())
+        .or_else(|| env_value.and_then(non_empty).map(PathBuf::from))
+        .or_else(|| {
+            configured
+                .and_then(non_empty)
+                .filter(|p| *p != default)
+                .map(|p| root.join(p))
+        })
+}
+
 fn find_repo_root() -> Result<Option<PathBuf>> {
     let cwd = env::current_dir().context("resolve current directory")?;
-    for ancestor in cwd.ancestors() {
-        if looks_like_root(ancestor) {
-            return Ok(Some(ancestor.to_path_buf()));
-        }
-    }
-    Ok(None)
+    Ok(find_root_from(&cwd))
+}
+
+/// Nearest ancestor with a root marker file; failing that, the nearest one
+/// with `credstore/` and `services/`. A marker anywhere up the tree wins over
+/// the directory heuristic, so nested project trees resolve predictably.
+fn find_root_from(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(constants::ROOT_MARKER_FILE).is_file())
+        .or_else(|| start.ancestors().find(|dir| looks_like_root(dir)))
+        .map(Path::to_path_buf)
 }
 
 fn looks_like_root(path: &Path) -> bool {
@@ -86,4 +201,73 @@ mod tests {
         assert_eq!(paths.vault_lock, PathBuf::from("/test/vault.lock"));
         assert_eq!(paths.audit_lock, PathBuf::from("/test/audit.lock"));
     }
+
+    #[test]
+    fn test_builder_overrides_only_given_paths() {
+        let paths = VaultPaths::builder("/test")
+            .credstore("/mnt/credstore")
+            .vault_toml("/etc/goamet/vault.toml")
+            .build();
+        assert_eq!(paths.root, PathBuf::from("/test"));
+        assert_eq!(paths.credstore, PathBuf::from("/mnt/credstore"));
+        assert_eq!(paths.vault_toml, PathBuf::from("/etc/goamet/vault.toml"));
+        assert_eq!(paths.services, PathBuf::from("/test/services"));
+        assert_eq!(paths.audit_lock, PathBuf::from("/test/audit.lock"));
+    }
+
+    #[test]
+    fn test_find_root_prefers_marker() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let vault = dir.path().join("vault");
+        let nested = vault.join("project");
+        for sub in ["credstore", "services"] {
+            std::fs::create_dir_all(nested.join(sub)).unwrap();
+        }
+        let start = nested.join("credstore");
+
+        // Without a marker the heuristic picks the nearest match.
+        assert_eq!(find_root_from(&start), Some(nested.clone()));
+
+        std::fs::write(vault.join(constants::ROOT_MARKER_FILE), "").unwrap();
+        assert_eq!(find_root_from(&start), Some(vault));
+    }
+
+    #[test]
+    fn test_credstore_precedence() {
+        let root = Path::new("/test");
+        let flag = || Some(PathBuf::from("/flag/cs"));
+        let env = || Some("/env/cs".to_string());
+        let toml = || Some("/toml/cs".to_string());
+
+        assert_eq!(select_credstore(root, flag(), env(), toml()), flag());
+        assert_eq!(
+            select_credstore(root, None, env(), toml()),
+            Some(PathBuf::from("/env/cs"))
+        );
+        assert_eq!(
+            select_credstore(root, None, Some(String::new()), toml()),
+            Some(PathBuf::from("/toml/cs"))
+        );
+        assert_eq!(select_credstore(root, None, None, None), None);
+        // Relative vault.toml paths are anchored at the root.
+        assert_eq!(
+            select_credstore(root, None, None, Some("store".to_string())),
+            Some(PathBuf::from("/test/store"))
+        );
+        // A recorded default is not an override, even when relative.
+        let rel = Path::new("vault");
+        assert_eq!(
+            select_credstore(rel, None, None, Some("vault/credstore".to_string())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_credstore_override_only_when_moved() {
+        let paths = VaultPaths::from_root(PathBuf::from("/test"));
+        assert_eq!(paths.credstore_override(), None);
+        let paths = paths.with_credstore(Some(PathBuf::from("/mnt/cs")), None);
+        assert_eq!(paths.credstore_override(), Some("/mnt/cs".to_string()));
+    }
+
 }
@@ -2,11 +2,23 @@
 //!
 //! Parses `services/*.conf` map files into structured entries.
 //! Used by dropin generation, health checks, and migration verify.
-
-use anyhow::{bail, Context, Result};
+//!
+//! A map may pull in other maps with `@include <path>`. Relative paths are
+//! resolved against the including file's directory and may be glob
+//! patterns (matches are included in sorted order). Includes nest up to
+//! `MAX_INCLUDE_DEPTH` levels; cycles are rejected.
+
+use crate::core::cred_name;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Maximum nesting depth for `@include` directives.
+pub const MAX_INCLUDE_DEPTH: usize = 8;
+
+const INCLUDE_DIRECTIVE: &str = "@include";
+
 /// A parsed entry from a service map file.
 #[derive(Debug, Clone)]
 pub struct ServiceMapEntry {
@@ -15,6 +27,18 @@ pub struct ServiceMapEntry {
     pub env_var: Option<String>,
     pub line_number: usize,
     pub is_custom_path: bool,
+    /// Included file this entry came from (`None` for the top-level map).
+    pub origin: Option<PathBuf>,
+}
+
+impl ServiceMapEntry {
+    /// Human-readable position, e.g. `line 3` or `line 3 of shared.inc`.
+    pub fn location(&self) -> String {
+        match &self.origin {
+            Some(origin) => format!("line {} of {}", self.line_number, origin.display()),
+            None => format!("line {}", self.line_number),
+        }
+    }
 }
 
 /// A warning produced during map validation.
@@ -35,15 +59,81 @@ impl std::fmt::Display for MapWarning {
 /// Format per line: `CRED_NAME [ENVVAR]` or `name:path [ENVVAR]`
 /// Lines starting with `#` (after optional whitespace) are comments.
 pub fn parse_service_map(path: &Path, default_cred_dir: &Path) -> Result<Vec<ServiceMapEntry>> {
-    let content = fs::read_to_string(path)
-        .with_context(|| format!("read map file {}", path.display()))?;
-    parse_service_map_content(&content, default_cred_dir)
+    let mut stack = Vec::new();
+    let entries = parse_map_file(path, default_cred_dir, &mut stack)?;
+    check_duplicates(&entries)?;
+    Ok(entries)
 }
 
 /// Parse service map content (testable without filesystem).
+///
+/// Relative `@include` paths are resolved against the current directory.
 pub fn parse_service_map_content(
     content: &str,
     default_cred_dir: &Path,
+) -> Result<Vec<ServiceMapEntry>> {
+    let mut stack = Vec::new();
+    let entries = parse_lines(content, Path::new("."), default_cred_dir, &mut stack)?;
+    check_duplicates(&entries)?;
+    Ok(entries)
+}
+
+fn parse_map_file(
+    path: &Path,
+    default_cred_dir: &Path,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Vec<ServiceMapEntry>> {
+    let canonical = fs::canonicalize(path)
+        .with_context(|| format!("read map file {}", path.display()))?;
+    if stack.contains(&canonical) {
+        let chain: Vec<String> = stack
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        bail!("include cycle: {}", chain.join(" -> "));
+    }
+    if stack.len() > MAX_INCLUDE_DEPTH {
+        bail!("include depth exceeds {}", MAX_INCLUDE_DEPTH);
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("read map file {}", path.display()))?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+    stack.push(canonical);
+    let result = parse_lines(&content, base_dir, default_cred_dir, stack);
+    stack.pop();
+    result
+}
+
+/// Resolve an `@include` target to the list of files it names.
+fn resolve_include(target: &str, base_dir: &Path) -> Result<Vec<PathBuf>> {
+    let path = if Path::new(target).is_absolute() {
+        PathBuf::from(target)
+    } else {
+        base_dir.join(target)
+    };
+    if !target.contains(['*', '?', '[']) {
+        return Ok(vec![path]);
+    }
+    let pattern = path.to_string_lossy().to_string();
+    let mut matches = Vec::new();
+    for entry in glob::glob(&pattern).with_context(|| format!("invalid include pattern {}", target))? {
+        let matched = entry.with_context(|| format!("expand include pattern {}", target))?;
+        if matched.is_file() {
+            matches.push(matched);
+        }
+    }
+    matches.sort();
+    Ok(matches)
+}
+
+fn parse_lines(
+    content: &str,
+    base_dir: &Path,
+    default_cred_dir: &Path,
+    stack: &mut Vec<PathBuf>,
 ) -> Result<Vec<ServiceMapEntry>> {
     let mut entries = Vec::new();
 
@@ -55,6 +145,27 @@ pub fn parse_service_map_content(
             continue;
         }
 
+        if let Some(rest) = trimmed.strip_prefix(INCLUDE_DIRECTIVE) {
+            let target = rest.trim();
+            if target.is_empty() || !rest.starts_with(char::is_whitespace) {
+                bail!("line {}: @include requires a path", line_num);
+            }
+            for file in resolve_include(target, base_dir)
+                .map_err(|e| anyhow!("line {}: {:#}", line_num, e))?
+            {
+                let included = parse_map_file(&file, default_cred_dir, stack).map_err(|e| {
+                    anyhow!("line {}: in @include {}: {:#}", line_num, file.display(), e)
+                })?;
+                entries.extend(included.into_iter().map(|mut entry| {
+                    if entry.origin.is_none() {
+                        entry.origin = Some(file.clone());
+                    }
+                    entry
+                }));
+            }
+            continue;
+        }
+
         let mut parts = trimmed.split_whitespace();
         let raw = match parts.next() {
             Some(val) => val,
@@ -76,14 +187,8 @@ pub fn parse_service_map_content(
         if name.is_empty() {
             bail!("empty credential name on line {}", line_num);
         }
-        if !name
-            .chars()
-            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
-        {
-            bail!(
-                "invalid credential name on line {}: only [a-zA-Z0-9._-] allowed",
-                line_num
-            );
+        if let Err(e) = cred_name::validate(&name) {
+            bail!("invalid credential name on line {}: {}", line_num, e);
         }
 
         // Validate custom paths
@@ -130,24 +235,28 @@ pub fn parse_service_map_content(
             env_var,
             line_number: line_num,
             is_custom_path: is_custom,
+            origin: None,
         });
     }
 
-    // Check for duplicate credential names
+    Ok(entries)
+}
+
+/// Reject duplicate credential names, including across included files.
+fn check_duplicates(entries: &[ServiceMapEntry]) -> Result<()> {
     for (i, entry) in entries.iter().enumerate() {
         for other in &entries[i + 1..] {
             if entry.cred_name == other.cred_name {
                 bail!(
-                    "duplicate credential '{}' on lines {} and {}",
+                    "duplicate credential '{}' on {} and {}",
                     entry.cred_name,
-                    entry.line_number,
-                    other.line_number
+                    entry.location(),
+                    other.location()
                 );
             }
         }
     }
-
-    Ok(entries)
+    Ok(())
 }
 
 /// Validate map entries against known credentials and credstore.
@@ -156,9 +265,33 @@ pub fn validate_map(
     known_creds: &[String],
     credstore: &Path,
 ) -> Vec<MapWarning> {
-    let mut warnings = Vec::new();
+    let mut warnings = env_var_collisions(entries);
 
     for entry in entries {
+        for message in cred_name::warnings(&entry.cred_name) {
+            warnings.push(MapWarning {
+                line: entry.line_number,
+                message,
+            });
+        }
+
+        // systemd decrypts with the ID as --name; a .cred made by the vault
+        // is encrypted under its file stem.
+        if entry.is_custom_path {
+            let stem = entry.cred_path.file_stem().and_then(|s| s.to_str());
+            if stem.is_some_and(|stem| stem != entry.cred_name) {
+                warnings.push(MapWarning {
+                    line: entry.line_number,
+                    message: format!(
+                        "credential '{}' points at {}; unless it was encrypted as '{}', it will not decrypt at unit start",
+                        entry.cred_name,
+                        entry.cred_path.display(),
+                        entry.cred_name
+                    ),
+                });
+            }
+        }
+
         // Warn if credential not in vault.toml
         if !known_creds.iter().any(|c| c == &entry.cred_name) {
             warnings.push(MapWarning {
@@ -190,6 +323,99 @@ pub fn validate_map(
     warnings
 }
 
+/// Flag entries that map to an environment variable already claimed by an
+/// earlier entry; the later `Environment=` line would shadow the first.
+pub fn env_var_collisions(entries: &[ServiceMapEntry]) -> Vec<MapWarning> {
+    let mut warnings = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let Some(env_var) = &entry.env_var else {
+            continue;
+        };
+        if let Some(first) = entries[..i]
+            .iter()
+            .find(|e| e.env_var.as_ref() == Some(env_var))
+        {
+            warnings.push(MapWarning {
+                line: entry.line_number,
+                message: format!(
+                    "env var collision: '{}' set by '{}' ({}) and '{}' ({})",
+                    env_var,
+                    first.cred_name,
+                    first.location(),
+                    entry.cred_name,
+                    entry.location()
+                ),
+            });
+        }
+    }
+    warnings
+}
+
+/// Services whose map file references a credential.
+#[derive(Debug, Clone, Default)]
+pub struct CredentialRefs {
+    /// Service names (map file stems), sorted.
+    pub services: Vec<String>,
+    /// Maps that could not be parsed, as `service: error`.
+    pub unreadable: Vec<String>,
+}
+
+/// Credential -> services index over all map files.
+#[derive(Debug, Clone, Default)]
+pub struct MapReferences {
+    /// Services (map file stems) referencing each credential, sorted.
+    pub by_credential: BTreeMap<String, Vec<String>>,
+    /// Maps that could not be parsed, as `service: error`.
+    pub unreadable: Vec<String>,
+}
+
+/// Parse every `<services_dir>/*.conf` map (including its `@include`s) and
+/// index which services reference which credentials.
+pub fn scan_references(services_dir: &Path, default_cred_dir: &Path) -> MapReferences {
+    let mut refs = MapReferences::default();
+    let Ok(dir) = fs::read_dir(services_dir) else {
+        return refs;
+    };
+    for entry in dir.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("conf") {
+            continue;
+        }
+        let Some(svc) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        match parse_service_map(&path, default_cred_dir) {
+            Ok(entries) => {
+                for e in entries {
+                    let services = refs.by_credential.entry(e.cred_name).or_default();
+                    if !services.iter().any(|s| s == svc) {
+                        services.push(svc.to_string());
+                    }
+                }
+            }
+            Err(e) => refs.unreadable.push(format!("{}: {:#}", svc, e)),
+        }
+    }
+    for services in refs.by_credential.values_mut() {
+        services.sort();
+    }
+    refs.unreadable.sort();
+    refs
+}
+
+/// Services whose map references `cred_name` (see [`scan_references`]).
+pub fn services_referencing(
+    services_dir: &Path,
+    default_cred_dir: &Path,
+    cred_name: &str,
+) -> CredentialRefs {
+    let mut refs = scan_references(services_dir, default_cred_dir);
+    CredentialRefs {
+        services: refs.by_credential.remove(cred_name).unwrap_or_default(),
+        unreadable: refs.unreadable,
+    }
+}
+
 fn is_valid_env_var(s: &str) -> bool {
     if s.is_empty() {
         return false;
@@ -314,6 +540,7 @@ mod tests {
                 env_var: None,
                 line_number: 1,
                 is_custom_path: false,
+                origin: None,
             },
             ServiceMapEntry {
                 cred_name: "missing".to_string(),
@@ -321,6 +548,7 @@ mod tests {
                 env_var: None,
                 line_number: 2,
                 is_custom_path: false,
+                origin: None,
             },
         ];
         let known = vec!["exists".to_string()];
@@ -330,12 +558,172 @@ mod tests {
         assert!(warnings.iter().any(|w| w.message.contains(".cred file not found")));
     }
 
+    #[test]
+    fn test_validate_map_warns_on_custom_path_name_mismatch() {
+        let entry = |name: &str, path: &str| ServiceMapEntry {
+            cred_name: name.to_string(),
+            cred_path: PathBuf::from(path),
+            env_var: None,
+            line_number: 1,
+            is_custom_path: true,
+            origin: None,
+        };
+        let known = vec!["db".to_string()];
+        let warnings = validate_map(&[entry("db", "/srv/creds/db.cred")], &known, Path::new("/c"));
+        assert!(!warnings.iter().any(|w| w.message.contains("will not decrypt")));
+        let warnings =
+            validate_map(&[entry("db", "/srv/creds/prod_db.cred")], &known, Path::new("/c"));
+        assert!(warnings.iter().any(|w| w.message.contains("will not decrypt at unit start")));
+    }
+
+    #[test]
+    fn test_env_var_collisions() {
+        let content = "db_a DB_PASS\ndb_b OTHER\ndb_c DB_PASS\nplain\n";
+        let entries = parse_service_map_content(content, Path::new("/creds")).unwrap();
+        let warnings = env_var_collisions(&entries);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 3);
+        assert!(warnings[0].message.contains("'DB_PASS'"));
+        assert!(warnings[0].message.contains("'db_a' (line 1)"));
+        assert!(warnings[0].message.contains("'db_c' (line 3)"));
+    }
+
+    #[test]
+    fn test_validate_map_reports_env_var_collision() {
+        let content = "db_a DB_PASS\ndb_b DB_PASS\n";
+        let entries = parse_service_map_content(content, Path::new("/creds")).unwrap();
+        let known = vec!["db_a".to_string(), "db_b".to_string()];
+        let warnings = validate_map(&entries, &known, Path::new("/creds"));
+        assert!(warnings.iter().any(|w| w.message.contains("env var collision")));
+    }
+
     #[test]
     fn test_parse_empty_content() {
         let entries = parse_service_map_content("", Path::new("/creds")).unwrap();
         assert!(entries.is_empty());
     }
 
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_include() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write(dir.path(), "shared.inc", "db_password DB_PASS_FILE\n");
+        let map = write(dir.path(), "api.conf", "@include shared.inc\napi_token\n");
+        let entries = parse_service_map(&map, Path::new("/creds")).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].cred_name, "db_password");
+        assert!(entries[0].origin.as_ref().unwrap().ends_with("shared.inc"));
+        assert_eq!(entries[1].cred_name, "api_token");
+        assert!(entries[1].origin.is_none());
+    }
+
+    #[test]
+    fn test_nested_include_and_glob() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("inc")).unwrap();
+        write(dir.path(), "inc/a.inc", "@include ../leaf.inc\nalpha\n");
+        write(dir.path(), "inc/b.inc", "beta\n");
+        write(dir.path(), "leaf.inc", "leaf\n");
+        let map = write(dir.path(), "svc.conf", "@include inc/*.inc\n");
+        let entries = parse_service_map(&map, Path::new("/creds")).unwrap();
+        let names: Vec<_> = entries.iter().map(|e| e.cred_name.as_str()).collect();
+        assert_eq!(names, vec!["leaf", "alpha", "beta"]);
+        assert!(entries[0].origin.as_ref().unwrap().ends_with("leaf.inc"));
+    }
+
+    #[test]
+    fn test_services_referencing() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write(dir.path(), "shared.inc", "db_password DB_PASS_FILE\n");
+        write(dir.path(), "api.conf", "@include shared.inc\napi_token\n");
+        write(dir.path(), "worker.conf", "db_password\n");
+        write(dir.path(), "web.conf", "api_token\n");
+        write(dir.path(), "broken.conf", "bad name!\n");
+        write(dir.path(), "notes.txt", "db_password\n");
+
+        let refs = services_referencing(dir.path(), Path::new("/creds"), "db_password");
+        assert_eq!(refs.services, vec!["api", "worker"]);
+        assert_eq!(refs.unreadable.len(), 1);
+        assert!(refs.unreadable[0].starts_with("broken: "));
+    }
+
+    #[test]
+    fn test_render_dropin_script_expands_includes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("inc")).unwrap();
+        write(dir.path(), "inc/a.inc", "@include ../leaf.inc\nalpha ALPHA_FILE\n");
+        write(dir.path(), "inc/b.inc", "beta:/etc/beta.cred\n");
+        write(dir.path(), "leaf.inc", "leaf\n");
+        let map = write(dir.path(), "svc.conf", "db DB_FILE\n@include inc/*.inc\n");
+        let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("scripts/render_dropin.sh");
+        let out_dir = dir.path().join("out");
+        let render = |map: &Path| {
+            std::process::Command::new("bash")
+                .arg(&script)
+                .arg("svc")
+                .args(["--cred-dir", "/creds", "--map-file"])
+                .arg(map)
+                .arg("--out-dir")
+                .arg(&out_dir)
+                .output()
+                .unwrap()
+        };
+
+        let output = render(&map);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let dropin = fs::read_to_string(out_dir.join("credentials.conf")).unwrap();
+        let loaded: Vec<String> = parse_service_map(&map, Path::new("/creds"))
+            .unwrap()
+            .iter()
+            .map(|e| format!("LoadCredentialEncrypted={}:{}", e.cred_name, e.cred_path.display()))
+            .collect();
+        let rendered: Vec<&str> =
+            dropin.lines().filter(|l| l.starts_with("LoadCredentialEncrypted=")).collect();
+        assert_eq!(rendered, loaded);
+        assert!(dropin.contains("Environment=ALPHA_FILE=%d/alpha\n"));
+        assert!(!dropin.contains("@include"));
+
+        write(dir.path(), "leaf.inc", "@include svc.conf\n");
+        let output = render(&map);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("include cycle"));
+    }
+
+    #[test]
+    fn test_include_cycle() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write(dir.path(), "a.inc", "@include b.inc\n");
+        write(dir.path(), "b.inc", "@include a.inc\n");
+        let map = write(dir.path(), "svc.conf", "@include a.inc\n");
+        let err = parse_service_map(&map, Path::new("/creds")).unwrap_err();
+        assert!(format!("{:#}", err).contains("include cycle"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_include_duplicate_across_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write(dir.path(), "shared.inc", "\ndb_password\n");
+        let map = write(dir.path(), "svc.conf", "db_password\n@include shared.inc\n");
+        let err = parse_service_map(&map, Path::new("/creds")).unwrap_err().to_string();
+        assert!(err.contains("line 1 and line 2 of"), "{}", err);
+        assert!(err.contains("shared.inc"), "{}", err);
+    }
+
+    #[test]
+    fn test_include_error_reports_including_line() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write(dir.path(), "bad.inc", "ok\ninv@lid\n");
+        let map = write(dir.path(), "svc.conf", "# header\n@include bad.inc\n");
+        let err = parse_service_map(&map, Path::new("/creds")).unwrap_err().to_string();
+        assert!(err.starts_with("line 2: in @include"), "{}", err);
+        assert!(err.contains("line 2"), "{}", err);
+    }
+
     #[test]
     fn test_line_numbers() {
         let content = "# comment\n\nfirst\nsecond\n";
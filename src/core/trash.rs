@@ -0,0 +1,219 @@
+//! Trash for deleted credentials.
+//!
+//! `delete` moves `<name>.cred` to `<root>/.trash/<name>.cred.<stamp>` and
+//! stashes its metadata as `<name>.meta.<stamp>.json`, so `trash restore`
+//! brings back both. Every deletion gets its own stamp; `trash purge`
+//! removes entries for good.
+
+use crate::constants;
+use crate::core::{credstore, metadata};
+use crate::core::paths::VaultPaths;
+use crate::models::credential::CredentialMeta;
+use crate::util::fs as vault_fs;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Deletion time in trash file names (UTC, millisecond resolution).
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// One deleted credential in the trash.
+#[derive(Debug, Clone)]
+pub struct TrashedCredential {
+    pub name: String,
+    /// Identifies this deletion when a name was deleted more than once.
+    pub stamp: String,
+    pub deleted_at: DateTime<Utc>,
+    pub has_metadata: bool,
+}
+
+impl TrashedCredential {
+    pub fn cred_path(&self, trash: &Path) -> PathBuf {
+        trash.join(format!("{}{}.{}", self.name, constants::CRED_EXTENSION, self.stamp))
+    }
+}
+
+fn meta_path(trash: &Path, name: &str, stamp: &str) -> PathBuf {
+    trash.join(format!("{}.meta.{}.json", name, stamp))
+}
+
+/// Split `<name>.cred.<stamp>` into name, stamp and deletion time.
+fn parse_trash_file(file_name: &str) -> Option<(String, String, DateTime<Utc>)> {
+    let (name, stamp) = file_name.rsplit_once(&format!("{}.", constants::CRED_EXTENSION))?;
+    let at = NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT).ok()?.and_utc();
+    (!name.is_empty()).then(|| (name.to_string(), stamp.to_string(), at))
+}
+
+/// Move `name`'s `.cred` into the trash, stashing `meta` if it has metadata.
+pub fn move_to_trash(
+    paths: &VaultPaths,
+    name: &str,
+    meta: Option<&CredentialMeta>,
+) -> Result<TrashedCredential> {
+    let cred_path = credstore::existing_cred_path(&paths.credstore, name)?;
+    vault_fs::ensure_dir(&paths.trash, constants::CREDSTORE_DIR_MODE)?;
+    let now = Utc::now();
+    let entry = TrashedCredential {
+        name: name.to_string(),
+        stamp: now.format(STAMP_FORMAT).to_string(),
+        deleted_at: now,
+        has_metadata: meta.is_some(),
+    };
+    if let Some(meta) = meta {
+        metadata::write_prev_snapshot(&meta_path(&paths.trash, name, &entry.stamp), meta)?;
+    }
+    move_file(&cred_path, &entry.cred_path(&paths.trash))?;
+    Ok(entry)
+}
+
+/// Everything in the trash, by name, newest deletion first.
+pub fn list(paths: &VaultPaths) -> Result<Vec<TrashedCredential>> {
+    if !paths.trash.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut items = Vec::new();
+    let dir = fs::read_dir(&paths.trash)
+        .with_context(|| format!("read {}", paths.trash.display()))?;
+    for entry in dir {
+        let entry = entry.with_context(|| format!("read {}", paths.trash.display()))?;
+        let file_name = entry.file_name();
+        let parsed = file_name.to_str().and_then(parse_trash_file);
+        let Some((name, stamp, deleted_at)) = parsed else {
+            continue;
+        };
+        items.push(TrashedCredential {
+            has_metadata: meta_path(&paths.trash, &name, &stamp).is_file(),
+            name,
+            stamp,
+            deleted_at,
+        });
+    }
+    items.sort_by(|a, b| a.name.cmp(&b.name).then(b.deleted_at.cmp(&a.deleted_at)));
+    Ok(items)
+}
+
+/// Move a trashed credential back into the credstore: the deletion with
+/// `stamp`, or the newest one. Returns it with the stashed metadata, if any,
+/// and the snapshot path; the caller removes the snapshot with
+/// [`remove_snapshot`] once vault.toml is saved. Refuses to overwrite a live
+/// credential.
+pub fn restore(
+    paths: &VaultPaths,
+    name: &str,
+    stamp: Option<&str>,
+) -> Result<(TrashedCredential, Option<CredentialMeta>, PathBuf)> {
+    let Some(entry) = list(paths)?
+        .into_iter()
+        .find(|e| e.name == name && stamp.is_none_or(|s| e.stamp == s))
+    else {
+        match stamp {
+            Some(stamp) => {
+                bail!("no deletion of '{}' with stamp {} in the trash", name, stamp)
+            }
+            None => bail!("'{}' is not in the trash ({})", name, paths.trash.display()),
+        }
+    };
+    let target = credstore::cred_path(&paths.credstore, name);
+    if target.exists() {
+        bail!(
+            "credential '{}' exists again at {}; delete or rename it before restoring",
+            name,
+            target.display()
+        );
+    }
+    let snapshot = meta_path(&paths.trash, name, &entry.stamp);
+    let meta = metadata::read_prev_snapshot(&snapshot)?;
+    vault_fs::ensure_dir(&paths.credstore, constants::CREDSTORE_DIR_MODE)?;
+    move_file(&entry.cred_path(&paths.trash), &target)?;
+    vault_fs::set_permissions(&target, constants::CRED_FILE_MODE)?;
+    Ok((entry, meta, snapshot))
+}
+
+/// Remove a stashed metadata snapshot, if it is still there.
+pub fn remove_snapshot(snapshot: &Path) -> Result<()> {
+    if snapshot.exists() {
+        fs::remove_file(snapshot).with_context(|| format!("remove {}", snapshot.display()))?;
+    }
+    Ok(())
+}
+
+/// Remove a trashed credential and its stashed metadata for good.
+pub fn purge(paths: &VaultPaths, entry: &TrashedCredential) -> Result<()> {
+    let cred = entry.cred_path(&paths.trash);
+    fs::remove_file(&cred).with_context(|| format!("remove {}", cred.display()))?;
+    remove_snapshot(&meta_path(&paths.trash, &entry.name, &entry.stamp))
+}
+
+/// Rename, falling back to copy + remove when the credstore and the trash
+/// are on different filesystems.
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)
+        .with_context(|| format!("copy {} to {}", from.display(), to.display()))?;
+    vault_fs::set_permissions(to, constants::CRED_FILE_MODE)?;
+    fs::remove_file(from).with_context(|| format!("remove {}", from.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_trash_file() {
+        let (name, stamp, at) = parse_trash_file("db.v2.cred.20250102T030405123Z").unwrap();
+        assert_eq!(name, "db.v2");
+        assert_eq!(stamp, "20250102T030405123Z");
+        assert_eq!(at.to_rfc3339(), "2025-01-02T03:04:05.123+00:00");
+        assert!(parse_trash_file("db.meta.20250102T030405123Z.json").is_none());
+        assert!(parse_trash_file("db.cred.yesterday").is_none());
+    }
+
+    #[test]
+    fn test_trash_restore_and_purge() {
+        let dir = TempDir::new().unwrap();
+        let paths = VaultPaths::from_root(dir.path().to_path_buf());
+        fs::create_dir_all(&paths.credstore).unwrap();
+        let cred = credstore::cred_path(&paths.credstore, "db");
+        let meta = CredentialMeta {
+            name: "db".into(),
+            tags: vec!["env:prod".into()],
+            ..Default::default()
+        };
+
+        fs::write(&cred, "first").unwrap();
+        let first = move_to_trash(&paths, "db", Some(&meta)).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        fs::write(&cred, "second").unwrap();
+        move_to_trash(&paths, "db", None).unwrap();
+        assert!(!cred.exists());
+
+        let items = list(&paths).unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(!items[0].has_metadata && items[1].has_metadata);
+
+        // The newest deletion is restored unless a stamp is given.
+        let (_, stashed, snapshot) = restore(&paths, "db", None).unwrap();
+        assert_eq!(fs::read_to_string(&cred).unwrap(), "second");
+        assert!(stashed.is_none());
+        assert!(!snapshot.exists());
+        assert!(restore(&paths, "db", Some(&first.stamp)).is_err());
+
+        fs::remove_file(&cred).unwrap();
+        let (_, stashed, snapshot) = restore(&paths, "db", Some(&first.stamp)).unwrap();
+        assert_eq!(fs::read_to_string(&cred).unwrap(), "first");
+        assert_eq!(stashed.unwrap().tags, vec!["env:prod".to_string()]);
+        // The snapshot stays until the caller has saved vault.toml.
+        assert!(snapshot.is_file());
+        remove_snapshot(&snapshot).unwrap();
+        assert!(list(&paths).unwrap().is_empty());
+
+        move_to_trash(&paths, "db", Some(&meta)).unwrap();
+        let entry = list(&paths).unwrap().remove(0);
+        purge(&paths, &entry).unwrap();
+        assert_eq!(fs::read_dir(&paths.trash).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,737 @@
+//! Library entry point for embedding the vault.
+//!
+//! [`Vault`] bundles the paths and `[policy]` of one vault and exposes the
+//! credential operations as typed calls. Nothing here prints, prompts,
+//! audits or runs hooks: the CLI handlers add those around these calls.
+//! Secrets go in and come out as bytes. Refusals and missing credentials
+//! carry a [`VaultError`] inside the returned `anyhow::Error`.
+
+use crate::constants;
+use crate::core::error::{VaultError, VaultResult};
+use crate::core::paths::VaultPaths;
+use crate::core::trash::{self, TrashedCredential};
+use crate::core::{cred_name, credstore, file_lock::FileLock, metadata};
+use crate::models::credential::CredentialMeta;
+use crate::models::policy::PolicySection;
+use crate::models::vault_config::VaultFile;
+use crate::util::{fs as vault_fs, scratch, systemd};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+use zeroize::Zeroizing;
+
+/// One vault: its paths and the policy its operations are checked against.
+#[derive(Debug, Clone)]
+pub struct Vault {
+    paths: VaultPaths,
+    policy: PolicySection,
+}
+
+/// Settings for [`Vault::create`] and [`Vault::rotate`].
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// `systemd-creds --with-key`; `None` picks host+tpm2 when a TPM2 is present.
+    pub with_key: Option<String>,
+    /// PCR binding; `None` keeps the recorded one (rotate) or uses policy
+    /// `default_tpm2_pcrs`.
+    pub tpm2_pcrs: Option<String>,
+    /// Refuse to fall back to host-only encryption.
+    pub require_tpm2: bool,
+    pub description: Option<String>,
+    pub group: Option<String>,
+    /// Replaces the stored tags when non-empty.
+    pub tags: Vec<String>,
+    /// Replaces the linked services when non-empty.
+    pub services: Vec<String>,
+}
+
+/// The key type and PCR binding a write uses, once policy has accepted it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub with_key: String,
+    pub tpm2_pcrs: Option<String>,
+}
+
+impl Vault {
+    /// Open the vault at `root`, with the `[policy]` and `[vault]
+    /// credstore_path` of its vault.toml when there is one.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let paths = VaultPaths::from_root(root.into());
+        let vault = if paths.vault_toml.exists() {
+            metadata::load(&paths.vault_toml)?
+        } else {
+            VaultFile::default()
+        };
+        vault
+            .policy
+            .validate_secret_size_limits()
+            .map_err(|e| {
+                anyhow::anyhow!("invalid policy in {}: {}", paths.vault_toml.display(), e)
+            })?;
+        let paths = paths.with_credstore(None, vault.vault.credstore_path);
+        Ok(Self::new(paths, vault.policy))
+    }
+
+    pub fn new(paths: VaultPaths, policy: PolicySection) -> Self {
+        Self { paths, policy }
+    }
+
+    pub fn paths(&self) -> &VaultPaths {
+        &self.paths
+    }
+
+    pub fn policy(&self) -> &PolicySection {
+        &self.policy
+    }
+
+    /// Credentials recorded in vault.toml. Without a vault.toml, every
+    /// `.cred` in the credstore is listed with its name only.
+    pub fn list(&self) -> Result<Vec<CredentialMeta>> {
+        if self.paths.vault_toml.exists() {
+            return Ok(metadata::load(&self.paths.vault_toml)?.credentials);
+        }
+        if !self.paths.credstore.is_dir() {
+            return Ok(Vec::new());
+        }
+        Ok(credstore::list_credentials(&self.paths.credstore)?
+            .into_iter()
+            .map(|entry| CredentialMeta {
+                name: entry.name,
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    /// The vault.toml record for `name`.
+    pub fn describe(&self, name: &str) -> Result<CredentialMeta> {
+        if !self.paths.vault_toml.exists() {
+            bail!("metadata not found: {}", self.paths.vault_toml.display());
+        }
+        metadata::load(&self.paths.vault_toml)?
+            .credentials
+            .into_iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| anyhow::anyhow!("metadata not found for {}", name))
+    }
+
+    /// Decrypt `name`. The plaintext is zeroed when dropped. Fails with
+    /// [`VaultError::CredentialNotFound`] or [`VaultError::DecryptFailed`].
+    pub fn get(&self, name: &str) -> Result<Zeroizing<Vec<u8>>> {
+        let path = self.check_get(name)?;
+        Ok(systemd::decrypt_to_stdout(&path, None, None)?)
+    }
+
+    /// Checks for reading `name`; returns its `.cred` path.
+    /// [`Vault::get`] runs these itself.
+    pub fn check_get(&self, name: &str) -> Result<PathBuf> {
+        check_name(name)?;
+        Ok(credstore::existing_cred_path(&self.paths.credstore, name)?)
+    }
+
+    /// Fail unless `secret` is non-empty and within policy
+    /// `min_secret_size`/`max_secret_size`. [`Vault::create`] and
+    /// [`Vault::rotate`] run this themselves.
+    pub fn check_secret(&self, secret: &[u8]) -> Result<()> {
+        if secret.is_empty() {
+            bail!("secret is empty");
+        }
+        Ok(self.policy.check_secret_size(secret.len())?)
+    }
+
+    /// Policy checks for creating `name`; returns the binding the write
+    /// would use, or fails with [`VaultError::PolicyViolation`].
+    /// [`Vault::create`] runs these itself.
+    pub fn check_create(&self, name: &str, opts: &WriteOptions) -> Result<KeyBinding> {
+        self.check_write(name, opts, None, None)
+    }
+
+    /// Policy checks for rotating `name`, keeping its recorded PCR binding
+    /// unless `opts` changes it. [`Vault::rotate`] runs these itself.
+    pub fn check_rotate(&self, name: &str, opts: &WriteOptions) -> Result<KeyBinding> {
+        let recorded = if self.paths.vault_toml.exists() {
+            metadata::load(&self.paths.vault_toml)?
+                .credentials
+                .into_iter()
+                .find(|c| c.name == name)
+                .and_then(|c| c.tpm2_pcrs)
+        } else {
+            None
+        };
+        self.check_write(name, opts, recorded.as_deref(), None)
+    }
+
+    /// `locked` is the vault.toml already loaded under the vault lock; without
+    /// it the required-tags check loads the file itself.
+    fn check_write(
+        &self,
+        name: &str,
+        opts: &WriteOptions,
+        recorded_pcrs: Option<&str>,
+        locked: Option<&VaultFile>,
+    ) -> Result<KeyBinding> {
+        check_name(name)?;
+        let with_key = resolve_key_type(opts.with_key.as_deref());
+        check_key_policy(&self.policy, &with_key, opts.require_tpm2)?;
+        let tpm2_pcrs = resolve_tpm2_pcrs(
+            &self.policy,
+            &with_key,
+            opts.tpm2_pcrs.as_deref(),
+            recorded_pcrs,
+        )?;
+
+        // Policy: service allowlist (for metadata linkage)
+        for svc in &opts.services {
+            if !self.policy.is_service_allowed(svc) {
+                return Err(VaultError::PolicyViolation(format!(
+                    "policy: service '{}' not allowed (service_allowlist enforced)",
+                    svc
+                ))
+                .into());
+            }
+            self.policy.check_name_prefix(name, svc)?;
+        }
+
+        // Advisory unless `locked`: create/rotate re-check against the file they lock.
+        match locked {
+            Some(vault) => self.check_tags(vault, name, opts)?,
+            None if !self.policy.required_tags.is_empty() => {
+                let vault = metadata::load(&self.paths.vault_toml)?;
+                self.check_tags(&vault, name, opts)?;
+            }
+            None => {}
+        }
+        Ok(KeyBinding {
+            with_key,
+            tpm2_pcrs,
+        })
+    }
+
+    /// Encrypt `secret` as `name`, replacing any existing `.cred` without a
+    /// backup. Returns the credential path.
+    pub fn create(&self, name: &str, secret: &[u8], opts: &WriteOptions) -> Result<PathBuf> {
+        check_name(name)?;
+        self.check_secret(secret)?;
+        let binding = self.check_create(name, opts)?;
+        let paths = &self.paths;
+        vault_fs::ensure_dir(&paths.credstore, constants::CREDSTORE_DIR_MODE)?;
+
+        let tmp_secret = write_temp_secret(secret, &paths.credstore)?;
+        let tmp_output = temp_cred_output(&paths.credstore)?;
+        systemd::encrypt(
+            &binding.with_key,
+            name,
+            tmp_secret.path(),
+            tmp_output.path(),
+            binding.tpm2_pcrs.as_deref(),
+        )?;
+        drop(tmp_secret);
+
+        let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
+        let mut vault = metadata::load(&paths.vault_toml)?;
+        self.check_tags(&vault, name, opts)?;
+        let output = credstore::cred_path(&paths.credstore, name);
+        tmp_output
+            .persist(&output)
+            .map_err(|e| anyhow::anyhow!("persist credential: {}", e))?;
+        vault_fs::set_permissions(&output, constants::CRED_FILE_MODE)?;
+
+        self.record(&mut vault, name, &output, binding, opts);
+        metadata::save(&paths.vault_toml, &vault)?;
+        Ok(output)
+    }
+
+    /// [`Vault::create`] for every `(name, secret)` record, under one vault
+    /// lock with one vault.toml load and save. Returns one result per record,
+    /// in order; fails as a whole only when vault.toml cannot be loaded or
+    /// saved.
+    pub fn create_many(
+        &self,
+        records: &[(&str, &[u8])],
+        opts: &WriteOptions,
+    ) -> Result<Vec<Result<(PathBuf, KeyBinding)>>> {
+        let paths = &self.paths;
+        let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
+        let mut vault = metadata::load(&paths.vault_toml)?;
+
+        let mut results = Vec::with_capacity(records.len());
+        for &(name, secret) in records {
+            results.push(self.create_locked(&mut vault, name, secret, opts));
+        }
+        if results.iter().any(Result::is_ok) {
+            metadata::save(&paths.vault_toml, &vault)?;
+        }
+        Ok(results)
+    }
+
+    /// One [`Vault::create_many`] record. Caller holds `vault_lock` and saves
+    /// `vault`.
+    fn create_locked(
+        &self,
+        vault: &mut VaultFile,
+        name: &str,
+        secret: &[u8],
+        opts: &WriteOptions,
+    ) -> Result<(PathBuf, KeyBinding)> {
+        check_name(name)?;
+        self.check_secret(secret)?;
+        let binding = self.check_write(name, opts, None, Some(vault))?;
+        let paths = &self.paths;
+        vault_fs::ensure_dir(&paths.credstore, constants::CREDSTORE_DIR_MODE)?;
+
+        let tmp_secret = write_temp_secret(secret, &paths.credstore)?;
+        let tmp_output = temp_cred_output(&paths.credstore)?;
+        systemd::encrypt(
+            &binding.with_key,
+            name,
+            tmp_secret.path(),
+            tmp_output.path(),
+            binding.tpm2_pcrs.as_deref(),
+        )?;
+        drop(tmp_secret);
+
+        let output = credstore::cred_path(&paths.credstore, name);
+        tmp_output
+            .persist(&output)
+            .map_err(|e| anyhow::anyhow!("persist credential: {}", e))?;
+        vault_fs::set_permissions(&output, constants::CRED_FILE_MODE)?;
+        self.record(vault, name, &output, binding.clone(), opts);
+        Ok((output, binding))
+    }
+
+    /// Re-encrypt `name` with `secret`. The previous blob is kept as `.prev`
+    /// for `rollback rotate`. Returns the credential path.
+    pub fn rotate(&self, name: &str, secret: &[u8], opts: &WriteOptions) -> Result<PathBuf> {
+        check_name(name)?;
+        self.check_secret(secret)?;
+        let binding = self.check_rotate(name, opts)?;
+        let paths = &self.paths;
+        vault_fs::ensure_dir(&paths.credstore, constants::CREDSTORE_DIR_MODE)?;
+
+        let tmp_secret = write_temp_secret(secret, &paths.credstore)?;
+        let tmp_output = temp_cred_output(&paths.credstore)?;
+        systemd::encrypt(
+            &binding.with_key,
+            name,
+            tmp_secret.path(),
+            tmp_output.path(),
+            binding.tpm2_pcrs.as_deref(),
+        )?;
+
+        let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
+        let mut vault = metadata::load(&paths.vault_toml)?;
+        self.check_tags(&vault, name, opts)?;
+        let final_path = install_with_backup(paths, &vault, name, tmp_output)?;
+        self.record(&mut vault, name, &final_path, binding, opts);
+        metadata::save(&paths.vault_toml, &vault)?;
+        Ok(final_path)
+    }
+
+    /// Policy `required_tags` against the tags `name` would end up with.
+    /// `vault` must be the file loaded under the vault lock.
+    fn check_tags(&self, vault: &VaultFile, name: &str, opts: &WriteOptions) -> Result<()> {
+        self.policy
+            .check_required_tags(&effective_tags(vault, name, &opts.tags))?;
+        Ok(())
+    }
+
+    /// Update the vault.toml record after a create or rotate.
+    fn record(
+        &self,
+        vault: &mut VaultFile,
+        name: &str,
+        cred_path: &Path,
+        binding: KeyBinding,
+        opts: &WriteOptions,
+    ) {
+        metadata::ensure_vault_section(vault, self.paths.credstore_override());
+        let now = Utc::now();
+        let mut meta = vault
+            .credentials
+            .iter()
+            .find(|c| c.name == name)
+            .cloned()
+            .unwrap_or_default();
+        if meta.name.is_empty() {
+            meta.name = name.to_string();
+        }
+        if meta.created_at.is_none() {
+            meta.created_at = Some(now);
+        }
+        meta.rotated_at = Some(now);
+        meta.encryption_key = Some(binding.with_key);
+        meta.tpm2_pcrs = binding.tpm2_pcrs;
+        meta.cred_sha256 = credstore::cred_sha256(cred_path).ok();
+        if let Some(desc) = &opts.description {
+            meta.description = Some(desc.clone());
+        }
+        if let Some(group) = &opts.group {
+            meta.group = Some(group.clone());
+        }
+        if !opts.tags.is_empty() {
+            meta.tags = dedup(opts.tags.clone());
+        }
+        if !opts.services.is_empty() {
+            meta.services = dedup(opts.services.clone());
+        }
+        metadata::upsert_credential(vault, meta);
+    }
+
+    /// Delete `name`: moved to the trash, or removed for good with `purge`.
+    /// Returns the trash entry, or `None` when purged.
+    pub fn delete(&self, name: &str, purge: bool) -> Result<Option<TrashedCredential>> {
+        check_name(name)?;
+        let paths = &self.paths;
+        let cred_path = credstore::existing_cred_path(&paths.credstore, name)?;
+
+        let _vault_lock = FileLock::exclusive(&paths.vault_lock)?;
+        let mut vault = if paths.vault_toml.exists() {
+            Some(metadata::load(&paths.vault_toml)?)
+        } else {
+            None
+        };
+        let trashed = if purge {
+            fs::remove_file(&cred_path)
+                .with_context(|| format!("remove {}", cred_path.display()))?;
+            None
+        } else {
+            let meta = vault
+                .as_ref()
+                .and_then(|v| v.credentials.iter().find(|c| c.name == name));
+            Some(trash::move_to_trash(paths, name, meta)?)
+        };
+
+        if let Some(vault) = &mut vault {
+            metadata::remove_credential(vault, name);
+            metadata::save(&paths.vault_toml, vault)?;
+        }
+        Ok(trashed)
+    }
+}
+
+/// Reject names that are not valid credential names (path traversal,
+/// separators, ...) before they are joined onto the credstore.
+fn check_name(name: &str) -> Result<()> {
+    cred_name::validate(name).map_err(|e| anyhow::anyhow!("invalid name '{}': {}", name, e))
+}
+
+/// Check key-type policy: forbid host-only when TPM2 is available, and
+/// require TPM2 when `--require-tpm2` or policy `require_tpm2` asks for it.
+pub(crate) fn check_key_policy(
+    policy: &PolicySection,
+    with_key: &str,
+    require_tpm2: bool,
+) -> VaultResult<()> {
+    let refuse = |message: String| Err(VaultError::PolicyViolation(message));
+    if policy.forbid_host_only_when_tpm2
+        && with_key == "host"
+        && systemd::has_tpm2().unwrap_or(false)
+    {
+        return refuse(
+            "policy: host-only encryption forbidden when TPM2 is available (use host+tpm2)"
+                .to_string(),
+        );
+    }
+    if require_tpm2 || policy.require_tpm2 {
+        let source = if require_tpm2 { "--require-tpm2" } else { "policy require_tpm2" };
+        if !systemd::has_tpm2().unwrap_or(false) {
+            return refuse(format!(
+                "{}: TPM2 not available on this host; refusing to fall back to host-only encryption",
+                source
+            ));
+        }
+        if with_key == "host" {
+            return refuse(format!(
+                "{}: --with-key=host does not use TPM2 (use host+tpm2 or tpm2)",
+                source
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Tags a credential ends up with: `--tag` replaces the stored set,
+/// otherwise the existing tags are kept.
+pub(crate) fn effective_tags(vault: &VaultFile, name: &str, requested: &[String]) -> Vec<String> {
+    if !requested.is_empty() {
+        return requested.to_vec();
+    }
+    vault
+        .credentials
+        .iter()
+        .find(|c| c.name == name)
+        .map(|c| c.tags.clone())
+        .unwrap_or_default()
+}
+
+/// Resolve the effective key type: use explicit value or auto-detect TPM2.
+pub(crate) fn resolve_key_type(explicit: Option<&str>) -> String {
+    match explicit {
+        Some(k) => k.to_string(),
+        None => {
+            if systemd::has_tpm2().unwrap_or(false) {
+                constants::DEFAULT_KEY_TYPE_WITH_TPM2.to_string()
+            } else {
+                constants::DEFAULT_KEY_TYPE_WITHOUT_TPM2.to_string()
+            }
+        }
+    }
+}
+
+/// PCR binding for a new encryption: `--tpm2-pcrs`, else the credential's
+/// recorded binding (rotate), else policy `default_tpm2_pcrs`. Host-only
+/// keys bind no PCRs, so an explicit `--tpm2-pcrs` with them is an error.
+pub(crate) fn resolve_tpm2_pcrs(
+    policy: &PolicySection,
+    with_key: &str,
+    explicit: Option<&str>,
+    recorded: Option<&str>,
+) -> Result<Option<String>> {
+    if with_key == "host" {
+        if let Some(spec) = explicit {
+            bail!("--tpm2-pcrs {} needs a TPM2 key (--with-key host+tpm2 or tpm2)", spec);
+        }
+        return Ok(None);
+    }
+    if explicit.is_none() && recorded.is_none() {
+        if let Some(spec) = &policy.default_tpm2_pcrs {
+            systemd::parse_tpm2_pcrs(spec)
+                .map_err(|e| anyhow::anyhow!("policy default_tpm2_pcrs: {}", e))?;
+        }
+    }
+    Ok(explicit
+        .or(recorded)
+        .or(policy.default_tpm2_pcrs.as_deref())
+        .map(str::to_string))
+}
+
+/// Move a freshly encrypted credential into place. The current blob is kept
+/// as `.prev`, with a metadata snapshot, so `rollback rotate` can undo it.
+/// Callers must hold `vault_lock`. Returns the final credential path.
+pub(crate) fn install_with_backup(
+    paths: &VaultPaths,
+    vault: &VaultFile,
+    name: &str,
+    tmp_output: NamedTempFile,
+) -> Result<PathBuf> {
+    let final_path = paths.credstore.join(format!("{}{}", name, constants::CRED_EXTENSION));
+
+    // Create .prev backup (and metadata snapshot) before overwriting
+    let prev_path = credstore::prev_cred_path(&paths.credstore, name);
+    let snapshot_path = metadata::prev_snapshot_path(&paths.credstore, name);
+    if final_path.is_file() {
+        fs::copy(&final_path, &prev_path)
+            .with_context(|| format!("backup {} to .prev", final_path.display()))?;
+        match vault.credentials.iter().find(|c| c.name == name) {
+            Some(meta) => metadata::write_prev_snapshot(&snapshot_path, meta)?,
+            None => {
+                if snapshot_path.exists() {
+                    fs::remove_file(&snapshot_path).with_context(|| {
+                        format!("remove stale {}", snapshot_path.display())
+                    })?;
+                }
+            }
+        }
+    }
+
+    match tmp_output.persist(&final_path) {
+        Ok(_) => {}
+        Err(e) => {
+            // Restore from backup on failure
+            if prev_path.is_file() {
+                let _ = fs::rename(&prev_path, &final_path);
+            }
+            bail!("persist rotated credential: {}", e);
+        }
+    }
+    vault_fs::set_permissions(&final_path, constants::CRED_FILE_MODE)?;
+    Ok(final_path)
+}
+
+pub(crate) fn write_temp_secret(
+    secret: &[u8],
+    credstore: &Path,
+) -> Result<vault_fs::ScrubbedTempFile> {
+    let scratch = scratch::resolve(credstore);
+    let mut tmp = vault_fs::ScrubbedTempFile::new_in(&scratch.path, ".secret-")?;
+    let file = tmp.as_file_mut();
+    file.write_all(secret)
+        .context("write temp secret")?;
+    file.flush().context("flush temp secret")?;
+    Ok(tmp)
+}
+
+/// Temp `.cred` output in the credstore, renamed into place once written.
+/// The `.cred.tmp` suffix keeps `watch` from treating it as a credential.
+pub(crate) fn temp_cred_output(credstore: &Path) -> Result<NamedTempFile> {
+    vault_fs::secure_tempfile(credstore, "cred-", ".cred.tmp").context("create temp output")
+}
+
+pub(crate) fn dedup(values: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    values
+        .into_iter()
+        .filter(|v| seen.insert(v.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_dedup_preserves_order() {
+        let input = vec!["b".into(), "a".into(), "b".into(), "c".into()];
+        assert_eq!(dedup(input), vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_dedup_empty() {
+        let input: Vec<String> = vec![];
+        assert_eq!(dedup(input), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_require_tpm2_rejects_host_only() {
+        // Fails either way: no TPM2 on this host, or TPM2 present but host-only requested.
+        let policy = PolicySection {
+            require_tpm2: true,
+            ..Default::default()
+        };
+        assert!(check_key_policy(&policy, "host", false).is_err());
+        assert!(check_key_policy(&PolicySection::default(), "host", true).is_err());
+        assert!(check_key_policy(&PolicySection::default(), "host", false).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_tpm2_pcrs_precedence() {
+        let policy = PolicySection {
+            default_tpm2_pcrs: Some("7".into()),
+            ..Default::default()
+        };
+        let resolve =
+            |key, explicit, recorded| resolve_tpm2_pcrs(&policy, key, explicit, recorded).unwrap();
+        assert_eq!(resolve("host+tpm2", Some("7+11"), Some("0")), Some("7+11".into()));
+        assert_eq!(resolve("host+tpm2", None, Some("0")), Some("0".into()));
+        assert_eq!(resolve("host+tpm2", None, None), Some("7".into()));
+        assert_eq!(resolve("host", None, Some("0")), None);
+        assert!(resolve_tpm2_pcrs(&policy, "host", Some("7"), None).is_err());
+
+        let bad = PolicySection {
+            default_tpm2_pcrs: Some("7++".into()),
+            ..Default::default()
+        };
+        assert!(resolve_tpm2_pcrs(&bad, "tpm2", None, None).is_err());
+    }
+
+    #[test]
+    fn test_list_describe_delete_without_systemd() {
+        let dir = TempDir::new().unwrap();
+        let vault = Vault::open(dir.path()).unwrap();
+        assert!(vault.list().unwrap().is_empty());
+        assert!(vault.describe("db").is_err());
+
+        // A credstore without vault.toml still lists its .cred files.
+        fs::create_dir_all(&vault.paths().credstore).unwrap();
+        fs::write(credstore::cred_path(&vault.paths().credstore, "db"), b"blob").unwrap();
+        let names: Vec<String> = vault.list().unwrap().into_iter().map(|c| c.name).collect();
+        assert_eq!(names, vec!["db"]);
+
+        let mut file = VaultFile::default();
+        metadata::upsert_credential(
+            &mut file,
+            CredentialMeta {
+                name: "db".into(),
+                group: Some("prod".into()),
+                ..Default::default()
+            },
+        );
+        metadata::save(&vault.paths().vault_toml, &file).unwrap();
+        assert_eq!(vault.describe("db").unwrap().group.as_deref(), Some("prod"));
+
+        let trashed = vault.delete("db", false).unwrap();
+        assert!(trashed.is_some());
+        assert!(vault.list().unwrap().is_empty());
+        assert!(!credstore::cred_path(&vault.paths().credstore, "db").exists());
+
+        let err = vault.get("db").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<VaultError>(),
+            Some(VaultError::CredentialNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_bad_names_and_secrets_before_systemd() {
+        let dir = TempDir::new().unwrap();
+        let policy = PolicySection {
+            max_secret_size: Some(4),
+            ..Default::default()
+        };
+        let vault = Vault::new(VaultPaths::from_root(dir.path().to_path_buf()), policy);
+        let opts = WriteOptions {
+            with_key: Some("host".into()),
+            ..Default::default()
+        };
+        assert!(vault.get("../etc/passwd").is_err());
+        assert!(vault.delete("a/b", false).is_err());
+        assert!(vault.create("..", b"s", &opts).is_err());
+        assert!(vault.rotate("a/b", b"s", &opts).is_err());
+        assert!(vault.create("db", b"", &opts).is_err());
+        let err = vault.rotate("db", b"12345", &opts).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<VaultError>(),
+            Some(VaultError::PolicyViolation(_))
+        ));
+        assert!(!dir.path().join("credstore").exists());
+    }
+
+    #[test]
+    fn test_create_many_reports_each_record() {
+        let dir = TempDir::new().unwrap();
+        let policy = PolicySection {
+            service_allowlist: vec!["api".into()],
+            ..Default::default()
+        };
+        let vault = Vault::new(VaultPaths::from_root(dir.path().to_path_buf()), policy);
+        let opts = WriteOptions {
+            with_key: Some("host".into()),
+            services: vec!["mail".into()],
+            ..Default::default()
+        };
+        let records: [(&str, &[u8]); 3] = [("..", b"s"), ("db", b""), ("db", b"s")];
+        let results = vault.create_many(&records, &opts).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(Result::is_err));
+        assert!(matches!(
+            results[2].as_ref().unwrap_err().downcast_ref::<VaultError>(),
+            Some(VaultError::PolicyViolation(_))
+        ));
+        // Nothing was created, so vault.toml is left alone.
+        assert!(!dir.path().join("vault.toml").exists());
+    }
+
+    #[test]
+    fn test_policy_refusal_is_typed() {
+        let dir = TempDir::new().unwrap();
+        let policy = PolicySection {
+            service_allowlist: vec!["api".into()],
+            ..Default::default()
+        };
+        let vault = Vault::new(VaultPaths::from_root(dir.path().to_path_buf()), policy);
+        let opts = WriteOptions {
+            with_key: Some("host".into()),
+            services: vec!["mail".into()],
+            ..Default::default()
+        };
+        let err = vault.check_create("db", &opts).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<VaultError>(),
+            Some(VaultError::PolicyViolation(_))
+        ));
+    }
+}
@@ -5,10 +5,15 @@
 //!
 //! ## Modules
 //! - `cli` — Command-line handlers
-//! - `core` — Business logic (audit, credstore, dropin, metadata)
+//! - `core` — Business logic (audit, credstore, dropin, metadata); typed
+//!   errors in `core::error` for library callers, and `core::vault::Vault`
+//!   as the entry point for using the crate as a dependency
 //! - `models` — Data structures
 //! - `util` — System utilities (fs, systemd)
 
+#[cfg(not(unix))]
+compile_error!("goamet-vault only supports unix targets (systemd-creds, nix, std::os::unix)");
+
 pub mod cli;
 pub mod constants;
 pub mod core;
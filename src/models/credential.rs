@@ -10,10 +10,23 @@ pub struct CredentialMeta {
     pub created_at: Option<DateTime<Utc>>,
     pub rotated_at: Option<DateTime<Utc>>,
     pub encryption_key: Option<String>,
+    /// PCRs the credential is bound to (`--tpm2-pcrs`); `None` means the
+    /// systemd-creds default or a host-only key.
+    #[serde(default)]
+    pub tpm2_pcrs: Option<String>,
+    /// SHA-256 of the `.cred` ciphertext as last written by the vault;
+    /// `health` compares it with the file to catch corruption.
+    #[serde(default)]
+    pub cred_sha256: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
     #[serde(default)]
     pub tags: Vec<String>,
     #[serde(default)]
     pub services: Vec<String>,
+    /// Organizational group (e.g. `payments`); does not affect the name.
+    #[serde(default)]
+    pub group: Option<String>,
 }
 
 impl std::fmt::Display for CredentialMeta {
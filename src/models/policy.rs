@@ -1,10 +1,16 @@
 //! Policy configuration for vault operations.
 
+use crate::constants;
+use crate::core::error::{VaultError, VaultResult};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// `[profiles.<name>]` tables: `[policy]` keys overridden per environment.
+pub type PolicyProfiles = BTreeMap<String, toml::Table>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicySection {
-    /// Allowed services (empty = no restriction).
+    /// Allowed services, exact or glob (`api-*`); empty = no restriction.
     #[serde(default)]
     pub service_allowlist: Vec<String>,
 
@@ -16,9 +22,95 @@ pub struct PolicySection {
     #[serde(default)]
     pub forbid_host_only_when_tpm2: bool,
 
+    /// Refuse to create credentials without TPM2: fail when TPM2 is not
+    /// detected instead of falling back to host-only encryption.
+    #[serde(default)]
+    pub require_tpm2: bool,
+
+    /// PCRs TPM2-backed credentials are bound to when `--tpm2-pcrs` is not
+    /// given (e.g. `7`); `health` warns about credentials bound differently.
+    #[serde(default)]
+    pub default_tpm2_pcrs: Option<String>,
+
     /// Forward audit entries to journald.
     #[serde(default)]
     pub journald_audit: bool,
+
+    /// Tags every credential must carry. An entry ending in `:` (e.g. `env:`)
+    /// matches any tag with that prefix.
+    #[serde(default)]
+    pub required_tags: Vec<String>,
+
+    /// Required credential name prefix per linked service
+    /// (e.g. `"api.service" = "svc-api."`).
+    #[serde(default)]
+    pub name_prefix_by_service: HashMap<String, String>,
+
+    /// Require `--reason` when `get` prints a secret to stdout.
+    #[serde(default = "default_true")]
+    pub require_reason_for_stdout: bool,
+
+    /// Require `--reason` for `rotate`.
+    #[serde(default)]
+    pub require_reason_for_rotate: bool,
+
+    /// Maximum successful `get`s of one credential within an hour.
+    #[serde(default)]
+    pub max_gets_per_hour: Option<u32>,
+
+    /// Charset every auto-generated secret must use (e.g. `alnum-symbols`).
+    /// A charset with symbols guarantees at least one symbol per secret.
+    #[serde(default)]
+    pub required_secret_charset: Option<String>,
+
+    /// Warn when a supplied secret's entropy estimate is below this many bits.
+    #[serde(default)]
+    pub min_secret_entropy_bits: Option<u32>,
+
+    /// Reject (instead of warn about) secrets below `min_secret_entropy_bits`.
+    #[serde(default)]
+    pub reject_weak_secrets: bool,
+
+    /// `health` warns about credentials not rotated within this many days.
+    #[serde(default)]
+    pub max_credential_age_days: Option<u32>,
+
+    /// Largest supplied secret accepted, in bytes (default
+    /// `constants::MAX_SECRET_SIZE`).
+    #[serde(default)]
+    pub max_secret_size: Option<usize>,
+
+    /// Smallest supplied secret accepted, in bytes.
+    #[serde(default)]
+    pub min_secret_size: Option<usize>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for PolicySection {
+    fn default() -> Self {
+        Self {
+            service_allowlist: Vec::new(),
+            min_auto_secret_length: None,
+            forbid_host_only_when_tpm2: false,
+            require_tpm2: false,
+            default_tpm2_pcrs: None,
+            journald_audit: false,
+            required_tags: Vec::new(),
+            name_prefix_by_service: HashMap::new(),
+            require_reason_for_stdout: true,
+            require_reason_for_rotate: false,
+            max_gets_per_hour: None,
+            required_secret_charset: None,
+            min_secret_entropy_bits: None,
+            reject_weak_secrets: false,
+            max_credential_age_days: None,
+            max_secret_size: None,
+            min_secret_size: None,
+        }
+    }
 }
 
 impl PolicySection {
@@ -26,13 +118,210 @@ impl PolicySection {
         service.strip_suffix(".service").unwrap_or(service)
     }
 
+    /// Whether `service` is on the allowlist. Entries may be globs
+    /// (`api-*`, `*-worker`, `app-*-db`); both sides are matched without a
+    /// `.service` suffix. An empty allowlist allows everything.
     pub fn is_service_allowed(&self, service: &str) -> bool {
         if self.service_allowlist.is_empty() {
             return true;
         }
         let svc = Self::normalize_service_name(service);
-        self.service_allowlist
+        self.service_allowlist.iter().any(|entry| {
+            let entry = Self::normalize_service_name(entry);
+            match glob::Pattern::new(entry) {
+                Ok(pattern) if entry.contains(['*', '?', '[']) => pattern.matches(svc),
+                _ => entry == svc,
+            }
+        })
+    }
+
+    /// Largest secret accepted: `max_secret_size`, else the built-in limit.
+    pub fn max_secret_size(&self) -> usize {
+        self.max_secret_size.unwrap_or(constants::MAX_SECRET_SIZE)
+    }
+
+    /// Check that `min_secret_size` and `max_secret_size` leave room for a
+    /// secret: max is not 0 and min does not exceed max.
+    pub fn validate_secret_size_limits(&self) -> Result<(), String> {
+        if self.max_secret_size == Some(0) {
+            return Err("max_secret_size = 0 would reject every secret".to_string());
+        }
+        if let Some(min) = self.min_secret_size {
+            if min > self.max_secret_size() {
+                return Err(format!(
+                    "min_secret_size = {} exceeds the maximum of {} bytes",
+                    min,
+                    self.max_secret_size()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fail unless a `len`-byte secret is within `min_secret_size` and
+    /// [`max_secret_size`](Self::max_secret_size).
+    pub fn check_secret_size(&self, len: usize) -> VaultResult<()> {
+        let max = self.max_secret_size();
+        if len > max {
+            return Err(VaultError::PolicyViolation(format!(
+                "policy: secret exceeds maximum size ({} bytes, max {} bytes)",
+                len, max
+            )));
+        }
+        if let Some(min) = self.min_secret_size {
+            if len < min {
+                return Err(VaultError::PolicyViolation(format!(
+                    "policy: secret is too short ({} bytes, min_secret_size = {})",
+                    len, min
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fail if `name` lacks the prefix configured for `service`.
+    pub fn check_name_prefix(&self, name: &str, service: &str) -> VaultResult<()> {
+        let svc = Self::normalize_service_name(service);
+        let prefix = self
+            .name_prefix_by_service
+            .iter()
+            .find(|(s, _)| Self::normalize_service_name(s) == svc)
+            .map(|(_, prefix)| prefix);
+        if let Some(prefix) = prefix {
+            if !name.starts_with(prefix.as_str()) {
+                return Err(VaultError::PolicyViolation(format!(
+                    "policy: credential '{}' linked to service '{}' must start with '{}' (name_prefix_by_service enforced)",
+                    name,
+                    svc,
+                    prefix
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fail unless `tags` satisfies every entry in `required_tags`.
+    pub fn check_required_tags(&self, tags: &[String]) -> VaultResult<()> {
+        let missing: Vec<&str> = self
+            .required_tags
             .iter()
-            .any(|s| Self::normalize_service_name(s) == svc)
+            .filter(|required| {
+                !tags.iter().any(|tag| {
+                    if required.ends_with(':') {
+                        tag.starts_with(required.as_str())
+                    } else {
+                        tag == *required
+                    }
+                })
+            })
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(VaultError::PolicyViolation(format!(
+                "policy: missing required tag(s): {} (required_tags enforced)",
+                missing.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_reason_defaults() {
+        let policy: PolicySection = toml::from_str("").unwrap();
+        assert!(policy.require_reason_for_stdout);
+        assert!(!policy.require_reason_for_rotate);
+        assert!(PolicySection::default().require_reason_for_stdout);
+    }
+
+    #[test]
+    fn test_service_allowlist_globs() {
+        let allow = |entries: &[&str]| PolicySection {
+            service_allowlist: tags(entries),
+            ..Default::default()
+        };
+        assert!(allow(&[]).is_service_allowed("anything"));
+
+        let policy = allow(&["api-*", "*-worker", "app-*-db", "web.service"]);
+        assert!(policy.is_service_allowed("api-public"));
+        assert!(policy.is_service_allowed("api-public.service"));
+        assert!(policy.is_service_allowed("mail-worker"));
+        assert!(policy.is_service_allowed("app-billing-db"));
+        assert!(policy.is_service_allowed("web"));
+        assert!(!policy.is_service_allowed("api"));
+        assert!(!policy.is_service_allowed("worker-mail"));
+        assert!(!policy.is_service_allowed("app-billing-cache"));
+        assert!(!policy.is_service_allowed("webapp"));
+    }
+
+    #[test]
+    fn test_name_prefix_by_service() {
+        let mut policy = PolicySection::default();
+        policy
+            .name_prefix_by_service
+            .insert("api.service".to_string(), "svc-api.".to_string());
+
+        assert!(policy.check_name_prefix("svc-api.db_pass", "api").is_ok());
+        assert!(policy.check_name_prefix("svc-api.db_pass", "api.service").is_ok());
+        assert!(policy.check_name_prefix("db_pass", "api.service").is_err());
+        assert!(policy.check_name_prefix("db_pass", "worker").is_ok());
+    }
+
+    #[test]
+    fn test_required_tags_satisfied() {
+        let policy = PolicySection {
+            required_tags: tags(&["env:", "owner:", "pci"]),
+            ..Default::default()
+        };
+        assert!(policy
+            .check_required_tags(&tags(&["env:prod", "owner:payments", "pci"]))
+            .is_ok());
+        assert!(PolicySection::default().check_required_tags(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_secret_size_bounds() {
+        let default = PolicySection::default();
+        assert!(default.check_secret_size(constants::MAX_SECRET_SIZE).is_ok());
+        assert!(default.check_secret_size(constants::MAX_SECRET_SIZE + 1).is_err());
+        assert!(default.check_secret_size(1).is_ok());
+
+        let policy: PolicySection =
+            toml::from_str("max_secret_size = 64\nmin_secret_size = 8").unwrap();
+        assert!(policy.check_secret_size(7).is_err());
+        assert!(policy.check_secret_size(8).is_ok());
+        assert!(policy.check_secret_size(64).is_ok());
+        let err = policy.check_secret_size(65).unwrap_err().to_string();
+        assert_eq!(err, "policy: secret exceeds maximum size (65 bytes, max 64 bytes)");
+
+        assert!(policy.validate_secret_size_limits().is_ok());
+        assert!(default.validate_secret_size_limits().is_ok());
+        let inverted: PolicySection =
+            toml::from_str("max_secret_size = 8\nmin_secret_size = 64").unwrap();
+        assert!(inverted.validate_secret_size_limits().is_err());
+        let zero: PolicySection = toml::from_str("max_secret_size = 0").unwrap();
+        assert!(zero.validate_secret_size_limits().is_err());
+    }
+
+    #[test]
+    fn test_required_tags_missing() {
+        let policy = PolicySection {
+            required_tags: tags(&["env:", "owner:", "pci"]),
+            ..Default::default()
+        };
+        let err = policy
+            .check_required_tags(&tags(&["env:prod", "pci-scope"]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("owner:, pci"), "{}", err);
+        assert!(!err.contains("env:"), "{}", err);
     }
 }
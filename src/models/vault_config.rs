@@ -1,8 +1,9 @@
 //! Vault configuration file model.
 
 use crate::models::credential::CredentialMeta;
-use crate::models::policy::PolicySection;
+use crate::models::policy::{PolicyProfiles, PolicySection};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct VaultFile {
@@ -10,10 +11,53 @@ pub struct VaultFile {
     pub vault: VaultSection,
     #[serde(default)]
     pub policy: PolicySection,
+    /// Named `[policy]` overrides selected with `--profile`.
+    #[serde(default, skip_serializing_if = "PolicyProfiles::is_empty")]
+    pub profiles: PolicyProfiles,
+    #[serde(default)]
+    pub audit: AuditSection,
+    #[serde(default, skip_serializing_if = "HooksSection::is_empty")]
+    pub hooks: HooksSection,
     #[serde(default)]
     pub credentials: Vec<CredentialMeta>,
 }
 
+/// `[audit]` settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditSection {
+    /// Ed25519 key used to sign each entry into `audit.log.sig`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key_path: Option<PathBuf>,
+    /// Remote collector for RFC 5424 copies of audit events
+    /// (`udp://host:514`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub syslog_target: Option<String>,
+}
+
+/// `[hooks]` command templates run after a successful change.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HooksSection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_create: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_rotate: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_delete: Option<String>,
+    /// Undo the change (and fail the command) when a hook fails, instead
+    /// of only warning.
+    #[serde(default)]
+    pub fail_on_hook_error: bool,
+}
+
+impl HooksSection {
+    pub fn is_empty(&self) -> bool {
+        self.post_create.is_none()
+            && self.post_rotate.is_none()
+            && self.post_delete.is_none()
+            && !self.fail_on_hook_error
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VaultSection {
     #[serde(default = "default_version")]
@@ -0,0 +1,23 @@
+//! Constant-time comparison for hashes and other values read from
+//! untrusted input during verification.
+
+use subtle::ConstantTimeEq;
+
+/// `a == b` without an early exit on the first differing byte. Only the
+/// lengths leak, which for hashes and signatures are public anyway.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && bool::from(a.ct_eq(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(b"abc123", b"abc123"));
+        assert!(ct_eq(b"", b""));
+        assert!(!ct_eq(b"abc123", b"abc124"));
+        assert!(!ct_eq(b"abc", b"abc123"));
+    }
+}
@@ -0,0 +1,55 @@
+//! Rough strength estimate for user-supplied secrets.
+//!
+//! Shannon entropy over the byte distribution, multiplied by the length.
+//! This only catches obviously weak input (repeated characters, very short
+//! values); it says nothing about dictionary words.
+
+/// Shannon entropy in bits per byte of `data` (0.0 for empty input).
+pub fn bits_per_byte(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for &b in data {
+        counts[b as usize] += 1;
+    }
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Estimated total entropy of `data` in bits.
+pub fn estimate_bits(data: &[u8]) -> f64 {
+    bits_per_byte(data) * data.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_estimate_bits_known_strings() {
+        assert!(approx(estimate_bits(b""), 0.0));
+        assert!(approx(estimate_bits(b"aaaaaaaa"), 0.0));
+        assert!(approx(estimate_bits(b"ab"), 2.0));
+        assert!(approx(estimate_bits(b"abcd"), 8.0));
+        assert!(approx(bits_per_byte(b"aabb"), 1.0));
+        assert!(approx(estimate_bits(b"0123456789abcdef"), 64.0));
+    }
+
+    #[test]
+    fn test_estimate_bits_orders_weak_below_strong() {
+        assert!(estimate_bits(b"password") < estimate_bits(b"xK9#mQ2$vL7@pR4!"));
+        assert!(estimate_bits(b"hunter2") < 28.0);
+    }
+}
@@ -1,8 +1,12 @@
 //! Filesystem helpers with permission management.
 
 use anyhow::{Context, Result};
-use std::fs;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
 use std::path::Path;
+use crate::constants;
+use crate::util::scratch;
+use tempfile::NamedTempFile;
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
@@ -26,3 +30,104 @@ pub fn set_permissions(path: &Path, mode: u32) -> Result<()> {
     }
     Ok(())
 }
+
+/// Create a named temp file `<dir>/<prefix>XXXXXX<suffix>`, opened with
+/// [`constants::TEMP_FILE_MODE`] so it is never group/other-readable, not
+/// even before the caller sets its final mode. Every temp file the vault
+/// writes goes through here.
+pub fn secure_tempfile(dir: &Path, prefix: &str, suffix: &str) -> io::Result<NamedTempFile> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(prefix).suffix(suffix);
+    #[cfg(unix)]
+    builder.permissions(fs::Permissions::from_mode(constants::TEMP_FILE_MODE));
+    builder.tempfile_in(dir)
+}
+
+/// Overwrite a file's contents with zeros and sync it to disk.
+pub fn scrub_file(path: &Path) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .with_context(|| format!("open {} for scrubbing", path.display()))?;
+    let len = file
+        .metadata()
+        .with_context(|| format!("stat {}", path.display()))?
+        .len();
+    let zeros = [0u8; 4096];
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(zeros.len() as u64) as usize;
+        file.write_all(&zeros[..n])
+            .with_context(|| format!("scrub {}", path.display()))?;
+        remaining -= n as u64;
+    }
+    file.sync_all()
+        .with_context(|| format!("sync {}", path.display()))?;
+    Ok(())
+}
+
+/// Temporary file that may hold plaintext. Its contents are overwritten with
+/// zeros before it is removed on drop.
+pub struct ScrubbedTempFile {
+    inner: NamedTempFile,
+}
+
+impl ScrubbedTempFile {
+    /// Create a temp file in the scratch dir (see [`scratch::resolve`]),
+    /// falling back to the default temp dir.
+    pub fn new() -> Result<Self> {
+        let dir = scratch::resolve(&std::env::temp_dir());
+        Self::new_in(&dir.path, ".vault-")
+    }
+
+    /// Create a temp file in `dir` with the given name prefix.
+    pub fn new_in(dir: &Path, prefix: &str) -> Result<Self> {
+        let inner = secure_tempfile(dir, prefix, "")
+            .with_context(|| format!("create temp file in {}", dir.display()))?;
+        Ok(Self { inner })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.inner.path()
+    }
+
+    pub fn as_file_mut(&mut self) -> &mut File {
+        self.inner.as_file_mut()
+    }
+}
+
+impl Drop for ScrubbedTempFile {
+    fn drop(&mut self) {
+        // Scrub by path: the writer may have replaced the file we created.
+        if let Err(e) = scrub_file(self.inner.path()) {
+            eprintln!("warning: could not scrub temp file: {:#}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrub_file_zeroes_contents() {
+        let mut tmp = ScrubbedTempFile::new().unwrap();
+        tmp.as_file_mut().write_all(b"s3cr3t-value").unwrap();
+        scrub_file(tmp.path()).unwrap();
+        assert_eq!(fs::read(tmp.path()).unwrap(), vec![0u8; 12]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_temp_files_created_private() {
+        let dir = tempfile::tempdir().unwrap();
+        let tmp = ScrubbedTempFile::new_in(dir.path(), ".secret-").unwrap();
+        let mode = fs::metadata(tmp.path()).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, constants::TEMP_FILE_MODE);
+
+        let tmp = secure_tempfile(dir.path(), "cred-", ".cred.tmp").unwrap();
+        assert!(tmp.path().to_str().unwrap().ends_with(".cred.tmp"));
+        let mode = tmp.as_file().metadata().unwrap().permissions().mode();
+        assert_eq!(mode & 0o077, 0, "temp file created with mode {:o}", mode & 0o777);
+    }
+}
@@ -1,10 +1,27 @@
-//! Optional journald forwarding via systemd-cat.
+//! Optional journald forwarding.
+//!
+//! Entries go to the native journal socket as structured fields, so operators
+//! can filter with e.g. `journalctl GOAMET_ACTION=rotate`. When the socket is
+//! not there (containers, non-systemd hosts) the message falls back to
+//! `systemd-cat`.
 //!
 //! Best-effort: failure to forward must not break vault operations.
 
 use std::io::Write;
+use std::os::unix::net::UnixDatagram;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
+/// Datagram socket of the native journal protocol.
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// syslog priority: error.
+pub const PRIORITY_ERR: u8 = 3;
+/// syslog priority: informational.
+pub const PRIORITY_INFO: u8 = 6;
+/// syslog priority: warning.
+pub const PRIORITY_WARNING: u8 = 4;
+
 pub fn systemd_cat_available() -> bool {
     Command::new("systemd-cat")
         .arg("--version")
@@ -15,6 +32,46 @@ pub fn systemd_cat_available() -> bool {
         .unwrap_or(false)
 }
 
+/// Forward one entry to journald: `message` plus extra `fields` (names must
+/// be uppercase `[A-Z0-9_]`). Only `message` survives the `systemd-cat`
+/// fallback.
+pub fn forward(tag: &str, message: &str, priority: u8, fields: &[(&str, &str)]) {
+    let priority = priority.to_string();
+    let mut all = vec![
+        ("SYSLOG_IDENTIFIER", tag),
+        ("MESSAGE", message),
+        ("PRIORITY", priority.as_str()),
+    ];
+    all.extend_from_slice(fields);
+    if send_native(Path::new(JOURNAL_SOCKET), &encode_fields(&all)).is_err() {
+        forward_line(tag, message);
+    }
+}
+
+/// Encode fields in the native protocol: `KEY=value\n`, or for values
+/// containing a newline `KEY\n<u64 LE length><value>\n`.
+fn encode_fields(fields: &[(&str, &str)]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for (key, value) in fields {
+        buf.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            buf.push(b'\n');
+            buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            buf.push(b'=');
+        }
+        buf.extend_from_slice(value.as_bytes());
+        buf.push(b'\n');
+    }
+    buf
+}
+
+fn send_native(socket: &Path, payload: &[u8]) -> std::io::Result<()> {
+    let sock = UnixDatagram::unbound()?;
+    sock.send_to(payload, socket)?;
+    Ok(())
+}
+
 /// Forward a single log line to journald using `systemd-cat`.
 pub fn forward_line(tag: &str, line: &str) {
     if !systemd_cat_available() {
@@ -41,3 +98,25 @@ pub fn forward_line(tag: &str, line: &str) {
     let _ = child.wait();
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_fields() {
+        let buf = encode_fields(&[("MESSAGE", "rotate db"), ("GOAMET_ACTION", "rotate")]);
+        assert_eq!(buf, b"MESSAGE=rotate db\nGOAMET_ACTION=rotate\n");
+
+        let buf = encode_fields(&[("MESSAGE", "a\nb")]);
+        let mut expected = b"MESSAGE\n".to_vec();
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(b"a\nb\n");
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_send_native_without_socket_fails() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(send_native(&dir.path().join("socket"), b"MESSAGE=x\n").is_err());
+    }
+}
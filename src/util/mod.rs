@@ -1,7 +1,15 @@
 //! Utility modules for filesystem and systemd operations.
 
+pub mod ct;
+pub mod entropy;
 pub mod fs;
 pub mod journald;
+pub mod pager;
 pub mod path;
 pub mod privilege;
+pub mod scratch;
+pub mod syslog;
 pub mod systemd;
+pub mod table;
+
+pub use ct::ct_eq;
@@ -0,0 +1,87 @@
+//! Page long output through `$PAGER` when stdout is a terminal.
+//!
+//! Output that fits on the screen, or that goes to a pipe or file, is
+//! printed directly. `PAGER` defaults to `less -R`; an empty `PAGER` or
+//! `cat` disables paging, as in git. Running as root or under sudo never
+//! pages: pagers can run shell commands (`!` in less), and `PAGER` may come
+//! from the invoking user's environment.
+
+use crate::util::privilege;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Print `text`, through the pager if `enabled`, the process is not
+/// privileged, stdout is a terminal and `text` is taller than it. Falls back
+/// to printing directly when the pager cannot be started.
+pub fn print(text: &str, enabled: bool) {
+    if enabled && !privileged() && std::io::stdout().is_terminal() {
+        let rows = console::Term::stdout().size_checked().map(|(rows, _)| usize::from(rows));
+        let pager = pager_command(std::env::var("PAGER").ok().as_deref());
+        if let (Some(rows), Some(argv)) = (rows, pager) {
+            if exceeds(text, rows) && spawn(&argv, text).is_ok() {
+                return;
+            }
+        }
+    }
+    print!("{}", text);
+}
+
+/// Whether we run as root (euid 0) or were started through sudo.
+fn privileged() -> bool {
+    privilege::is_root() || std::env::var_os("SUDO_USER").is_some()
+}
+
+/// Whether `text` needs more than `rows` terminal lines.
+fn exceeds(text: &str, rows: usize) -> bool {
+    text.lines().count() > rows
+}
+
+/// The pager argv for a `PAGER` value, or `None` if paging is disabled.
+fn pager_command(pager: Option<&str>) -> Option<Vec<String>> {
+    let pager = pager.unwrap_or(DEFAULT_PAGER).trim();
+    if pager.is_empty() || pager == "cat" {
+        return None;
+    }
+    Some(pager.split_whitespace().map(str::to_string).collect())
+}
+
+fn spawn(argv: &[String], text: &str) -> std::io::Result<()> {
+    let (program, args) = argv.split_first().ok_or(std::io::ErrorKind::InvalidInput)?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closes its stdin when the user quits early; not an error.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(pager_command(None), Some(vec!["less".to_string(), "-R".to_string()]));
+        assert_eq!(pager_command(Some("more")), Some(vec!["more".to_string()]));
+        assert_eq!(pager_command(Some("")), None);
+        assert_eq!(pager_command(Some("cat")), None);
+    }
+
+    #[test]
+    fn test_exceeds() {
+        assert!(!exceeds("a\nb\n", 2));
+        assert!(exceeds("a\nb\nc\n", 2));
+    }
+
+    #[test]
+    fn test_spawn_missing_pager_fails() {
+        let argv = vec!["goamet-vault-no-such-pager".to_string()];
+        assert!(spawn(&argv, "text\n").is_err());
+    }
+}
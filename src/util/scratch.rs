@@ -0,0 +1,80 @@
+//! Scratch space for short-lived plaintext files.
+//!
+//! Prefers the per-user runtime directory, which is tmpfs-backed on systemd
+//! hosts, so plaintext never reaches persistent disk.
+
+use std::env;
+use std::fs;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+/// Directory chosen for plaintext scratch files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScratchDir {
+    pub path: PathBuf,
+    /// True when a private runtime directory was found; false for the fallback.
+    pub runtime: bool,
+}
+
+/// Pick `$XDG_RUNTIME_DIR`, then `/run/user/<uid>`, when it is a directory
+/// owned by the effective user with mode 0700; otherwise `fallback`.
+pub fn resolve(fallback: &Path) -> ScratchDir {
+    let uid = nix::unistd::geteuid().as_raw();
+    let mut candidates = Vec::new();
+    if let Some(dir) = env::var_os("XDG_RUNTIME_DIR").filter(|d| !d.is_empty()) {
+        candidates.push(PathBuf::from(dir));
+    }
+    candidates.push(PathBuf::from(format!("/run/user/{}", uid)));
+    resolve_from(&candidates, uid, fallback)
+}
+
+fn resolve_from(candidates: &[PathBuf], uid: u32, fallback: &Path) -> ScratchDir {
+    match candidates.iter().find(|dir| is_private_dir(dir, uid)) {
+        Some(dir) => ScratchDir {
+            path: dir.clone(),
+            runtime: true,
+        },
+        None => ScratchDir {
+            path: fallback.to_path_buf(),
+            runtime: false,
+        },
+    }
+}
+
+fn is_private_dir(path: &Path, uid: u32) -> bool {
+    match fs::metadata(path) {
+        Ok(meta) => {
+            meta.is_dir() && meta.uid() == uid && meta.permissions().mode() & 0o777 == 0o700
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_prefers_private_runtime_dir() {
+        let uid = nix::unistd::geteuid().as_raw();
+        let open = TempDir::new().unwrap();
+        fs::set_permissions(open.path(), fs::Permissions::from_mode(0o755)).unwrap();
+        let private = TempDir::new().unwrap();
+        fs::set_permissions(private.path(), fs::Permissions::from_mode(0o700)).unwrap();
+        let fallback = Path::new("/var/lib/goamet-vault/credstore");
+
+        let candidates = vec![open.path().to_path_buf(), private.path().to_path_buf()];
+        let chosen = resolve_from(&candidates, uid, fallback);
+        assert!(chosen.runtime);
+        assert_eq!(chosen.path, private.path());
+
+        let chosen = resolve_from(&candidates[..1], uid, fallback);
+        assert!(!chosen.runtime);
+        assert_eq!(chosen.path, fallback);
+
+        // Owned by someone else: not private to us.
+        let chosen = resolve_from(&candidates[1..], uid + 1, fallback);
+        assert!(!chosen.runtime);
+    }
+}
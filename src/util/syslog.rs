@@ -0,0 +1,158 @@
+//! Optional forwarding to a remote syslog collector.
+//!
+//! Messages are formatted per RFC 5424 and sent as one UDP datagram each
+//! (RFC 5426). Like journald forwarding this is best-effort: UDP gives no
+//! delivery guarantee, and the local `audit.log` stays authoritative.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fs;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// syslog facility for security/authorization messages (`authpriv`).
+pub const FACILITY_AUTHPRIV: u8 = 10;
+
+const DEFAULT_PORT: u16 = 514;
+const APP_NAME: &str = "goamet-vault";
+
+/// A resolved `udp://host[:port]` target with a bound socket, set up once
+/// and reused for every message of a run.
+#[derive(Debug)]
+pub struct SyslogSender {
+    target: String,
+    addr: SocketAddr,
+    socket: UdpSocket,
+    hostname: String,
+}
+
+impl SyslogSender {
+    /// Resolve `target` and bind a local UDP socket for it.
+    pub fn new(target: &str) -> Result<Self> {
+        let addr = resolve_target(target)?;
+        let bind = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(bind).context("bind UDP socket")?;
+        Ok(Self {
+            target: target.to_string(),
+            addr,
+            socket,
+            hostname: hostname(),
+        })
+    }
+
+    /// The target as configured.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Send `msg` with the given facility and severity (syslog numbering,
+    /// as in `journald::PRIORITY_*`).
+    pub fn send(&self, facility: u8, severity: u8, msg: &str) -> Result<()> {
+        let line =
+            format_message(facility, severity, Utc::now(), &self.hostname, std::process::id(), msg);
+        self.socket
+            .send_to(line.as_bytes(), self.addr)
+            .with_context(|| format!("send to {}", self.addr))?;
+        Ok(())
+    }
+}
+
+/// `host:port` of a `udp://` target, with the default port added if missing.
+fn parse_target(target: &str) -> Result<String> {
+    let Some(rest) = target.strip_prefix("udp://") else {
+        bail!("unsupported syslog_target '{}' (use udp://host:port)", target);
+    };
+    let rest = rest.trim_end_matches('/');
+    if rest.is_empty() {
+        bail!("syslog_target '{}' has no host", target);
+    }
+    // The port follows the last ':' outside an IPv6 `[...]` literal.
+    let has_port = rest
+        .rsplit_once(':')
+        .is_some_and(|(host, _)| !host.is_empty() && (!host.contains(':') || host.ends_with(']')));
+    Ok(if has_port {
+        rest.to_string()
+    } else {
+        format!("{}:{}", rest, DEFAULT_PORT)
+    })
+}
+
+fn resolve_target(target: &str) -> Result<SocketAddr> {
+    let host_port = parse_target(target)?;
+    host_port
+        .to_socket_addrs()
+        .with_context(|| format!("resolve syslog_target {}", host_port))?
+        .next()
+        .with_context(|| format!("syslog_target {} did not resolve", host_port))
+}
+
+/// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA MSG`.
+fn format_message(
+    facility: u8,
+    severity: u8,
+    at: DateTime<Utc>,
+    hostname: &str,
+    pid: u32,
+    msg: &str,
+) -> String {
+    let pri = u16::from(facility) * 8 + u16::from(severity.min(7));
+    format!(
+        "<{}>1 {} {} {} {} audit - {}",
+        pri,
+        at.to_rfc3339_opts(SecondsFormat::Millis, true),
+        hostname,
+        APP_NAME,
+        pid,
+        msg.replace('\n', " ")
+    )
+}
+
+/// The host name, or the RFC 5424 nil value `-` if unknown.
+fn hostname() -> String {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty() && !h.contains(' '))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(parse_target("udp://logs.example:1514").unwrap(), "logs.example:1514");
+        assert_eq!(parse_target("udp://10.0.0.5").unwrap(), "10.0.0.5:514");
+        assert_eq!(parse_target("udp://[::1]").unwrap(), "[::1]:514");
+        assert_eq!(parse_target("udp://[::1]:600").unwrap(), "[::1]:600");
+        assert!(parse_target("tcp://logs.example:514").is_err());
+        assert!(parse_target("udp://").is_err());
+    }
+
+    #[test]
+    fn test_format_message() {
+        let at = DateTime::parse_from_rfc3339("2025-03-04T05:06:07.089Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let line = format_message(FACILITY_AUTHPRIV, 3, at, "host1", 42, "rotate 'db' failed\n(x)");
+        assert_eq!(
+            line,
+            "<83>1 2025-03-04T05:06:07.089Z host1 goamet-vault 42 audit - rotate 'db' failed (x)"
+        );
+    }
+
+    #[test]
+    fn test_forward_sends_datagram() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let target = format!("udp://{}", server.local_addr().unwrap());
+        let sender = SyslogSender::new(&target).unwrap();
+        let mut buf = [0u8; 512];
+        for msg in ["get 'db' by root: ok", "rotate 'db' by root: ok"] {
+            sender.send(FACILITY_AUTHPRIV, 6, msg).unwrap();
+            let n = server.recv(&mut buf).unwrap();
+            let line = String::from_utf8_lossy(&buf[..n]);
+            assert!(line.starts_with("<86>1 "));
+            assert!(line.ends_with(&format!(" audit - {}", msg)));
+        }
+    }
+}
@@ -1,10 +1,41 @@
 //! Wrappers around systemd-creds commands.
 
+use crate::constants;
+use crate::core::error::{VaultError, VaultResult};
 use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use std::io::Read;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use zeroize::Zeroizing;
 
+/// Limit for each command run here, in seconds (`--command-timeout`).
+static COMMAND_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(constants::DEFAULT_COMMAND_TIMEOUT_SECS);
+
+/// Print each command line to stderr before running it (`--verbose`).
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// How often a running command is checked for exit.
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Set the limit after which a hanging command (e.g. a wedged TPM) is killed.
+pub fn set_command_timeout(timeout: Duration) {
+    COMMAND_TIMEOUT_SECS.store(timeout.as_secs().max(1), Ordering::Relaxed);
+}
+
+/// Echo every command run here to stderr. The argv holds only key types,
+/// names and file paths (for a secret, its temp file path), never a secret.
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+fn command_timeout() -> Duration {
+    Duration::from_secs(COMMAND_TIMEOUT_SECS.load(Ordering::Relaxed))
+}
+
 /// Encrypt a secret using systemd-creds.
 pub fn encrypt(
     with_key: &str,
@@ -21,45 +52,203 @@ pub fn encrypt(
         cmd.arg(format!("--tpm2-pcrs={}", pcrs));
     }
     cmd.arg(input).arg(output);
-    run(cmd).context("systemd-creds encrypt")
+    run(cmd, "systemd-creds encrypt")
+}
+
+/// Highest PCR index a TPM2 exposes.
+const MAX_PCR_INDEX: u32 = 23;
+
+/// PCR names systemd accepts in place of an index.
+const PCR_NAMES: &[(&str, u32)] = &[
+    ("platform-code", 0),
+    ("platform-config", 1),
+    ("external-code", 2),
+    ("external-config", 3),
+    ("boot-loader-code", 4),
+    ("boot-loader-config", 5),
+    ("host-platform", 6),
+    ("secure-boot-policy", 7),
+    ("kernel-initrd", 9),
+    ("ima", 10),
+    ("kernel-boot", 11),
+    ("kernel-config", 12),
+    ("sysexts", 13),
+    ("shim-policy", 14),
+    ("system-identity", 15),
+    ("debug", 16),
+    ("application-support", 23),
+];
+
+/// PCR banks and the length of their digests in hex.
+const PCR_BANKS: &[(&str, usize)] = &[
+    ("sha1", 40),
+    ("sha256", 64),
+    ("sha384", 96),
+    ("sha512", 128),
+];
+
+/// One entry of a `--tpm2-pcrs` spec.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PcrEntry {
+    pub index: u32,
+    /// `<bank>` or `<bank>=<hex digest>` after a `:`, lowercased.
+    pub value: Option<String>,
+}
+
+/// Validate a `--tpm2-pcrs` spec as systemd reads it: entries separated by
+/// `+` or `,`, each a PCR index 0-23 or name (`secure-boot-policy`),
+/// optionally followed by `:<bank>` or `:<bank>=<hex>` (`7:sha256=3b3f...`).
+/// Returns the entries in the given order.
+pub fn parse_tpm2_pcrs(spec: &str) -> Result<Vec<PcrEntry>, String> {
+    if spec.trim().is_empty() {
+        return Err("empty PCR list (e.g. 7 or 7+11)".to_string());
+    }
+    let mut pcrs: Vec<PcrEntry> = Vec::new();
+    for part in spec.split(['+', ',']) {
+        if part.is_empty() {
+            return Err(format!(
+                "malformed PCR list '{}': empty entry (separate indices with a single + or ,)",
+                spec
+            ));
+        }
+        let (pcr, value) = match part.split_once(':') {
+            Some((pcr, value)) => (pcr, Some(parse_pcr_value(value, part)?)),
+            None => (part, None),
+        };
+        let index = match PCR_NAMES.iter().find(|(name, _)| *name == pcr) {
+            Some((_, index)) => *index,
+            None => pcr.parse().map_err(|_| {
+                format!(
+                    "invalid PCR '{}' in '{}' (expected an index 0-{} or a PCR name)",
+                    pcr, spec, MAX_PCR_INDEX
+                )
+            })?,
+        };
+        if index > MAX_PCR_INDEX {
+            return Err(format!("PCR {} out of range (0-{})", index, MAX_PCR_INDEX));
+        }
+        if pcrs.iter().any(|p| p.index == index) {
+            return Err(format!("PCR {} listed twice in '{}'", index, spec));
+        }
+        pcrs.push(PcrEntry { index, value });
+    }
+    Ok(pcrs)
+}
+
+/// Check the `<bank>[=<hex>]` part of a PCR entry.
+fn parse_pcr_value(value: &str, part: &str) -> Result<String, String> {
+    let value = value.to_ascii_lowercase();
+    let (bank, digest) = match value.split_once('=') {
+        Some((bank, digest)) => (bank, Some(digest)),
+        None => (value.as_str(), None),
+    };
+    let Some((_, hex_len)) = PCR_BANKS.iter().find(|(name, _)| *name == bank) else {
+        let banks: Vec<&str> = PCR_BANKS.iter().map(|(name, _)| *name).collect();
+        return Err(format!(
+            "unknown PCR bank '{}' in '{}' (use {})",
+            bank,
+            part,
+            banks.join(", ")
+        ));
+    };
+    if let Some(digest) = digest {
+        if digest.len() != *hex_len || !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(format!(
+                "invalid {} digest in '{}' (expected {} hex digits)",
+                bank, part, hex_len
+            ));
+        }
+    }
+    Ok(value)
+}
+
+/// Whether two PCR specs bind the same PCRs (`7+11` == `11,7`,
+/// `secure-boot-policy` == `7`). `None` is the systemd-creds default and
+/// only matches `None`.
+pub fn same_tpm2_pcrs(a: Option<&str>, b: Option<&str>) -> bool {
+    let set = |spec: &str| {
+        parse_tpm2_pcrs(spec).ok().map(|mut pcrs| {
+            pcrs.sort_unstable();
+            pcrs
+        })
+    };
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => match (set(a), set(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => a == b,
+        },
+        _ => false,
+    }
 }
 
 /// Decrypt a credential to a file.
-pub fn decrypt_to_file(input: &Path, output: &Path) -> Result<()> {
+///
+/// `cred_name` is the name the credential was encrypted under; `None` uses
+/// the file stem, as for every credential the vault creates. The same
+/// applies to the other decrypt helpers.
+pub fn decrypt_to_file(input: &Path, cred_name: Option<&str>, output: &Path) -> Result<()> {
     let mut cmd = Command::new("systemd-creds");
     cmd.arg("decrypt");
-    if let Some(name) = cred_name_from_path(input) {
+    if let Some(name) = cred_name.map(str::to_string).or_else(|| cred_name_from_path(input)) {
         cmd.arg(format!("--name={}", name));
     }
     cmd.arg(input).arg(output);
-    run(cmd).context("systemd-creds decrypt")
+    run(cmd, "systemd-creds decrypt")
+}
+
+/// Check that a credential decrypts, discarding the plaintext.
+///
+/// The output goes to stdout, which is wired to /dev/null, so no plaintext
+/// reaches a file or our memory.
+pub fn verify_decryptable(input: &Path, cred_name: Option<&str>) -> VaultResult<()> {
+    let mut cmd = Command::new("systemd-creds");
+    cmd.arg("decrypt");
+    if let Some(name) = cred_name.map(str::to_string).or_else(|| cred_name_from_path(input)) {
+        cmd.arg(format!("--name={}", name));
+    }
+    cmd.arg(input).arg("-");
+    let output = output(&mut cmd, "systemd-creds decrypt", false)?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(VaultError::DecryptFailed {
+        path: input.to_path_buf(),
+        detail: failure_detail(&output),
+    })
 }
 
 /// Decrypt a credential and return its contents (zeroized on drop).
-pub fn decrypt_to_stdout(input: &Path, newline: Option<&str>) -> Result<Zeroizing<Vec<u8>>> {
+pub fn decrypt_to_stdout(
+    input: &Path,
+    cred_name: Option<&str>,
+    newline: Option<&str>,
+) -> VaultResult<Zeroizing<Vec<u8>>> {
     let mut cmd = Command::new("systemd-creds");
     cmd.arg("decrypt");
-    if let Some(name) = cred_name_from_path(input) {
+    if let Some(name) = cred_name.map(str::to_string).or_else(|| cred_name_from_path(input)) {
         cmd.arg(format!("--name={}", name));
     }
     cmd.arg(input);
     if let Some(newline) = newline {
         cmd.arg(format!("--newline={}", newline));
     }
-    let output = cmd.output().context("run systemd-creds decrypt")?;
+    let output = output(&mut cmd, "systemd-creds decrypt", true)?;
     if output.status.success() {
         return Ok(Zeroizing::new(output.stdout));
     }
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    bail!("command failed: {}{}", stdout, stderr);
+    // On failure stdout carries no plaintext, only diagnostics.
+    Err(VaultError::DecryptFailed {
+        path: input.to_path_buf(),
+        detail: failure_detail(&output),
+    })
 }
 
 /// Run systemd-creds setup to ensure host key exists.
 pub fn setup() -> Result<()> {
     let mut cmd = Command::new("systemd-creds");
     cmd.arg("setup");
-    run(cmd).context("systemd-creds setup")
+    run(cmd, "systemd-creds setup")
 }
 
 fn cred_name_from_path(path: &Path) -> Option<String> {
@@ -68,12 +257,41 @@ fn cred_name_from_path(path: &Path) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Sealing schemes by the 16-byte id that starts every encrypted credential
+/// (`CRED_AES256_GCM_BY_*` in systemd), as `--with-key` names.
+const KEY_TYPE_IDS: &[(u128, &str)] = &[
+    (0x5a1c6a86_df9d4096_b1d5a65e_0862f19a, "host"),
+    (0x0c7cc07b_11764591_9c4b0bea_08bc20fe, "tpm2"),
+    (0xfaf7eb93_41e3412c_a1a436f9_5a29362f, "tpm2-with-public-key"),
+    (0x93a89409_48744490_90caf2fc_93cab553, "host+tpm2"),
+    (0xaf4950a8_49134eb1_a7384630_4ff30c05, "host+tpm2-with-public-key"),
+    (0x058469da_f6f54324_800549da_0f8ea2fb, "null"),
+];
+
+/// Key type a `.cred` file was sealed with, read from its header without
+/// decrypting. `None` if the file is not a recognizable credential.
+pub fn detect_key_type(input: &Path) -> Result<Option<&'static str>> {
+    let data = std::fs::read(input).with_context(|| format!("read {}", input.display()))?;
+    Ok(key_type_from_header(&data))
+}
+
+/// Credentials are Base64 text; the raw binary form is accepted too.
+fn key_type_from_header(data: &[u8]) -> Option<&'static str> {
+    let text: Vec<u8> = data.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+    let decoded = STANDARD.decode(&text).ok();
+    let raw = decoded.as_deref().unwrap_or(data);
+    let id = u128::from_be_bytes(raw.get(..16)?.try_into().ok()?);
+    KEY_TYPE_IDS
+        .iter()
+        .find(|(known, _)| *known == id)
+        .map(|(_, name)| *name)
+}
+
 /// Check whether TPM2 is available via systemd-creds.
 pub fn has_tpm2() -> Result<bool> {
-    let output = Command::new("systemd-creds")
-        .args(["has-tpm2", "--quiet"])
-        .output()
-        .context("run systemd-creds has-tpm2")?;
+    let mut cmd = Command::new("systemd-creds");
+    cmd.args(["has-tpm2", "--quiet"]);
+    let output = output(&mut cmd, "systemd-creds has-tpm2", true)?;
     Ok(output.status.success())
 }
 
@@ -125,20 +343,307 @@ impl Tpm2Status {
 
 /// Detailed TPM2 subsystem status.
 pub fn tpm2_status() -> Result<Tpm2Status> {
-    let output = Command::new("systemd-creds")
-        .arg("has-tpm2")
-        .output()
-        .context("run systemd-creds has-tpm2")?;
+    let mut cmd = Command::new("systemd-creds");
+    cmd.arg("has-tpm2");
+    let output = output(&mut cmd, "systemd-creds has-tpm2", true)?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     Ok(Tpm2Status::parse(&stdout, output.status.success()))
 }
 
-fn run(mut cmd: Command) -> Result<()> {
-    let output = cmd.output().context("run command")?;
+/// Check a timer `OnCalendar=` expression. It must be one line (it is
+/// written into a unit file); the syntax is checked by
+/// `systemd-analyze calendar`.
+pub fn validate_on_calendar(expr: &str) -> Result<()> {
+    if expr.trim().is_empty() {
+        bail!("empty OnCalendar expression");
+    }
+    if expr.chars().any(char::is_control) {
+        bail!("OnCalendar expression must be a single line");
+    }
+    let mut cmd = Command::new("systemd-analyze");
+    cmd.arg("calendar").arg(expr);
+    let output = output(&mut cmd, "systemd-analyze calendar", true)?;
+    if !output.status.success() {
+        bail!(
+            "invalid OnCalendar expression '{}': {}",
+            expr,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn run(mut cmd: Command, what: &str) -> Result<()> {
+    let output = output(&mut cmd, what, true)?;
     if output.status.success() {
         return Ok(());
     }
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    bail!("command failed: {}{}", stdout, stderr);
+    bail!("{} failed: {}", what, failure_detail(&output));
+}
+
+/// Common systemd-creds failures (matched lowercase against stderr) and what
+/// to do about them. The first match wins.
+const FAILURE_HINTS: &[(&str, &str)] = &[
+    (
+        "permission denied",
+        "the host key and the TPM are only accessible to root; run with sudo",
+    ),
+    (
+        "local credential key: no such file",
+        "the host key /var/lib/systemd/credential.secret is missing; `init --setup` creates one, but a new key cannot decrypt credentials sealed with the old one",
+    ),
+    (
+        "tpm2 support not installed",
+        "this systemd was built without TPM2 support; use --with-key=host",
+    ),
+    (
+        "no tpm2",
+        "no TPM2 device found (see `systemd-creds has-tpm2`); use --with-key=host",
+    ),
+    (
+        "does not match filename",
+        "the .cred was encrypted under another name; pass --cred-name <NAME>",
+    ),
+];
+
+fn failure_hint(stderr: &str) -> Option<&'static str> {
+    let stderr = stderr.to_lowercase();
+    FAILURE_HINTS
+        .iter()
+        .find(|(signature, _)| stderr.contains(signature))
+        .map(|(_, hint)| *hint)
+}
+
+/// `exit status N: <stderr>`, then any stdout and a hint for a known failure,
+/// all on one line.
+fn failure_detail(output: &Output) -> String {
+    let one_line = |bytes: &[u8]| {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join("; ")
+    };
+    let stderr = one_line(&output.stderr);
+    let stdout = one_line(&output.stdout);
+    let mut detail = match output.status.code() {
+        Some(code) => format!("exit status {}", code),
+        None => output.status.to_string(),
+    };
+    if !stderr.is_empty() {
+        detail.push_str(&format!(": {}", stderr));
+    }
+    if !stdout.is_empty() {
+        detail.push_str(&format!(" (stdout: {})", stdout));
+    }
+    if let Some(hint) = failure_hint(&stderr) {
+        detail.push_str(&format!(" (hint: {})", hint));
+    }
+    detail
+}
+
+/// [`output_within`] with the configured command timeout.
+fn output(cmd: &mut Command, what: &str, capture_stdout: bool) -> VaultResult<Output> {
+    if VERBOSE.load(Ordering::Relaxed) {
+        eprintln!("+ {}", command_line(cmd));
+    }
+    output_within(cmd, what, capture_stdout, command_timeout())
+}
+
+/// `cmd` as a shell-like line; arguments with spaces or quotes are quoted.
+fn command_line(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || "'\"\\".contains(c)) {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            } else {
+                arg.into_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Like `Command::output` (stdin closed, stderr captured), but the child is
+/// killed with [`VaultError::CommandTimeout`] once it runs longer than
+/// `timeout`. Without `capture_stdout` its stdout goes to /dev/null.
+fn output_within(
+    cmd: &mut Command,
+    what: &str,
+    capture_stdout: bool,
+    timeout: Duration,
+) -> VaultResult<Output> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(if capture_stdout { Stdio::piped() } else { Stdio::null() })
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| VaultError::io(format!("run {}", what), e))?;
+
+    // Drain both pipes while waiting so a chatty child cannot block on a full pipe.
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(VaultError::CommandTimeout {
+                    command: what.to_string(),
+                    timeout,
+                });
+            }
+            Ok(None) => std::thread::sleep(COMMAND_POLL_INTERVAL),
+            Err(e) => return Err(VaultError::io(format!("wait for {}", what), e)),
+        }
+    };
+    let collect = |reader: Option<JoinHandle<Vec<u8>>>| {
+        reader.and_then(|r| r.join().ok()).unwrap_or_default()
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+fn drain(mut pipe: impl Read + Send + 'static) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_type_from_header() {
+        // Start of a credential from `systemd-creds encrypt --with-key=host`.
+        let host = b"Whxqht+dQJax1aZeCGLxmiAAAAABAAAADAAAABAAAAD+6Z5HqD3vNxh+rYwA\n";
+        assert_eq!(key_type_from_header(host), Some("host"));
+        // `--with-key=auto-initrd` without a TPM falls back to the null key.
+        let null = b"BYRp2vb1QySABUnaD46i+yAAAAABAAAADAAAABAAAAArIqMMtvnOK7IYSekA\n";
+        assert_eq!(key_type_from_header(null), Some("null"));
+
+        let mut raw = KEY_TYPE_IDS[3].0.to_be_bytes().to_vec();
+        raw.extend_from_slice(&[0x20, 0, 0, 0]);
+        assert_eq!(key_type_from_header(&raw), Some("host+tpm2"));
+        assert_eq!(key_type_from_header(b"not a credential"), None);
+        assert_eq!(key_type_from_header(b""), None);
+    }
+
+    #[test]
+    fn test_output_within_kills_on_timeout() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo out; echo err >&2"]);
+        let output = output(&mut cmd, "sh", true).unwrap();
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+
+        let started = Instant::now();
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10");
+        let err = output_within(&mut cmd, "sleep", true, Duration::from_secs(1)).unwrap_err();
+        assert!(matches!(err, VaultError::CommandTimeout { .. }), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            err.to_string(),
+            "sleep did not finish within 1s and was killed (see --command-timeout)"
+        );
+    }
+
+    #[test]
+    fn test_command_line() {
+        let mut cmd = Command::new("systemd-creds");
+        cmd.args(["encrypt", "--with-key=host+tpm2", "--name=db"])
+            .arg("/run/vault scratch/.secret-Ab3x")
+            .arg("it's");
+        assert_eq!(
+            command_line(&cmd),
+            "systemd-creds encrypt --with-key=host+tpm2 --name=db '/run/vault scratch/.secret-Ab3x' 'it'\\''s'"
+        );
+    }
+
+    #[test]
+    fn test_failure_detail() {
+        use std::os::unix::process::ExitStatusExt;
+        let failed = |stderr: &str| Output {
+            status: std::process::ExitStatus::from_raw(1 << 8),
+            stdout: Vec::new(),
+            stderr: stderr.as_bytes().to_vec(),
+        };
+        let output = failed(
+            "Credential secret file '/var/lib/systemd/credential.secret' is not located on encrypted media, using anyway.\n\
+             Failed to determine local credential key: Permission denied\n",
+        );
+        let detail = failure_detail(&output);
+        assert!(detail.starts_with("exit status 1: Credential secret file"), "{}", detail);
+        assert!(detail.contains("using anyway.; Failed to determine"), "{}", detail);
+        assert!(
+            detail.ends_with("(hint: the host key and the TPM are only accessible to root; run with sudo)"),
+            "{}",
+            detail
+        );
+
+        let missing_key = "Failed to determine local credential key: No such file or directory";
+        assert!(failure_detail(&failed(missing_key)).contains("credential.secret is missing"));
+        let renamed = "Embedded credential name 'x' does not match filename 'y', refusing.";
+        assert!(failure_detail(&failed(renamed)).contains("pass --cred-name"));
+        assert_eq!(failure_detail(&failed("boom\n")), "exit status 1: boom");
+    }
+
+    #[test]
+    fn test_validate_on_calendar_rejects_multiline() {
+        assert!(validate_on_calendar("").is_err());
+        let err = validate_on_calendar("daily\nExecStart=/bin/sh").unwrap_err();
+        assert_eq!(err.to_string(), "OnCalendar expression must be a single line");
+    }
+
+    #[test]
+    fn test_parse_tpm2_pcrs() {
+        let indices = |spec| -> Vec<u32> {
+            parse_tpm2_pcrs(spec).unwrap().iter().map(|p| p.index).collect()
+        };
+        assert_eq!(indices("7"), vec![7]);
+        assert_eq!(indices("7+11"), vec![7, 11]);
+        assert_eq!(indices("0,2,7"), vec![0, 2, 7]);
+        assert_eq!(indices("23"), vec![23]);
+        assert_eq!(indices("secure-boot-policy+kernel-boot"), vec![7, 11]);
+        assert_eq!(indices("7:sha256+11"), vec![7, 11]);
+
+        let digest = "3B3F".repeat(16);
+        let pinned = parse_tpm2_pcrs(&format!("7:sha256={}", digest)).unwrap();
+        assert_eq!(pinned[0].value, Some(format!("sha256={}", digest.to_lowercase())));
+
+        assert!(parse_tpm2_pcrs("").is_err());
+        assert!(parse_tpm2_pcrs("7++11").is_err());
+        assert!(parse_tpm2_pcrs("7+").is_err());
+        assert!(parse_tpm2_pcrs("24").is_err());
+        assert!(parse_tpm2_pcrs("-1").is_err());
+        assert!(parse_tpm2_pcrs("7+7").is_err());
+        assert!(parse_tpm2_pcrs("seven").is_err());
+        assert!(parse_tpm2_pcrs("7+secure-boot-policy").is_err());
+        assert!(parse_tpm2_pcrs("7:md5").is_err());
+        assert!(parse_tpm2_pcrs("7:sha256=abcd").is_err());
+        assert!(parse_tpm2_pcrs(&format!("7:sha1={}", "zz".repeat(20))).is_err());
+    }
+
+    #[test]
+    fn test_same_tpm2_pcrs() {
+        assert!(same_tpm2_pcrs(None, None));
+        assert!(same_tpm2_pcrs(Some("7+11"), Some("11,7")));
+        assert!(same_tpm2_pcrs(Some("secure-boot-policy"), Some("7")));
+        assert!(!same_tpm2_pcrs(Some("7:sha256"), Some("7")));
+        assert!(!same_tpm2_pcrs(Some("7"), Some("7+11")));
+        assert!(!same_tpm2_pcrs(Some("7"), None));
+    }
 }
@@ -0,0 +1,34 @@
+//! Table construction that adapts to where output is going.
+
+use comfy_table::presets::{ASCII_FULL, UTF8_FULL};
+use comfy_table::{ContentArrangement, Table};
+use std::io::IsTerminal;
+
+/// Whether styled output is allowed: not disabled by `--no-color` or a
+/// non-empty `NO_COLOR` (https://no-color.org).
+pub fn color_enabled(no_color_flag: bool) -> bool {
+    !no_color_flag && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+/// Create a table for stdout. On a terminal it uses UTF-8 borders and wraps
+/// to the terminal width; when piped it is plain ASCII without styling.
+pub fn new_table(color: bool) -> Table {
+    let mut table = Table::new();
+    if !std::io::stdout().is_terminal() {
+        table.load_preset(ASCII_FULL).force_no_tty();
+        return table;
+    }
+
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+    if !color {
+        // force_no_tty also drops terminal width detection; pin it first.
+        let width = table.width();
+        table.force_no_tty();
+        if let Some(width) = width {
+            table.set_width(width);
+        }
+    }
+    table
+}